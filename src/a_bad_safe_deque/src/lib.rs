@@ -1,9 +1,11 @@
+use std::fmt::{self, Debug, Display, Formatter};
 use std::rc::Rc;
 use std::cell::{RefCell, Ref, RefMut};
 
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
+    len: usize,
 }
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
@@ -29,6 +31,7 @@ impl<T> List<T> {
         List {
             head: None,
             tail: None,
+            len: 0,
         }
     }
 
@@ -45,6 +48,7 @@ impl<T> List<T> {
                 self.head = Some(new_head);
             }
         }
+        self.len += 1;
     }
 
     pub fn pop_front(&mut self) -> Option<T>{
@@ -59,6 +63,7 @@ impl<T> List<T> {
                     self.head = Some(new_head);
                 }
             }
+            self.len -= 1;
             Rc::try_unwrap(old_head).ok().unwrap().into_inner().elem
         })
     }
@@ -76,6 +81,7 @@ impl<T> List<T> {
                 self.head = Some(new_tail);
             }
         }
+        self.len += 1;
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
@@ -90,35 +96,50 @@ impl<T> List<T> {
                     self.tail = Some(new_tail);
                 }
             }
+            self.len -= 1;
             Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem
         })
     }
 
-    pub fn peek_front(&self) -> Option<Ref<T>> {
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
         self.head.as_ref().map(|node| {
             Ref::map(node.borrow(), |t| &t.elem)
         })
     }
 
-    pub fn peek_back(&self) -> Option<Ref<T>> {
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
         self.tail.as_ref().map(|node| {
             Ref::map(node.borrow(), |t| &t.elem)
         })
     }
 
-    pub fn peek_front_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.head.as_ref().map(|node| {
             RefMut::map(node.borrow_mut(), |t| &mut t.elem)
         })
     }
 
-    pub fn peek_back_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.tail.as_ref().map(|node| {
             RefMut::map(node.borrow_mut(), |t| &mut t.elem)
         })
     }
 }
 
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
@@ -127,8 +148,11 @@ impl<T> Drop for List<T> {
 
 pub struct IntoIter<T>(List<T>);
 
-impl<T> List<T> {
-    pub fn into_iter(self) -> IntoIter<T>{
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
         IntoIter(self)
     }
 }
@@ -139,8 +163,14 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
 impl<T> DoubleEndedIterator for IntoIter<T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.0.pop_back()
@@ -148,6 +178,37 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 }
 
 
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.head.is_none() {
+            return write!(f, "Ø");
+        }
+        let mut cur = self.head.clone();
+        while let Some(node) = cur {
+            let node = node.borrow();
+            write!(f, "{}", node.elem)?;
+            if node.next.is_some() {
+                write!(f, " <-> ")?;
+            }
+            cur = node.next.clone();
+        }
+        Ok(())
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        let mut cur = self.head.clone();
+        while let Some(node) = cur {
+            let node = node.borrow();
+            list.entry(&node.elem);
+            cur = node.next.clone();
+        }
+        list.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
@@ -207,6 +268,31 @@ mod tests {
         assert_eq!(list.pop_back(), None);
     }
 
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        list.push_front(1);
+        list.push_back(2);
+        list.push_front(3);
+        assert_eq!(list.len(), 3);
+        list.pop_back();
+        assert_eq!(list.len(), 2);
+        list.pop_front();
+        list.pop_front();
+        list.pop_back();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        list.push_back(4);
+        list.push_back(5);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 2);
+        iter.next_back();
+        assert_eq!(iter.len(), 1);
+    }
+
     #[test]
     fn peek() {
         let mut list = List::new();
@@ -229,9 +315,9 @@ mod tests {
         list.push_back(1);
         list.push_back(2);
         list.push_back(3);
-        list.peek_back_mut().map(|mut node| {
+        if let Some(mut node) = list.peek_back_mut() {
             *node = 30;
-        });
+        }
         assert_eq!(list.pop_back(), Some(30));
     }
 
@@ -247,4 +333,17 @@ mod tests {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        list.push_back(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(list.to_string(), "1 <-> 2 <-> 3");
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:#?}", list), "[\n    1,\n    2,\n    3,\n]");
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter;
 use std::mem;
 
 pub struct List {
@@ -41,6 +43,12 @@ impl List {
     }
 }
 
+impl Default for List {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for List {
     fn drop(&mut self) {
         let mut cur_link = mem::replace(&mut self.head, Link::Empty);
@@ -52,6 +60,42 @@ impl Drop for List {
 
 
 
+impl List {
+    // 从栈顶开始依次返回每个元素，用于打印
+    fn elems(&self) -> impl Iterator<Item = &i32> {
+        let mut cur = &self.head;
+        iter::from_fn(move || match cur {
+            Link::Empty => None,
+            Link::More(node) => {
+                cur = &node.next;
+                Some(&node.elem)
+            }
+        })
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut elems = self.elems();
+        match elems.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in elems {
+                    write!(f, " -> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Debug for List {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.elems()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
@@ -77,4 +121,16 @@ mod tests {
         assert_eq!(list.pop(), Some(1));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.to_string(), "3 -> 2 -> 1");
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+    }
 }
@@ -1,3 +1,4 @@
+use std::fmt::{self, Debug, Display, Formatter};
 use std::rc::Rc;
 
 pub struct List<T> {
@@ -67,6 +68,12 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 }
 
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         let mut head = self.head.take();
@@ -81,6 +88,28 @@ impl<T> Drop for List<T> {
     }
 }
 
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        match iter.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in iter {
+                    write!(f, " -> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
@@ -115,4 +144,15 @@ mod tests {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    #[test]
+    fn display() {
+        let list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        let list = list.prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.to_string(), "3 -> 2 -> 1");
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+        assert_eq!(list.tail().to_string(), "2 -> 1");
+    }
 }
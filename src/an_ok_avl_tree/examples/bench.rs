@@ -9,4 +9,9 @@ fn main() {
     }
     let elapsed_time = now.elapsed();
     println!("AVL Tree insert 10000 times took {} ms.", elapsed_time.as_millis());
-}
\ No newline at end of file
+
+    let now = Instant::now();
+    let tree = AVLTree::from_sorted_iter((0..10000).map(|i| (i, i)));
+    let elapsed_time = now.elapsed();
+    println!("AVL Tree from_sorted_iter with {} keys took {} ms.", tree.len(), elapsed_time.as_millis());
+}
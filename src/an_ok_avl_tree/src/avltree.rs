@@ -1,22 +1,146 @@
-use crate::iterator::{RangePairIter, TraverseIter};
-use crate::node::{Node, Link};
+use crate::iterator::{Iter, IterMut, Order, RangePairIter, TraverseIter, ValuesMut};
+use crate::entry::{Entry, OccupiedEntry, VacantEntry};
+use crate::node::{Link, Node, Side};
+use ordered_map::{sort_unique_pairs, Compare, Difference, Intersection, InvariantViolation, Natural, OrderedMap, SymmetricDifference, Union};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::collections::{Bound, VecDeque};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, BitXor, RangeBounds, Sub};
 
-pub struct AVLTree<K, V> {
+/// AVL树，键的顺序由比较器C决定，默认按`Ord`比较
+pub struct AVLTree<K, V, C = Natural> {
     root: Link<K, V>,
+    len: usize,
+    marker: PhantomData<C>,
 }
 
-impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
-    /// 构建一棵空的AVL树
+impl<K: Ord, V> AVLTree<K, V> {
+    /// 构建一棵空的AVL树，按`Ord`比较键
     /// # Examples
     /// ```
     /// use an_ok_avl_tree::AVLTree;
     /// let mut tree: AVLTree<i32, i32> = AVLTree::new();
     /// ```
     pub fn new() -> Self {
-        Self { root: None }
+        Self::with_comparator()
     }
 
+    /// 由按键有序的键值对在O(n)时间内构建完全平衡的AVL树，避免逐个插入时反复旋转。
+    /// 输入无序时先按键排序，时间复杂度变为O(n log n)，重复的键保留最后一个值
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let tree = AVLTree::from_sorted_iter((0..100).map(|key| (key, key * 10)));
+    /// assert_eq!(tree.len(), 100);
+    /// assert_eq!(tree.get(&42), Some(&420));
+    /// assert!(tree.is_avl_tree());
+    /// let tree = AVLTree::from_sorted_iter(vec![(3, 'c'), (1, 'a'), (3, 'd')]);
+    /// let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
+    /// assert_eq!(res, vec![(&1, &'a'), (&3, &'d')]);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        Self::from_sorted_iter_with_comparator(pairs)
+    }
+}
+
+impl<K, V, C: Compare<K>> AVLTree<K, V, C> {
+    /// 构建一棵空的AVL树，按比较器C比较键
+    /// # Examples
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// use ordered_map::Reverse;
+    /// let mut tree: AVLTree<i32, char, Reverse> = AVLTree::with_comparator();
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// assert_eq!(tree.min_pair(), Some((&2, &'b')));
+    /// ```
+    pub fn with_comparator() -> Self {
+        Self::from_root(None, 0)
+    }
+
+    /// 与`from_sorted_iter`相同，按比较器C的顺序构建
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// use ordered_map::Reverse;
+    /// let tree: AVLTree<i32, char, Reverse> = AVLTree::from_sorted_iter_with_comparator(vec![(1, 'a'), (3, 'c')]);
+    /// assert_eq!(tree.min_pair(), Some((&3, &'c')));
+    /// ```
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        let pairs = sort_unique_pairs(pairs, C::compare);
+        let len = pairs.len();
+        Self::from_root(Node::build_balanced(&mut pairs.into_iter(), len), len)
+    }
+
+    // 用根节点和键值对个数构造一棵树
+    fn from_root(root: Link<K, V>, len: usize) -> Self {
+        let tree = AVLTree {
+            root,
+            len,
+            marker: PhantomData,
+        };
+        tree.check();
+        tree
+    }
+
+    /// 检查AVL树的所有性质：键按比较器C严格递增、每个节点记录的树高和子树大小正确、
+    /// 左右子树高度差不超过1，以及键值对个数正确。不满足时返回第一个被发现的问题
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// for key in 0..100 {
+    ///     tree.insert(key, key);
+    /// }
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let (_, size) = Node::validate::<C>(&self.root, &mut None)?;
+        if size != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                cached: self.len,
+                actual: size,
+            });
+        }
+        Ok(())
+    }
+
+    // 开启checked特性时，每次修改之后检查AVL树的性质，不满足时panic
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("AVL broken: {}", violation);
+        }
+    }
+}
+
+impl<K: Display, V: Display, C> AVLTree<K, V, C> {
+    /// 生成DOT格式的图，节点标签中注明树高，可以保存为文件后用Graphviz绘制：`dot -Tpng tree.dot -o tree.png`
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for key in 1..=3 {
+    ///     tree.insert(key, key);
+    /// }
+    /// let dot = tree.to_dot();
+    /// assert!(dot.starts_with("digraph AVLTree {"));
+    /// assert!(dot.contains("0 [label=\"2: 2\\nh=2\"];\n    0 -> 1;\n    1 [label=\"1: 1\\nh=1\"];\n"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph AVLTree {\n    ordering=out;\n    node [shape=circle];\n");
+        if let Some(root) = self.root.as_ref() {
+            root.write_dot(&mut out, &mut 0);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> AVLTree<K, V, C> {
     /// 向AVL树中插入键值对，如果键已经存在，则替换旧值为新值
     /// # Example
     /// ```
@@ -29,26 +153,121 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// ```
     pub fn insert(&mut self, key: K, value: V) {
         match self.root.take() {
-            None => self.root = Some(Box::new(Node::new(key, value))),
-            Some(node) => self.root = Some(node.insert(key, value)),
+            None => {
+                self.root = Some(Box::new(Node::new(key, value)));
+                self.len = 1;
+            }
+            Some(node) => {
+                let (root, inserted) = node.insert::<C>(key, value);
+                self.root = Some(root);
+                if inserted {
+                    self.len += 1;
+                }
+            }
+        }
+        self.check();
+    }
+
+    /// 获取键对应的入口，只需查找一次即可完成“不存在则插入，存在则修改”
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for c in "abcab".chars() {
+    ///     *tree.entry(c).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(tree.get(&'a'), Some(&2));
+    /// assert_eq!(tree.get(&'c'), Some(&1));
+    /// assert!(tree.is_avl_tree());
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, C> {
+        let mut path = Vec::new();
+        if Node::search_path::<C>(&self.root, &key, &mut path) {
+            Entry::Occupied(OccupiedEntry::new(self, path))
+        } else {
+            Entry::Vacant(VacantEntry::new(self, key, path))
         }
     }
 
+    // 返回路径对应节点的键值对
+    pub(crate) fn pair_at(&self, path: &[Side]) -> (&K, &V) {
+        self.root.as_ref().expect("AVL broken").pair_at(path)
+    }
+
+    // 返回路径对应节点的键和值的可变借用
+    pub(crate) fn pair_at_mut(&mut self, path: &[Side]) -> (&K, &mut V) {
+        self.root.as_mut().expect("AVL broken").pair_at_mut(path)
+    }
+
+    // 沿着查找时记录的路径插入新节点，返回新插入的值的可变借用
+    pub(crate) fn insert_at(&mut self, path: &[Side], key: K, value: V) -> &mut V {
+        self.len += 1;
+        let mut trail = match self.root.take() {
+            None => {
+                self.root = Some(Box::new(Node::new(key, value)));
+                VecDeque::new()
+            }
+            Some(node) => {
+                let (node, trail) = node.insert_at(path, key, value);
+                self.root = Some(node);
+                trail
+            }
+        };
+        self.check();
+        self.pair_at_mut(trail.make_contiguous()).1
+    }
+
+    // 删除路径对应的节点，返回被删除的键值对
+    pub(crate) fn remove_at(&mut self, path: &[Side]) -> (K, V) {
+        let (root, pair) = self.root.take().expect("AVL broken").remove_at(path);
+        self.root = root;
+        self.len -= 1;
+        self.check();
+        pair
+    }
+
     /// 从AVL树中删除键值对，如果找不到键值对，则忽略
     /// # Example
     /// ```
     /// use an_ok_avl_tree::AVLTree;
     /// let mut tree = AVLTree::new();
     /// tree.insert(1, 'a');
-    /// tree.delete(1);
+    /// tree.delete(&1);
     /// assert!(tree.is_empty());
-    /// tree.delete(2);
+    /// tree.delete(&2);
     /// assert!(tree.is_empty());
     /// ```
-    pub fn delete(&mut self, key: K) {
+    pub fn delete<Q: ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         if let Some(node) = self.root.take() {
-            self.root = node.delete(key)
+            let (root, deleted) = node.delete::<Q, C>(key);
+            self.root = root;
+            if deleted {
+                self.len -= 1;
+            }
         }
+        self.check();
+    }
+
+    /// 返回AVL树中键值对的个数，时间复杂度O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'c');
+    /// assert_eq!(tree.len(), 2);
+    /// tree.delete(&3);
+    /// assert_eq!(tree.len(), 2);
+    /// tree.delete(&1);
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
     }
 
     /// 判断当前AVL树是否为空
@@ -70,8 +289,12 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.get_pair(&1), Some((&1, &'a')));
     /// ```
-    pub fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.search_pair(key))
+    pub fn get_pair<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.search_pair::<Q, C>(key))
     }
 
     /// 根据键查找对应的值，找不到返回None，返回值的不可变借用
@@ -82,8 +305,31 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.get(&1), Some(&'a'));
     /// ```
-    pub fn get(&self, key: &K) -> Option<&V> {
-        self.root.as_ref().and_then(|node| node.search(key))
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.search::<Q, C>(key))
+    }
+
+    /// 根据键查找对应的值，找不到返回None，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 'a');
+    /// if let Some(value) = tree.get_mut(&1) {
+    ///     *value = 'b';
+    /// }
+    /// assert_eq!(tree.get(&1), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_mut().and_then(|node| node.search_mut::<Q, C>(key))
     }
 
     /// 据键查找对应的值，找不到返回默认值
@@ -95,7 +341,11 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert_eq!(tree.get_or(&1, &'z'), &'a');
     /// assert_eq!(tree.get_or(&2, &'z'), &'z');
     /// ```
-    pub fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+    pub fn get_or<'a, Q: ?Sized>(&'a self, key: &Q, default: &'a V) -> &'a V
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         self.get(key).map_or(default, |data| data)
     }
 
@@ -108,7 +358,11 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert_eq!(tree.contains(&1), true);
     /// assert_eq!(tree.contains(&2), false);
     /// ```
-    pub fn contains(&self, key: &K) -> bool {
+    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         self.get(key).is_some()
     }
 
@@ -150,10 +404,7 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert!(tree.is_avl_tree());
     /// ```
     pub fn is_avl_tree(&self) -> bool {
-        if self.root.is_none() {
-            return false;
-        }
-        Node::is_avl_tree(&self.root)
+        self.root.is_some() && self.validate().is_ok()
     }
 
     ///返回第一个大于key的键值对
@@ -168,8 +419,12 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert_eq!(tree.successor(&0), Some((&1, &'a')));
     /// assert_eq!(tree.successor(&3), None);
     /// ```
-    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.successor(key))
+    pub fn successor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.successor::<Q, C>(key))
     }
 
     ///返回第一个小于key的键值对
@@ -184,11 +439,146 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert_eq!(tree.predecessor(&5), Some((&3, &'c')));
     /// assert_eq!(tree.predecessor(&1), None);
     /// ```
-    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.predecessor(key))
+    pub fn predecessor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.predecessor::<Q, C>(key))
     }
 
-    /// 范围迭代器
+    /// 把树按key分成两棵，键不小于key的键值对移到返回的新树中，两棵树仍是AVL树，时间复杂度O(log n)
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// let other = tree.split_off(&4);
+    /// assert_eq!(tree.len(), 3);
+    /// assert_eq!(other.len(), 7);
+    /// assert_eq!(other.min_pair(), Some((&4, &40)));
+    /// assert!(tree.is_avl_tree() && other.is_avl_tree());
+    /// ```
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let (less, rest) = Node::split::<Q, C>(self.root.take(), key);
+        let rest_len = Node::size(&rest);
+        self.root = less;
+        self.len -= rest_len;
+        self.check();
+        Self::from_root(rest, rest_len)
+    }
+
+    /// 把other中的键值对全部移到当前树中，other变为空树，时间复杂度O(log n)
+    /// # Panics
+    /// other中存在不大于当前树最大键的键时panic
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// let mut other = AVLTree::new();
+    /// for key in 1..=3 {
+    ///     tree.insert(key, key * 10);
+    ///     other.insert(key + 3, key * 10 + 30);
+    /// }
+    /// tree.append(&mut other);
+    /// assert_eq!(tree.len(), 6);
+    /// assert!(other.is_empty());
+    /// assert!(tree.is_avl_tree());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        if let (Some((max, _)), Some((min, _))) = (self.max_pair(), other.min_pair()) {
+            assert!(C::compare(max, min) == Ordering::Less, "keys of other must be greater than all keys of self");
+        }
+        self.root = Node::concat(self.root.take(), other.root.take());
+        self.len += other.len;
+        other.len = 0;
+        self.check();
+    }
+
+    // 由按键排序的键值对构建新树
+    fn from_sorted_pairs<'a, I>(pairs: I) -> Self
+    where
+        I: Iterator<Item = (&'a K, &'a V)>,
+        K: 'a,
+        V: Clone + 'a,
+    {
+        Self::from_sorted_iter_with_comparator(pairs.map(|(key, value)| (key.clone(), value.clone())))
+    }
+
+    /// 返回第k小的键值对，k从0开始计数，k超出范围返回None
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(30, 'c');
+    /// tree.insert(10, 'a');
+    /// tree.insert(20, 'b');
+    /// assert_eq!(tree.select(0), Some((&10, &'a')));
+    /// assert_eq!(tree.select(2), Some((&30, &'c')));
+    /// assert_eq!(tree.select(3), None);
+    /// ```
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        self.root.as_ref().and_then(|node| node.select(k))
+    }
+
+    /// 返回树中小于key的键的个数，key可以不存在树中。key存在时就是它在中序序列中的下标
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(30, 'c');
+    /// tree.insert(10, 'a');
+    /// tree.insert(20, 'b');
+    /// assert_eq!(tree.rank(&10), 0);
+    /// assert_eq!(tree.rank(&25), 2);
+    /// assert_eq!(tree.rank(&99), 3);
+    /// ```
+    pub fn rank<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        Node::count_below::<Q, C>(&self.root, key, false)
+    }
+
+    /// 返回键在范围内的键值对个数，不需要遍历范围内的节点
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// assert_eq!(tree.count_range(3..=5), 3);
+    /// assert_eq!(tree.count_range(3..5), 2);
+    /// assert_eq!(tree.count_range(..), 10);
+    /// assert_eq!(tree.count_range(8..3), 0);
+    /// ```
+    pub fn count_range<Q: ?Sized, R: RangeBounds<Q>>(&self, range: R) -> usize
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let lower = match range.start_bound() {
+            Bound::Included(key) => Node::count_below::<Q, C>(&self.root, key, false),
+            Bound::Excluded(key) => Node::count_below::<Q, C>(&self.root, key, true),
+            Bound::Unbounded => 0,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(key) => Node::count_below::<Q, C>(&self.root, key, true),
+            Bound::Excluded(key) => Node::count_below::<Q, C>(&self.root, key, false),
+            Bound::Unbounded => self.len,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    /// 范围迭代器，可以从两端迭代
     /// # Example
     /// ```
     /// use an_ok_avl_tree::AVLTree;
@@ -203,9 +593,187 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b')]);
     /// let res: Vec<(&i32, &char)> = tree.range_pair_iter(Bound::Excluded(1), Bound::Excluded(3)).collect();
     /// assert_eq!(res, vec![(&2, &'b')]);
+    /// let res: Vec<(&i32, &char)> = tree.range_pair_iter(Bound::Included(2), Bound::Unbounded).rev().collect();
+    /// assert_eq!(res, vec![(&3, &'c'), (&2, &'b')]);
+    /// ```
+    pub fn range_pair_iter(&self, min: Bound<K>, max: Bound<K>) -> RangePairIter<'_, K, V> {
+        self.range::<K, _>((min, max))
+    }
+
+    /// 范围迭代器，接受任意`RangeBounds`，可以从两端迭代，`len()`的时间复杂度为O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// let res: Vec<i32> = tree.range(3..=5).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![3, 4, 5]);
+    /// let res: Vec<i32> = tree.range(..3).rev().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// assert_eq!(tree.range(11..).next(), None);
+    /// assert_eq!(tree.range(3..8).len(), 5);
     /// ```
-    pub fn range_pair_iter(&self, min: Bound<K>, max: Bound<K>) -> RangePairIter<K, V> {
-        RangePairIter::new(self, min, max)
+    pub fn range<Q: ?Sized, R: RangeBounds<Q>>(&self, range: R) -> RangePairIter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let bounds = (range.start_bound(), range.end_bound());
+        RangePairIter::new(&self.root, bounds.0, bounds.1, self.count_range::<Q, _>(bounds), C::compare)
+    }
+
+    /// 并集迭代器，按键的顺序返回两棵树中的键值对，两棵树都有的键只返回当前树的键值对
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.union(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, K, V>> {
+        Union::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 交集迭代器，按键的顺序返回两棵树都有的键对应的当前树的键值对
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.intersection(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![3, 4]);
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, K, V>> {
+        Intersection::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 差集迭代器，按键的顺序返回只在当前树中的键值对
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.difference(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2]);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, K, V>> {
+        Difference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 对称差迭代器，按键的顺序返回只在其中一棵树中的键值对
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.symmetric_difference(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2, 5, 6]);
+    /// ```
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, K, V>> {
+        SymmetricDifference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 范围可变迭代器，按键的顺序返回范围内的键值对，只能修改值
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// use std::collections::Bound;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 10);
+    /// tree.insert(2, 20);
+    /// tree.insert(3, 30);
+    /// for (_, value) in tree.range_pair_iter_mut(Bound::Excluded(&1), Bound::Unbounded) {
+    ///     *value += 1;
+    /// }
+    /// let res: Vec<(&i32, &i32)> = tree.inorder_iter().collect();
+    /// assert_eq!(res, vec![(&1, &10), (&2, &21), (&3, &31)]);
+    /// ```
+    pub fn range_pair_iter_mut(&mut self, min: Bound<&K>, max: Bound<&K>) -> IterMut<'_, K, V> {
+        self.range_mut::<K, _>((min, max))
+    }
+
+    /// 范围可变迭代器，接受任意`RangeBounds`，按键的顺序返回范围内的键值对，只能修改值，`len()`的时间复杂度为O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// for (_, value) in tree.range_mut(3..=5) {
+    ///     *value += 1;
+    /// }
+    /// let res: Vec<i32> = tree.range(2..=6).map(|(_, v)| *v).collect();
+    /// assert_eq!(res, vec![20, 31, 41, 51, 60]);
+    /// assert_eq!(tree.range_mut(3..8).len(), 5);
+    /// ```
+    pub fn range_mut<Q: ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let len = self.count_range::<Q, _>((range.start_bound(), range.end_bound()));
+        IterMut::with_lower_bound(&mut self.root, range.start_bound(), len, C::compare)
+    }
+
+    /// 中序可变迭代器，按键的顺序返回键值对，只能修改值
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'a');
+    /// for (key, value) in tree.iter_mut() {
+    ///     if *key == 2 {
+    ///         *value = 'z';
+    ///     }
+    /// }
+    /// assert_eq!(tree.get(&2), Some(&'z'));
+    /// ```
+    /// 键是只读的，修改键无法通过编译
+    /// ```compile_fail
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 'a');
+    /// for (key, _) in tree.iter_mut() {
+    ///     *key = 2;
+    /// }
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut::new(&mut self.root, self.len)
+    }
+
+    /// 按键的顺序返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 10);
+    /// tree.insert(2, 20);
+    /// tree.values_mut().for_each(|value| *value *= 2);
+    /// assert_eq!(tree.get(&2), Some(&40));
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut::new(self.iter_mut())
     }
 
     /// 前序遍历迭代器
@@ -219,18 +787,11 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.preorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn preorder_iter(&self) -> TraverseIter<K, V> {
-        let pre_order = self.prev_order();
-        let mut queue = VecDeque::new();
-        for key in pre_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Pre, self.len)
     }
 
-    /// 中序遍历迭代器
+    /// 中序遍历迭代器，可以从两端迭代
     /// # Example
     /// ```
     /// use an_ok_avl_tree::AVLTree;
@@ -240,16 +801,12 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// tree.insert(1, 'a');
     /// let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    /// let res: Vec<(&i32, &char)> = tree.inorder_iter().rev().collect();
+    /// assert_eq!(res, vec![(&3, &'c'), (&2, &'b'), (&1, &'a')]);
+    /// assert_eq!(tree.inorder_iter().len(), 3);
     /// ```
-    pub fn inorder_iter(&self) -> TraverseIter<K, V> {
-        let in_order = self.in_order();
-        let mut queue = VecDeque::new();
-        for key in in_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn inorder_iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.root, self.len)
     }
 
     /// 后序遍历迭代器
@@ -263,15 +820,8 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.postorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&3, &'c'), (&2, &'b')]);
     /// ```
-    pub fn postorder_iter(&self) -> TraverseIter<K, V> {
-        let post_order = self.post_order();
-        let mut queue = VecDeque::new();
-        for key in post_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Post, self.len)
     }
 
     /// 层序遍历迭代器
@@ -285,64 +835,212 @@ impl<K: PartialOrd + Clone, V> AVLTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.levelorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn levelorder_iter(&self) -> TraverseIter<K, V> {
-        let level_order = self.level_order();
-        let mut queue = VecDeque::new();
-        for key in level_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Level, self.len)
     }
+}
+
+/// 返回两棵树的并集构成的新树
+/// # Example
+/// ```
+/// use an_ok_avl_tree::AVLTree;
+/// let mut a = AVLTree::new();
+/// let mut b = AVLTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a | &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitOr<&AVLTree<K, V, C>> for &AVLTree<K, V, C> {
+    type Output = AVLTree<K, V, C>;
 
-    ///前序遍历
-    fn prev_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::prev_order(&self.root, &mut buf);
-        buf
+    fn bitor(self, rhs: &AVLTree<K, V, C>) -> AVLTree<K, V, C> {
+        AVLTree::from_sorted_pairs(self.union(rhs))
     }
+}
+
+/// 返回两棵树的交集构成的新树
+/// # Example
+/// ```
+/// use an_ok_avl_tree::AVLTree;
+/// let mut a = AVLTree::new();
+/// let mut b = AVLTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a & &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![3, 4]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitAnd<&AVLTree<K, V, C>> for &AVLTree<K, V, C> {
+    type Output = AVLTree<K, V, C>;
 
-    ///中序遍历
-    fn in_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::in_order(&self.root, &mut buf);
-        buf
+    fn bitand(self, rhs: &AVLTree<K, V, C>) -> AVLTree<K, V, C> {
+        AVLTree::from_sorted_pairs(self.intersection(rhs))
     }
+}
+
+/// 返回两棵树的差集构成的新树
+/// # Example
+/// ```
+/// use an_ok_avl_tree::AVLTree;
+/// let mut a = AVLTree::new();
+/// let mut b = AVLTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a - &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> Sub<&AVLTree<K, V, C>> for &AVLTree<K, V, C> {
+    type Output = AVLTree<K, V, C>;
 
-    ///后序遍历
-    fn post_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::post_order(&self.root, &mut buf);
-        buf
+    fn sub(self, rhs: &AVLTree<K, V, C>) -> AVLTree<K, V, C> {
+        AVLTree::from_sorted_pairs(self.difference(rhs))
     }
+}
+
+/// 返回两棵树的对称差构成的新树
+/// # Example
+/// ```
+/// use an_ok_avl_tree::AVLTree;
+/// let mut a = AVLTree::new();
+/// let mut b = AVLTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a ^ &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2, 5, 6]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitXor<&AVLTree<K, V, C>> for &AVLTree<K, V, C> {
+    type Output = AVLTree<K, V, C>;
 
-    ///层序遍历
-    fn level_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::level_order(&self.root, &mut buf);
-        buf
+    fn bitxor(self, rhs: &AVLTree<K, V, C>) -> AVLTree<K, V, C> {
+        AVLTree::from_sorted_pairs(self.symmetric_difference(rhs))
     }
 }
 
-/// 将AVL树打印成字符串
+/// 将AVL树打印成字符串，`{}`打印成一行嵌套的形式，`{:#}`把树画成多行，空的孩子画成Ø
 /// # Example
 /// ```
 /// use an_ok_avl_tree::AVLTree;
 /// let mut tree = AVLTree::new();
 /// tree.insert(1, 'a');
 /// assert_eq!(tree.to_string(), "[K: 1, V: a, L: Ø, R: Ø]".to_string());
+/// tree.insert(2, 'b');
+/// assert_eq!(format!("{:#}", tree), "1: a\n├── Ø\n└── 2: b");
 /// ```
-impl<K: PartialOrd + ToString, V: ToString> ToString for AVLTree<K, V> {
-    fn to_string(&self) -> String {
-        self.root
-            .as_ref()
-            .map_or(String::from("None"), |node| node.to_string())
+impl<K: Display, V: Display, C> Display for AVLTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.root {
+            None if f.alternate() => write!(f, "Ø"),
+            None => write!(f, "None"),
+            Some(ref node) if f.alternate() => node.draw(f, &|key, value, f| write!(f, "{}: {}", key, value)),
+            Some(ref node) => write!(f, "{}", node),
+        }
     }
 }
 
-impl<K: PartialOrd + Clone, V> Default for AVLTree<K, V> {
+/// `{:?}`按键的顺序打印所有键值对，`{:#?}`把树画成多行
+/// # Example
+/// ```
+/// use an_ok_avl_tree::AVLTree;
+/// let mut tree = AVLTree::new();
+/// for key in 1..=3 {
+///     tree.insert(key, 'a');
+/// }
+/// assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'a', 3: 'a'}");
+/// assert_eq!(format!("{:#?}", tree), "2: 'a'\n├── 1: 'a'\n└── 3: 'a'");
+/// ```
+impl<K: Debug + Clone, V: Debug, C: Compare<K>> Debug for AVLTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.root {
+            _ if !f.alternate() => f.debug_map().entries(self.inorder_iter()).finish(),
+            None => write!(f, "Ø"),
+            Some(ref node) => node.draw(f, &|key, value, f| write!(f, "{:?}: {:?}", key, value)),
+        }
+    }
+}
+
+impl<K, V, C: Compare<K>> Default for AVLTree<K, V, C> {
     fn default() -> Self {
-        Self::new()
+        Self::with_comparator()
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> OrderedMap<K, V> for AVLTree<K, V, C> {
+    type TraverseIter<'a> = TraverseIter<'a, K, V> where Self: 'a;
+    type Iter<'a> = Iter<'a, K, V> where Self: 'a;
+
+    fn new() -> Self {
+        AVLTree::with_comparator()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        AVLTree::insert(self, key, value)
+    }
+
+    fn delete(&mut self, key: K) {
+        AVLTree::delete(self, &key)
+    }
+
+    fn len(&self) -> usize {
+        AVLTree::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        AVLTree::is_empty(self)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        AVLTree::get(self, key)
+    }
+
+    fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
+        AVLTree::get_pair(self, key)
+    }
+
+    fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+        AVLTree::get_or(self, key, default)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        AVLTree::contains(self, key)
+    }
+
+    fn min_pair(&self) -> Option<(&K, &V)> {
+        AVLTree::min_pair(self)
+    }
+
+    fn max_pair(&self) -> Option<(&K, &V)> {
+        AVLTree::max_pair(self)
+    }
+
+    fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        AVLTree::successor(self, key)
+    }
+
+    fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        AVLTree::predecessor(self, key)
+    }
+
+    fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        AVLTree::preorder_iter(self)
+    }
+
+    fn inorder_iter(&self) -> Iter<'_, K, V> {
+        AVLTree::inorder_iter(self)
+    }
+
+    fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        AVLTree::postorder_iter(self)
+    }
+
+    fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        AVLTree::levelorder_iter(self)
     }
 }
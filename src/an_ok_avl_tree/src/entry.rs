@@ -0,0 +1,165 @@
+use crate::node::Side;
+use crate::AVLTree;
+use ordered_map::{Compare, Natural};
+
+/// AVL树中某个键的入口，由`AVLTree::entry`返回
+pub enum Entry<'a, K, V, C = Natural> {
+    /// 键不存在
+    Vacant(VacantEntry<'a, K, V, C>),
+    /// 键已存在
+    Occupied(OccupiedEntry<'a, K, V, C>),
+}
+
+/// 不存在的键的入口，记录了查找时从根节点出发的路径，插入时无需再次比较
+pub struct VacantEntry<'a, K, V, C = Natural> {
+    tree: &'a mut AVLTree<K, V, C>,
+    key: K,
+    path: Vec<Side>,
+}
+
+/// 已存在的键的入口，记录了从根节点到该节点的路径
+pub struct OccupiedEntry<'a, K, V, C = Natural> {
+    tree: &'a mut AVLTree<K, V, C>,
+    path: Vec<Side>,
+}
+
+impl<'a, K: Clone, V, C: Compare<K>> Entry<'a, K, V, C> {
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// 键不存在时插入default，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// *tree.entry(1).or_insert(10) += 1;
+    /// *tree.entry(1).or_insert(10) += 1;
+    /// assert_eq!(tree.get(&1), Some(&12));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入f的返回值，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.entry(1).or_insert_with(Vec::new).push('a');
+    /// tree.entry(1).or_insert_with(Vec::new).push('b');
+    /// assert_eq!(tree.get(&1), Some(&vec!['a', 'b']));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(f()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入默认值，返回值的可变借用
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// 键存在时用f原地修改值，返回修改后的入口
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::AVLTree;
+    /// let mut tree = AVLTree::new();
+    /// tree.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// tree.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// assert_eq!(tree.get(&1), Some(&1));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K: Clone, V, C: Compare<K>> VacantEntry<'a, K, V, C> {
+    pub(crate) fn new(tree: &'a mut AVLTree<K, V, C>, key: K, path: Vec<Side>) -> Self {
+        VacantEntry { tree, key, path }
+    }
+
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 取回入口对应的键
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 插入值，插入后沿路径逐层调整保持平衡，返回值的可变借用
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.tree.insert_at(&self.path, self.key, value)
+    }
+}
+
+impl<'a, K: Clone, V, C: Compare<K>> OccupiedEntry<'a, K, V, C> {
+    pub(crate) fn new(tree: &'a mut AVLTree<K, V, C>, path: Vec<Side>) -> Self {
+        OccupiedEntry { tree, path }
+    }
+
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        self.tree.pair_at(&self.path).0
+    }
+
+    /// 返回值的不可变借用
+    pub fn get(&self) -> &V {
+        self.tree.pair_at(&self.path).1
+    }
+
+    /// 返回值的可变借用
+    pub fn get_mut(&mut self) -> &mut V {
+        self.tree.pair_at_mut(&self.path).1
+    }
+
+    /// 将入口转换为值的可变借用，生命周期与树的借用相同
+    pub fn into_mut(self) -> &'a mut V {
+        self.tree.pair_at_mut(&self.path).1
+    }
+
+    /// 替换为新值，返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 从树中删除该键值对并返回
+    /// # Example
+    /// ```
+    /// use an_ok_avl_tree::{AVLTree, Entry};
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1, 'a');
+    /// if let Entry::Occupied(entry) = tree.entry(1) {
+    ///     assert_eq!(entry.remove_entry(), (1, 'a'));
+    /// }
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        self.tree.remove_at(&self.path)
+    }
+
+    /// 从树中删除该键值对，返回值
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
@@ -1,92 +1,347 @@
-use crate::AVLTree;
+use crate::node::{Link, Node};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::collections::{Bound, VecDeque};
 
-// 范围迭代器
-pub struct RangePairIter<'a, K: PartialOrd + Clone, V> {
-    tree: &'a AVLTree<K, V>, // AVL树的借用
-    from: Bound<K>, // 范围的起点
-    to: Bound<K>, //范围的终点
-    prev: Option<&'a K>, // 前一次迭代时输出的key
-}
-
-impl<'a, K: PartialOrd + Clone, V> RangePairIter<'a, K, V> {
-    pub fn new(tree: &'a AVLTree<K, V>, lower: Bound<K>, upper: Bound<K>) -> Self {
-        Self {
-            tree,
-            from: lower,
-            to: upper,
-            prev: None,
-        }
+// 将节点及其左侧路径上的节点依次入栈
+fn push_left<'a, K, V>(stack: &mut Vec<&'a Node<K, V>>, mut link: &'a Link<K, V>) {
+    while let Some(node) = link {
+        stack.push(node);
+        link = node.children().0;
     }
+}
 
-    // 获取迭代器中的下一个键值对，检查上下边界
-    fn get_next_key_under(&mut self) -> Option<(&'a K, &'a V)> {
-        let res = self
-            .get_next_pair()
-            .and_then(|cur| self.check_upper_bound(cur));
-        if let Some((key, _)) = res {
-            self.prev = Some(key);
-        }
-        res
+// 将节点及其右侧路径上的节点依次入栈
+fn push_right<'a, K, V>(stack: &mut Vec<&'a Node<K, V>>, mut link: &'a Link<K, V>) {
+    while let Some(node) = link {
+        stack.push(node);
+        link = node.children().1;
+    }
+}
+
+// key是否不小于下边界
+fn above_lower<Q: ?Sized>(from: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match from {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Less,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Greater,
+        Bound::Unbounded => true,
     }
+}
+
+// key是否不大于上边界
+fn below_upper<Q: ?Sized>(to: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match to {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Greater,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Less,
+        Bound::Unbounded => true,
+    }
+}
+
+// 中序迭代器，可以从两端迭代。front栈顶是下一个最小的节点，back栈顶是下一个最大的节点
+pub struct Iter<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+    remaining: usize, // 剩余未输出的键值对个数，为0时两端相遇
+}
 
-    // 获取迭代器中的下一个键值对，检查下边界
-    fn get_next_pair(&mut self) -> Option<(&'a K, &'a V)> {
-        match self.prev {
-            None => self.get_lower_bound_pair(),
-            Some(key) => self.tree.successor(key),
+impl<'a, K, V> Iter<'a, K, V> {
+    pub fn new(root: &'a Link<K, V>, len: usize) -> Self {
+        let mut iter = Iter {
+            front: Vec::new(),
+            back: Vec::new(),
+            remaining: len,
+        };
+        push_left(&mut iter.front, root);
+        push_right(&mut iter.back, root);
+        iter
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        let node = self.front.pop()?;
+        push_left(&mut self.front, node.children().1);
+        self.remaining -= 1;
+        Some(node.pair())
     }
 
-    // 获取下边界对应的键值对
-    fn get_lower_bound_pair(&self) -> Option<(&'a K, &'a V)> {
-        match self.from {
-            Bound::Included(ref key) => {
-                self.tree.get_pair(key).or_else(|| self.tree.successor(key))
-            }
-            Bound::Excluded(ref key) => self.tree.successor(key),
-            Bound::Unbounded => self.tree.min_pair(),
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        let node = self.back.pop()?;
+        push_right(&mut self.back, node.children().0);
+        self.remaining -= 1;
+        Some(node.pair())
     }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+// 范围迭代器，可以从两端迭代。两个栈只保存范围内的节点，len为范围内键值对的个数，由调用者根据子树大小求出
+pub struct RangePairIter<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+    remaining: usize, // 剩余未输出的键值对个数，为0时两端相遇
+}
 
-    // 检查是否超过上边界，超过则返回None
-    fn check_upper_bound(&self, current: (&'a K, &'a V)) -> Option<(&'a K, &'a V)> {
-        let ok = match self.to {
-            Bound::Included(ref key) => current.0 <= key,
-            Bound::Excluded(ref key) => current.0 < key,
-            Bound::Unbounded => true,
+impl<'a, K, V> RangePairIter<'a, K, V> {
+    pub fn new<Q: ?Sized>(
+        root: &'a Link<K, V>,
+        lower: Bound<&Q>,
+        upper: Bound<&Q>,
+        len: usize,
+        cmp: fn(&Q, &Q) -> Ordering,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut iter = RangePairIter {
+            front: Vec::new(),
+            back: Vec::new(),
+            remaining: len,
         };
-        if ok {
-            Some(current)
-        } else {
-            None
+        // 只保留不小于下边界的节点，第一个出栈的就是范围内最小的节点
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (left, right) = node.children();
+            if above_lower(lower, node.pair().0.borrow(), cmp) {
+                iter.front.push(node);
+                cur = left;
+            } else {
+                cur = right;
+            }
         }
+        // 只保留不大于上边界的节点
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (left, right) = node.children();
+            if below_upper(upper, node.pair().0.borrow(), cmp) {
+                iter.back.push(node);
+                cur = right;
+            } else {
+                cur = left;
+            }
+        }
+        iter
     }
 }
 
-impl<'a, K: PartialOrd + Clone, V> Iterator for RangePairIter<'a, K, V> {
+impl<'a, K, V> Iterator for RangePairIter<'a, K, V> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.get_next_key_under()
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.front.pop()?;
+        push_left(&mut self.front, node.children().1);
+        self.remaining -= 1;
+        Some(node.pair())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangePairIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back.pop()?;
+        push_right(&mut self.back, node.children().0);
+        self.remaining -= 1;
+        Some(node.pair())
     }
 }
 
-//遍历迭代器，包括前序、中序、后序、层序
+impl<'a, K, V> ExactSizeIterator for RangePairIter<'a, K, V> {}
+
+// 遍历的顺序，中序遍历由Iter完成
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Order {
+    Pre,
+    Post,
+    Level,
+}
+
+//遍历迭代器，包括前序、后序、层序。迭代器只保存待访问的节点，按需产生键值对
 pub struct TraverseIter<'a, K, V> {
-    data: VecDeque<(&'a K, &'a V)>,
+    order: Order,
+    // 前序、后序遍历时作为栈使用，只保存根到叶子路径附近的节点；层序遍历时作为队列使用
+    // bool表示节点的孩子是否已经展开，只在后序遍历时使用
+    nodes: VecDeque<(&'a Node<K, V>, bool)>,
+    remaining: usize, // 剩余未输出的键值对个数
 }
 
 impl<'a, K, V> TraverseIter<'a, K, V> {
-    pub fn new(queue: VecDeque<(&'a K, &'a V)>) -> Self {
-        TraverseIter { data: queue }
+    pub fn new(root: &'a Link<K, V>, order: Order, len: usize) -> Self {
+        let mut iter = TraverseIter {
+            order,
+            nodes: VecDeque::new(),
+            remaining: len,
+        };
+        if let Some(node) = root {
+            iter.nodes.push_back((node, false));
+        }
+        iter
+    }
+
+    // 将孩子节点加入待访问序列，先加入的后访问
+    fn push_children(&mut self, first: &'a Link<K, V>, second: &'a Link<K, V>) {
+        if let Some(node) = first {
+            self.nodes.push_back((node, false));
+        }
+        if let Some(node) = second {
+            self.nodes.push_back((node, false));
+        }
+    }
+
+    fn next_node(&mut self) -> Option<&'a Node<K, V>> {
+        match self.order {
+            Order::Pre => {
+                let (node, _) = self.nodes.pop_back()?;
+                let (left, right) = node.children();
+                self.push_children(right, left);
+                Some(node)
+            }
+            Order::Post => loop {
+                let (node, expanded) = self.nodes.pop_back()?;
+                let (left, right) = node.children();
+                if expanded || (left.is_none() && right.is_none()) {
+                    return Some(node);
+                }
+                self.nodes.push_back((node, true));
+                self.push_children(right, left);
+            },
+            Order::Level => {
+                let (node, _) = self.nodes.pop_front()?;
+                let (left, right) = node.children();
+                self.push_children(left, right);
+                Some(node)
+            }
+        }
     }
 }
 
-impl<'a, K: PartialOrd + Clone, V> Iterator for TraverseIter<'a, K, V> {
+impl<'a, K, V> Iterator for TraverseIter<'a, K, V> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.data.pop_front()
+        let node = self.next_node()?;
+        self.remaining -= 1;
+        Some(node.pair())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for TraverseIter<'a, K, V> {}
+
+// 中序可变迭代器，只能修改值，不能修改键
+pub struct IterMut<'a, K, V> {
+    stack: Vec<(&'a K, &'a mut V, &'a mut Link<K, V>)>, // 待访问节点的键、值以及右子树
+    remaining: usize, // 剩余未输出的键值对个数，为0时结束
+}
+
+impl<'a, K, V> IterMut<'a, K, V> {
+    pub fn new(root: &'a mut Link<K, V>, len: usize) -> Self {
+        let mut iter = IterMut {
+            stack: Vec::new(),
+            remaining: len,
+        };
+        iter.push_left(root);
+        iter
+    }
+
+    // 从第一个不小于下边界的节点开始迭代，len为范围内键值对的个数，由调用者根据子树大小求出
+    pub fn with_lower_bound<Q: ?Sized>(root: &'a mut Link<K, V>, from: Bound<&Q>, len: usize, cmp: fn(&Q, &Q) -> Ordering) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut iter = IterMut {
+            stack: Vec::new(),
+            remaining: len,
+        };
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (key, value, left, right) = node.parts_mut();
+            if above_lower(from, key.borrow(), cmp) {
+                iter.stack.push((key, value, right));
+                cur = left;
+            } else {
+                cur = right;
+            }
+        }
+        iter
+    }
+
+    // 将节点及其左侧路径上的节点依次入栈
+    fn push_left(&mut self, mut link: &'a mut Link<K, V>) {
+        while let Some(node) = link {
+            let (key, value, left, right) = node.parts_mut();
+            self.stack.push((key, value, right));
+            link = left;
+        }
     }
 }
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (key, value, right) = self.stack.pop()?;
+        self.push_left(right);
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+// 按键的顺序返回值的可变借用
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> ValuesMut<'a, K, V> {
+    pub fn new(inner: IterMut<'a, K, V>) -> Self {
+        ValuesMut { inner }
+    }
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
@@ -1,5 +1,7 @@
 mod node;
 mod iterator;
+mod entry;
 
 mod avltree;
 pub use avltree::AVLTree;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
@@ -1,50 +1,63 @@
-use std::cmp::max;
+use ordered_map::{dot_escape, draw_tree, Compare, InvariantViolation};
+use std::borrow::Borrow;
+use std::cmp::{max, Ordering};
 use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
 
 pub type Link<K, V> = Option<Box<Node<K, V>>>;
 
+// 从根节点出发时每一步的走向，用于入口(Entry)记录查找路径
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
 pub struct Node<K, V> {
     key: K, //键
     value: V, //值
     height: u32, //树高
+    size: usize, //以该节点为根的子树中的节点个数
     left: Link<K, V>,
     right: Link<K, V>,
 }
 
-impl<K: PartialOrd + Clone, V> Node<K, V> {
+impl<K, V> Node<K, V> {
 
     pub fn new(key: K, value: V) -> Self {
         Node {
             key,
             value,
             height: 1,
+            size: 1,
             left: None,
             right: None,
         }
     }
 
-    // 判断当前节点是否为叶子节点
-    fn is_leaf(&self) -> bool {
-        self.left.is_none() && self.right.is_none()
-    }
-
     // 得到当前节点的高度
     fn height(node: &Link<K, V>) -> u32 {
         node.as_ref().map_or(0, |node| node.height)
     }
 
-    // 更新当前节点的高度
-    fn update_height(&mut self) {
+    // 得到以当前节点为根的子树中的节点个数
+    pub fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map_or(0, |node| node.size)
+    }
+
+    // 更新当前节点的高度和子树节点个数，旋转和插入删除后都要调用
+    fn update_height_and_size(&mut self) {
         self.height = max(Self::height(&self.left), Self::height(&self.right)) + 1;
+        self.size = Self::size(&self.left) + Self::size(&self.right) + 1;
     }
 
     //对当前节点进行一次左旋操作，返回旋转后的根节点
     fn left_rotate(mut self) -> Box<Node<K, V>> {
         let mut new_root = self.right.take().expect("AVL broken");
         self.right = new_root.left.take();
-        self.update_height();
+        self.update_height_and_size();
         new_root.left = Some(Box::new(self));
-        new_root.update_height();
+        new_root.update_height_and_size();
         new_root
     }
 
@@ -52,9 +65,9 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
     fn right_rotate(mut self) -> Box<Node<K, V>> {
         let mut new_root = self.left.take().expect("AVL broken");
         self.left = new_root.right.take();
-        self.update_height();
+        self.update_height_and_size();
         new_root.right = Some(Box::new(self));
-        new_root.update_height();
+        new_root.update_height_and_size();
         new_root
     }
 
@@ -64,7 +77,7 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         if Self::height(&left.left) < Self::height(&left.right) {
             let rotated = left.left_rotate();
             self.left = Some(rotated);
-            self.update_height();
+            self.update_height_and_size();
         } else {
             self.left = Some(left);
         }
@@ -77,7 +90,7 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         if Self::height(&right.left) > Self::height(&right.right) {
             let rotated = right.right_rotate();
             self.right = Some(rotated);
-            self.update_height();
+            self.update_height_and_size();
         } else {
             self.right = Some(right);
         }
@@ -94,7 +107,7 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
     //判断当前节点是否需要进行旋转调整，返回调整后的根节点
     fn rotate_if_necessary(self) -> Box<Node<K, V>> {
         let diff = self.diff_of_height();
-        if -1 <= diff && diff <= 1 {
+        if (-1..=1).contains(&diff) {
             Box::new(self)
         } else if diff == -2 {
             self.right_balance()
@@ -107,35 +120,43 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
 
     //更新当前根节点，包括高度更新和旋转操作
     fn update_node(mut self) -> Box<Node<K, V>> {
-        self.update_height();
+        self.update_height_and_size();
         self.rotate_if_necessary()
     }
 
     //插入新节点，并返回调整后的根节点
-    pub fn insert(mut self, key: K, value: V) -> Box<Node<K, V>> {
-        if self.key > key {
-            match self.left.take() {
+    pub fn insert<C: Compare<K>>(mut self, key: K, value: V) -> (Box<Node<K, V>>, bool) {
+        // 返回新生成树的根节点，以及是否新增了节点(键已存在时只替换值)
+        let inserted;
+        match C::compare(&key, &self.key) {
+            Ordering::Less => match self.left.take() {
                 None => {
                     self.left = Some(Box::new(Node::new(key, value)));
+                    inserted = true;
                 }
                 Some(node) => {
-                    self.left = Some(node.insert(key, value));
+                    let (node, res) = node.insert::<C>(key, value);
+                    self.left = Some(node);
+                    inserted = res;
                 }
-            }
-        } else if self.key < key {
-            match self.right.take() {
+            },
+            Ordering::Greater => match self.right.take() {
                 None => {
                     self.right = Some(Box::new(Node::new(key, value)));
+                    inserted = true;
                 }
                 Some(node) => {
-                    self.right = Some(node.insert(key, value));
+                    let (node, res) = node.insert::<C>(key, value);
+                    self.right = Some(node);
+                    inserted = res;
                 }
+            },
+            Ordering::Equal => {
+                self.value = value;
+                return (Box::new(self), false);
             }
-        } else {
-            self.value = value;
-            return Box::new(self);
         }
-        self.update_node()
+        (self.update_node(), inserted)
     }
 
     //找出当前树中值最小的节点，返回元组:(除去最小节点后剩下的树，最小节点)
@@ -164,8 +185,72 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         new_root.update_node()
     }
 
+    //以mid为中间节点连接两棵AVL树，left中的键都小于mid的键，right中的键都大于mid的键，返回新的根节点
+    //沿较高的树向下找到高度相近的子树再连接，时间与两棵树的高度差成正比
+    pub fn join(left: Link<K, V>, mut mid: Box<Node<K, V>>, right: Link<K, V>) -> Box<Node<K, V>> {
+        let (left_height, right_height) = (Self::height(&left), Self::height(&right));
+        if left_height > right_height + 1 {
+            let mut left = left.expect("AVL broken");
+            left.right = Some(Self::join(left.right.take(), mid, right));
+            left.update_node()
+        } else if right_height > left_height + 1 {
+            let mut right = right.expect("AVL broken");
+            right.left = Some(Self::join(left, mid, right.left.take()));
+            right.update_node()
+        } else {
+            mid.left = left;
+            mid.right = right;
+            mid.update_node()
+        }
+    }
+
+    //由按键严格递增的n个键值对构建完全平衡的AVL树，左右子树的节点个数最多相差1，时间复杂度O(n)
+    pub fn build_balanced<I: Iterator<Item = (K, V)>>(pairs: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_balanced(pairs, (n - 1) / 2);
+        let (key, value) = pairs.next().expect("AVL broken");
+        let mut node = Box::new(Node::new(key, value));
+        node.left = left;
+        node.right = Self::build_balanced(pairs, n - 1 - (n - 1) / 2);
+        node.update_height_and_size();
+        Some(node)
+    }
+
+    //连接两棵AVL树，left中的键都小于right中的键，返回新的根节点
+    pub fn concat(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+        match right {
+            None => left,
+            Some(right) => {
+                let (rest, min) = right.remove_min();
+                Some(Self::join(left, min, rest))
+            }
+        }
+    }
+
+    //按key把树分成两棵AVL树，第一棵中的键都小于key，第二棵中的键都不小于key
+    pub fn split<Q: ?Sized, C: Compare<Q>>(root: Link<K, V>, key: &Q) -> (Link<K, V>, Link<K, V>)
+    where
+        K: Borrow<Q>,
+    {
+        match root {
+            None => (None, None),
+            Some(mut node) => {
+                let (left, right) = (node.left.take(), node.right.take());
+                if C::compare(node.key.borrow(), key) == Ordering::Less {
+                    let (less, rest) = Self::split::<Q, C>(right, key);
+                    (Some(Self::join(left, node, less)), rest)
+                } else {
+                    let (less, rest) = Self::split::<Q, C>(left, key);
+                    (less, Some(Self::join(rest, node, right)))
+                }
+            }
+        }
+    }
+
     //删除当前节点，重构二叉树，并返回新的根节点
-    fn delete_root(mut self) -> Link<K, V> {
+    fn delete_root(&mut self) -> Link<K, V> {
         // AVL树删除节点的三种情况(包括二叉搜索树)，AVL树的删除还要多一步旋转操作
         // 1.如果是叶子节点，则直接删除
         // 2.如果待删除节点只有左子树或只有右子树，删除该节点，然后将左子树或右子树移动到该节点
@@ -178,115 +263,281 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         }
     }
 
-    //删除节点key，并保持改树仍为AVL树，返回的新生成的树的根节点
-    pub fn delete(mut self, key: K) -> Link<K, V> {
-        if self.key < key {
-            if let Some(succ) = self.right.take() {
-                self.right = succ.delete(key);
-                return Some(self.update_node());
+    //删除节点key，并保持改树仍为AVL树，返回新生成的树的根节点，以及是否删除了节点
+    pub fn delete<Q: ?Sized, C: Compare<Q>>(mut self, key: &Q) -> (Link<K, V>, bool)
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => {
+                if let Some(succ) = self.right.take() {
+                    let (right, deleted) = succ.delete::<Q, C>(key);
+                    self.right = right;
+                    return (Some(self.update_node()), deleted);
+                }
             }
-        } else if self.key > key {
-            if let Some(succ) = self.left.take() {
-                self.left = succ.delete(key);
-                return Some(self.update_node());
+            Ordering::Less => {
+                if let Some(succ) = self.left.take() {
+                    let (left, deleted) = succ.delete::<Q, C>(key);
+                    self.left = left;
+                    return (Some(self.update_node()), deleted);
+                }
             }
-        } else {
-            return self.delete_root();
+            Ordering::Equal => return (self.delete_root(), true),
         }
         // 没有找到待删除节点则直接返回
-        Some(Box::new(self))
+        (Some(Box::new(self)), false)
     }
 
-    // 返回第一个大于key的键值对,key可以不存在树中
-    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
-        if self.key > *key {
-            match self.left {
-                None => Some((&self.key, &self.value)),
-                Some(ref succ) => succ.successor(key).or(Some((&self.key, &self.value))),
+    // 从root开始查找key，沿途的走向记录在path中，找到返回true
+    pub fn search_path<C: Compare<K>>(root: &Link<K, V>, key: &K, path: &mut Vec<Side>) -> bool {
+        let mut cur = root;
+        while let Some(node) = cur {
+            match C::compare(key, &node.key) {
+                Ordering::Less => {
+                    path.push(Side::Left);
+                    cur = &node.left;
+                }
+                Ordering::Greater => {
+                    path.push(Side::Right);
+                    cur = &node.right;
+                }
+                Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+
+    // 沿着路径找到对应节点，返回键值对的不可变借用
+    pub fn pair_at(&self, path: &[Side]) -> (&K, &V) {
+        let mut cur = self;
+        for side in path {
+            cur = match side {
+                Side::Left => cur.left.as_ref(),
+                Side::Right => cur.right.as_ref(),
+            }
+            .expect("AVL broken");
+        }
+        (&cur.key, &cur.value)
+    }
+
+    // 沿着路径找到对应节点，返回键的不可变借用和值的可变借用
+    pub fn pair_at_mut(&mut self, path: &[Side]) -> (&K, &mut V) {
+        let mut cur = self;
+        for side in path {
+            cur = match side {
+                Side::Left => cur.left.as_mut(),
+                Side::Right => cur.right.as_mut(),
+            }
+            .expect("AVL broken");
+        }
+        (&cur.key, &mut cur.value)
+    }
+
+    //沿着路径插入新节点，返回调整后的根节点，以及调整后新节点所在的路径
+    pub fn insert_at(mut self, path: &[Side], key: K, value: V) -> (Box<Node<K, V>>, VecDeque<Side>) {
+        let (side, rest) = path.split_first().expect("AVL broken");
+        let child = match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        };
+        let mut trail = match child.take() {
+            None => {
+                *child = Some(Box::new(Node::new(key, value)));
+                VecDeque::new()
+            }
+            Some(node) => {
+                let (node, trail) = node.insert_at(rest, key, value);
+                *child = Some(node);
+                trail
+            }
+        };
+        trail.push_front(*side);
+        self.update_height_and_size();
+        // 旋转会改变新节点的位置，旋转前先按照同样的规则修正路径
+        let diff = self.diff_of_height();
+        if diff == 2 {
+            let left = self.left.as_ref().expect("AVL broken");
+            if Self::height(&left.left) < Self::height(&left.right) && trail.front() == Some(&Side::Left) {
+                trail.pop_front();
+                Self::left_rotate_trail(&mut trail);
+                trail.push_front(Side::Left);
+            }
+            Self::right_rotate_trail(&mut trail);
+        } else if diff == -2 {
+            let right = self.right.as_ref().expect("AVL broken");
+            if Self::height(&right.left) > Self::height(&right.right) && trail.front() == Some(&Side::Right) {
+                trail.pop_front();
+                Self::right_rotate_trail(&mut trail);
+                trail.push_front(Side::Right);
+            }
+            Self::left_rotate_trail(&mut trail);
+        }
+        (self.rotate_if_necessary(), trail)
+    }
+
+    // 当前节点左旋后，修正从当前节点出发的路径
+    fn left_rotate_trail(trail: &mut VecDeque<Side>) {
+        if trail.front() == Some(&Side::Right) {
+            // 原右孩子成为新的根节点，其左子树挂到原根节点的右边
+            trail.pop_front();
+            if trail.front() == Some(&Side::Left) {
+                trail.pop_front();
+                trail.push_front(Side::Right);
+                trail.push_front(Side::Left);
             }
-        } else if self.key < *key {
-            self.right.as_ref().and_then(|right| right.successor(key))
         } else {
-            self.right.as_ref().map(|right| right.min_pair())
+            trail.push_front(Side::Left);
         }
     }
 
-    // 返回第一个小于key的键值对,key可以不存在树中
-    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
-        if self.key < *key {
-            match self.right {
-                None => Some((&self.key, &self.value)),
-                Some(ref succ) => succ.predecessor(key).or(Some((&self.key, &self.value))),
+    // 当前节点右旋后，修正从当前节点出发的路径
+    fn right_rotate_trail(trail: &mut VecDeque<Side>) {
+        if trail.front() == Some(&Side::Left) {
+            // 原左孩子成为新的根节点，其右子树挂到原根节点的左边
+            trail.pop_front();
+            if trail.front() == Some(&Side::Right) {
+                trail.pop_front();
+                trail.push_front(Side::Left);
+                trail.push_front(Side::Right);
             }
-        } else if self.key > *key {
-            self.left.as_ref().and_then(|left| left.predecessor(key))
         } else {
-            self.left.as_ref().map(|left| left.max_pair())
+            trail.push_front(Side::Right);
         }
     }
 
-    // 前序遍历
-    pub fn prev_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            buf.push(node.key.clone());
-            Self::prev_order(&node.left, buf);
-            Self::prev_order(&node.right, buf);
+    //删除路径对应的节点并保持AVL树的性质，返回新的根节点以及被删除的键值对
+    pub fn remove_at(mut self, path: &[Side]) -> (Link<K, V>, (K, V)) {
+        match path.split_first() {
+            None => {
+                let root = self.delete_root();
+                (root, (self.key, self.value))
+            }
+            Some((side, rest)) => {
+                let child = match side {
+                    Side::Left => &mut self.left,
+                    Side::Right => &mut self.right,
+                };
+                let (node, pair) = child.take().expect("AVL broken").remove_at(rest);
+                *child = node;
+                (Some(self.update_node()), pair)
+            }
         }
     }
 
-    // 中序遍历
-    pub fn in_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            Self::in_order(&node.left, buf);
-            buf.push(node.key.clone());
-            Self::in_order(&node.right, buf);
+    // 返回第一个大于key的键值对,key可以不存在树中
+    pub fn successor<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Less => match self.left {
+                None => Some((&self.key, &self.value)),
+                Some(ref succ) => succ.successor::<Q, C>(key).or(Some((&self.key, &self.value))),
+            },
+            Ordering::Greater => self.right.as_ref().and_then(|right| right.successor::<Q, C>(key)),
+            Ordering::Equal => self.right.as_ref().map(|right| right.min_pair()),
         }
     }
 
-    // 后序遍历
-    pub fn post_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            Self::post_order(&node.left, buf);
-            Self::post_order(&node.right, buf);
-            buf.push(node.key.clone());
+    // 返回第一个小于key的键值对,key可以不存在树中
+    pub fn predecessor<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => match self.right {
+                None => Some((&self.key, &self.value)),
+                Some(ref succ) => succ.predecessor::<Q, C>(key).or(Some((&self.key, &self.value))),
+            },
+            Ordering::Less => self.left.as_ref().and_then(|left| left.predecessor::<Q, C>(key)),
+            Ordering::Equal => self.left.as_ref().map(|left| left.max_pair()),
         }
     }
 
-    // 层序遍历
-    pub fn level_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        let mut queue = VecDeque::new();
-        if let Some(node) = root {
-            queue.push_back(node);
+    // 返回第k小(从0开始)的键值对，k超出范围返回None
+    pub fn select(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut cur = self;
+        loop {
+            let left_size = Self::size(&cur.left);
+            if k < left_size {
+                cur = cur.left.as_ref()?;
+            } else if k == left_size {
+                return Some((&cur.key, &cur.value));
+            } else {
+                k -= left_size + 1;
+                cur = cur.right.as_ref()?;
+            }
         }
-        while !queue.is_empty() {
-            if let Some(node) = queue.pop_front() {
-                buf.push(node.key.clone());
-                if let Some(left) = node.left.as_ref() {
-                    queue.push_back(left);
-                }
-                if let Some(right) = node.right.as_ref() {
-                    queue.push_back(right);
-                }
+    }
+
+    // 返回树中小于key(inclusive为true时是不大于key)的键的个数，key可以不存在树中
+    pub fn count_below<Q: ?Sized, C: Compare<Q>>(root: &Link<K, V>, key: &Q, inclusive: bool) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        let mut count = 0;
+        let mut cur = root;
+        while let Some(node) = cur {
+            let order = C::compare(node.key.borrow(), key);
+            if order == Ordering::Less || (inclusive && order == Ordering::Equal) {
+                count += Self::size(&node.left) + 1;
+                cur = &node.right;
+            } else {
+                cur = &node.left;
             }
         }
+        count
+    }
+
+    // 返回当前节点的键值对
+    pub fn pair(&self) -> (&K, &V) {
+        (&self.key, &self.value)
+    }
+
+    // 返回当前节点的左右子树
+    pub fn children(&self) -> (&Link<K, V>, &Link<K, V>) {
+        (&self.left, &self.right)
     }
 
     // 返回查找的键值对的不可变借用
-    pub fn search_pair(&self, key: &K,) -> Option<(&K, &V)> {
-        if self.key < *key {
-            self.right
+    pub fn search_pair<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => self
+                .right
                 .as_ref()
-                .and_then(|right| right.search_pair(key))
-        } else if self.key > *key {
-            self.left.as_ref().and_then(|left| left.search_pair(key))
-        } else {
-            Some((&self.key, &self.value))
+                .and_then(|right| right.search_pair::<Q, C>(key)),
+            Ordering::Less => self.left.as_ref().and_then(|left| left.search_pair::<Q, C>(key)),
+            Ordering::Equal => Some((&self.key, &self.value)),
         }
     }
 
+    // 返回查找的值的可变借用
+    pub fn search_mut<Q: ?Sized, C: Compare<Q>>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => self.right.as_mut().and_then(|right| right.search_mut::<Q, C>(key)),
+            Ordering::Less => self.left.as_mut().and_then(|left| left.search_mut::<Q, C>(key)),
+            Ordering::Equal => Some(&mut self.value),
+        }
+    }
+
+    // 同时借出键、值的可变借用以及左右子树，供可变迭代器使用，键始终只读
+    pub fn parts_mut(&mut self) -> (&K, &mut V, &mut Link<K, V>, &mut Link<K, V>) {
+        (&self.key, &mut self.value, &mut self.left, &mut self.right)
+    }
+
     // 根据键查找对应的值
-    pub fn search(&self, key: &K) -> Option<&V> {
-        self.search_pair(key).map(|(_, v)| v)
+    pub fn search<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.search_pair::<Q, C>(key).map(|(_, v)| v)
     }
 
     // 返回AVL树中的最小键值对
@@ -303,53 +554,95 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
             .map_or((&self.key, &self.value), |right| right.max_pair())
     }
 
-    // 判断节点是否满足AVL树的性质
-    fn is_avl_node(&self) -> bool {
-        if self.is_leaf() {
-            return true;
+    // 检查以root为根的子树是否满足AVL树的性质，prev为中序遍历中上一个节点的键，
+    // 用于检查键的顺序。满足时返回子树的树高和节点个数
+    pub fn validate<'a, C: Compare<K>>(
+        root: &'a Link<K, V>,
+        prev: &mut Option<&'a K>,
+    ) -> Result<(usize, usize), InvariantViolation> {
+        let node = match root {
+            None => return Ok((0, 0)),
+            Some(node) => node,
+        };
+        let (left_height, left_size) = Self::validate::<C>(&node.left, prev)?;
+        if prev.is_some_and(|prev| C::compare(prev, &node.key) != Ordering::Less) {
+            return Err(InvariantViolation::KeyOrder);
         }
-        if !self.left.as_ref().map_or(true, |succ| succ.key < self.key) {
-            return false;
+        *prev = Some(&node.key);
+        let (right_height, right_size) = Self::validate::<C>(&node.right, prev)?;
+        let height = max(left_height, right_height) + 1;
+        if node.height as usize != height {
+            return Err(InvariantViolation::StaleHeight {
+                cached: node.height as usize,
+                actual: height,
+            });
         }
-        if !self.right.as_ref().map_or(true, |succ| succ.key > self.key) {
-            return false;
+        let size = left_size + right_size + 1;
+        if node.size != size {
+            return Err(InvariantViolation::StaleSize {
+                cached: node.size,
+                actual: size,
+            });
         }
-        let balance = self.diff_of_height();
-        if balance > 1 || balance < -1 {
-            return false;
+        if left_height.abs_diff(right_height) > 1 {
+            return Err(InvariantViolation::Unbalanced {
+                left: left_height,
+                right: right_height,
+            });
         }
-        true
+        Ok((height, size))
     }
-
-    // 判断是否为AVL树
-    pub fn is_avl_tree(root: &Link<K, V>) -> bool {
-        match root {
-            None => true,
-            Some(node) => {
-                if !node.is_avl_node() {
-                    return false;
+    // 按前序把以该节点为根的子树写成DOT格式，标签中注明树高，id为下一个可用的节点编号。
+    // 只有一个孩子时用一个点表示空的孩子，以便区分左右
+    pub fn write_dot(&self, out: &mut String, id: &mut usize)
+    where
+        K: Display,
+        V: Display,
+    {
+        let me = *id;
+        *id += 1;
+        let label = dot_escape(&format!("{}: {}", self.key, self.value));
+        out.push_str(&format!("    {} [label=\"{}\\nh={}\"];\n", me, label, self.height));
+        for child in [&self.left, &self.right].iter() {
+            match child {
+                Some(node) => {
+                    out.push_str(&format!("    {} -> {};\n", me, *id));
+                    node.write_dot(out, id);
+                }
+                None if self.left.is_some() || self.right.is_some() => {
+                    out.push_str(&format!("    nil{0} [shape=point];\n    {0} -> nil{0};\n", me));
                 }
-                Self::is_avl_tree(&node.left) && Self::is_avl_tree(&node.right)
+                None => {}
             }
         }
     }
 }
 
-impl<K: PartialOrd + ToString, V: ToString> ToString for Node<K, V> {
-    fn to_string(&self) -> String {
-        format!(
-            "[K: {}, V: {}, L: {}, R: {}]",
-            self.key.to_string(),
-            self.value.to_string(),
-            to_string(&self.left),
-            to_string(&self.right)
-        )
+impl<K, V> Node<K, V> {
+    // 把以该节点为根的树画成多行，label写出每个节点的键值对
+    pub fn draw(&self, f: &mut Formatter<'_>, label: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        draw_tree(f, self, &|node: &&Self, f: &mut Formatter<'_>| label(&node.key, &node.value, f), &|node: &&Self| {
+            if node.left.is_none() && node.right.is_none() {
+                return Vec::new();
+            }
+            vec![node.left.as_deref(), node.right.as_deref()]
+        })
     }
 }
 
-fn to_string<K: PartialOrd + ToString, V: ToString>(node: &Link<K, V>) -> String {
+impl<K: Display, V: Display> Display for Node<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[K: {}, V: {}, L: ", self.key, self.value)?;
+        fmt_link(&self.left, f)?;
+        write!(f, ", R: ")?;
+        fmt_link(&self.right, f)?;
+        write!(f, "]")
+    }
+}
+
+fn fmt_link<K: Display, V: Display>(node: &Link<K, V>, f: &mut Formatter<'_>) -> fmt::Result {
     match node {
-        None => "Ø".to_string(),
-        Some(box_node) => box_node.to_string(),
+        None => write!(f, "Ø"),
+        Some(box_node) => write!(f, "{}", box_node),
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use an_ok_avl_tree::AVLTree;
-    use std::collections::Bound;
+    use an_ok_avl_tree::{AVLTree, Entry};
+    use ordered_map::{Compare, InvariantViolation, Reverse};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, Bound};
 
     #[test]
     fn insert_delete() {
@@ -26,8 +28,8 @@ mod tests {
         tree.insert(9, 'i');
         tree.insert(8, 'h');
         assert!(tree.is_avl_tree());
-        tree.delete(5);
-        tree.delete(6);
+        tree.delete(&5);
+        tree.delete(&6);
         assert!(!tree.contains(&5));
         assert!(!tree.contains(&6));
         let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
@@ -194,6 +196,179 @@ mod tests {
         assert_eq!(res, vec![(&2, &'b'), (&3, &'c'), (&4, &'d')]);
     }
 
+    #[test]
+    fn entry() {
+        // 四种旋转(LL、RR、LR、RL)都会在填充空入口时发生，返回的借用必须指向新插入的值
+        let mut tree = AVLTree::new();
+        for key in [5, 3, 1, 7, 9, 4, 6, 8, 2, 10, 15, 12, 11, 13] {
+            let value = tree.entry(key).or_insert(0);
+            assert_eq!(*value, 0);
+            *value = key * 10;
+            assert!(tree.is_avl_tree());
+        }
+        for key in 1..=15 {
+            let value = tree.entry(key).and_modify(|v| *v += 1).or_insert_with(|| 0);
+            if key == 14 {
+                assert_eq!(*value, 0);
+            } else {
+                assert_eq!(*value, key * 10 + 1);
+            }
+        }
+        assert!(tree.is_avl_tree());
+        assert_eq!(*tree.entry(14).key(), 14);
+
+        match tree.entry(3) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), &3);
+                assert_eq!(entry.insert(33), 31);
+                assert_eq!(entry.remove_entry(), (3, 33));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match tree.entry(3) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), 3),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert!(!tree.contains(&3));
+        for key in [5, 12, 1, 9] {
+            if let Entry::Occupied(entry) = tree.entry(key) {
+                assert_eq!(entry.remove(), key * 10 + 1);
+            }
+            assert!(tree.is_avl_tree());
+        }
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![2, 4, 6, 7, 8, 10, 11, 13, 14, 15]);
+    }
+
+    #[test]
+    fn mutable_access() {
+        let mut tree = AVLTree::new();
+        for key in 1..=20 {
+            tree.insert(key, key);
+        }
+        assert_eq!(tree.get_mut(&21), None);
+        if let Some(value) = tree.get_mut(&7) {
+            *value = 700;
+        }
+        assert_eq!(tree.get(&7), Some(&700));
+
+        let keys: Vec<i32> = tree.iter_mut().map(|(key, value)| {
+            *value += 1;
+            *key
+        }).collect();
+        assert_eq!(keys, (1..=20).collect::<Vec<i32>>());
+        for value in tree.values_mut() {
+            *value *= 2;
+        }
+        assert_eq!(tree.get(&7), Some(&1402));
+        assert_eq!(tree.get(&20), Some(&42));
+
+        for (_, value) in tree.range_pair_iter_mut(Bound::Included(&5), Bound::Excluded(&8)) {
+            *value = 0;
+        }
+        let res: Vec<(&i32, &i32)> = tree
+            .range_pair_iter(Bound::Included(4), Bound::Included(8))
+            .collect();
+        assert_eq!(res, vec![(&4, &10), (&5, &0), (&6, &0), (&7, &0), (&8, &18)]);
+        let keys: Vec<i32> = tree
+            .range_pair_iter_mut(Bound::Excluded(&17), Bound::Unbounded)
+            .map(|(key, _)| *key)
+            .collect();
+        assert_eq!(keys, vec![18, 19, 20]);
+        assert_eq!(tree.range_pair_iter_mut(Bound::Excluded(&20), Bound::Unbounded).next(), None);
+        for (_, value) in tree.range_mut(10..=12) {
+            *value = -1;
+        }
+        let res: Vec<i32> = tree.range(9..=13).map(|(_, v)| *v).collect();
+        assert_eq!(res, vec![20, -1, -1, -1, 28]);
+        let mut iter = tree.range_mut(3..18);
+        assert_eq!(iter.len(), 15);
+        iter.next();
+        assert_eq!(iter.len(), 14);
+        assert_eq!(iter.last().map(|(key, _)| *key), Some(17));
+        assert_eq!(tree.range_mut((Bound::Included(8), Bound::Excluded(3))).next(), None);
+        assert_eq!(tree.range_mut(21..).next(), None);
+        assert!(tree.is_avl_tree());
+
+        let mut empty: AVLTree<i32, i32> = AVLTree::new();
+        assert_eq!(empty.iter_mut().next(), None);
+        assert_eq!(empty.values_mut().next(), None);
+    }
+
+    #[test]
+    fn lazy_traverse() {
+        let mut tree = AVLTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        let res: Vec<(&i32, &i32)> = tree.inorder_iter().take(3).collect();
+        assert_eq!(res, vec![(&0, &0), (&1, &2), (&2, &4)]);
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+
+        // 前序遍历的第一个、后序遍历的最后一个以及层序遍历的第一个都是根节点
+        let root = tree.preorder_iter().next();
+        assert_eq!(root, tree.levelorder_iter().next());
+        assert_eq!(root, tree.postorder_iter().last());
+        for iter in [tree.preorder_iter(), tree.postorder_iter(), tree.levelorder_iter()] {
+            let mut keys: Vec<i32> = iter.map(|(k, _)| *k).collect();
+            keys.sort();
+            assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+        }
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut tree = AVLTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        tree.insert(1, 1);
+        assert_eq!(tree.len(), 1000);
+        for key in (0..1000).step_by(3) {
+            tree.delete(&key);
+        }
+        tree.delete(&1000);
+        let expected: Vec<i32> = (0..1000).filter(|key| key % 3 != 0).collect();
+        assert_eq!(tree.len(), expected.len());
+        let keys: Vec<i32> = tree.inorder_iter().rev().map(|(k, _)| *k).collect();
+        assert_eq!(keys, expected.iter().rev().cloned().collect::<Vec<i32>>());
+
+        // 两端交替迭代，在中间相遇后两端都结束
+        let mut iter = tree.inorder_iter();
+        let n = expected.len();
+        for i in 0..n / 2 {
+            assert_eq!(iter.len(), n - 2 * i);
+            assert_eq!(iter.next().map(|(k, _)| *k), Some(expected[i]));
+            assert_eq!(iter.next_back().map(|(k, _)| *k), Some(expected[n - 1 - i]));
+        }
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(tree.preorder_iter().len(), n);
+        assert_eq!(tree.postorder_iter().len(), n);
+        assert_eq!(tree.levelorder_iter().len(), n);
+
+        // 范围迭代器同样可以从两端迭代
+        let res: Vec<i32> = tree
+            .range_pair_iter(Bound::Included(100), Bound::Excluded(200))
+            .rev()
+            .map(|(k, _)| *k)
+            .collect();
+        let range: Vec<i32> = expected.iter().cloned().filter(|k| (100..200).contains(k)).collect();
+        assert_eq!(res, range.iter().rev().cloned().collect::<Vec<i32>>());
+        let mut iter = tree.range_pair_iter(Bound::Excluded(100), Bound::Included(104));
+        assert_eq!(iter.next_back(), Some((&104, &208)));
+        assert_eq!(iter.next(), Some((&101, &202)));
+        assert_eq!(iter.next_back(), Some((&103, &206)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
     #[test]
     fn to_string() {
         let mut tree = AVLTree::new();
@@ -203,4 +378,350 @@ mod tests {
         tree.insert(4, 'd');
         assert_eq!(tree.to_string(), String::from("[K: 2, V: b, L: [K: 1, V: a, L: Ø, R: Ø], R: [K: 3, V: c, L: Ø, R: [K: 4, V: d, L: Ø, R: Ø]]]"))
     }
+
+    #[test]
+    fn to_dot() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.to_dot(), "digraph AVLTree {\n    ordering=out;\n    node [shape=circle];\n}\n");
+        tree.insert(3, 'c');
+        tree.insert(2, 'b');
+        tree.insert(1, 'a');
+        tree.insert(4, 'd');
+        let expected = [
+            "digraph AVLTree {",
+            "    ordering=out;",
+            "    node [shape=circle];",
+            r#"    0 [label="2: b\nh=3"];"#,
+            "    0 -> 1;",
+            r#"    1 [label="1: a\nh=1"];"#,
+            "    0 -> 2;",
+            r#"    2 [label="3: c\nh=2"];"#,
+            "    nil2 [shape=point];",
+            "    2 -> nil2;",
+            "    2 -> 3;",
+            r#"    3 [label="4: d\nh=1"];"#,
+            "}",
+        ];
+        assert_eq!(tree.to_dot(), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn pretty_print() {
+        let mut tree = AVLTree::new();
+        assert_eq!(format!("{:#}", tree), "Ø");
+        assert_eq!(format!("{:?}", tree), "{}");
+        tree.insert(3, 'c');
+        tree.insert(2, 'b');
+        tree.insert(1, 'a');
+        tree.insert(4, 'd');
+        let expected = ["2: b", "├── 1: a", "└── 3: c", "    ├── Ø", "    └── 4: d"];
+        assert_eq!(format!("{:#}", tree), expected.join("\n"));
+        assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'b', 3: 'c', 4: 'd'}");
+        let expected = ["2: 'b'", "├── 1: 'a'", "└── 3: 'c'", "    ├── Ø", "    └── 4: 'd'"];
+        assert_eq!(format!("{:#?}", tree), expected.join("\n"));
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = AVLTree::new();
+        let mut expected = BTreeSet::new();
+        // 37与1000互质，键以打乱的顺序插入，只保留偶数键
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            if key % 2 == 0 {
+                tree.insert(key, key);
+                expected.insert(key);
+            }
+        }
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Included(101), Bound::Excluded(199)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Excluded(500), Bound::Excluded(502)),
+            (Bound::Included(-5), Bound::Excluded(0)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            let want: Vec<i32> = expected.range((lower, upper)).cloned().collect();
+            let res: Vec<i32> = tree.range((lower, upper)).map(|(k, _)| *k).collect();
+            assert_eq!(res, want);
+            let res: Vec<i32> = tree.range((lower, upper)).rev().map(|(k, _)| *k).collect();
+            assert_eq!(res, want.iter().rev().cloned().collect::<Vec<i32>>());
+            // 两端交替迭代，相遇后两端都结束
+            let mut iter = tree.range((lower, upper));
+            let (mut front, mut back) = (0, want.len());
+            while front < back {
+                assert_eq!(iter.len(), back - front);
+                assert_eq!(iter.next().map(|(k, _)| *k), Some(want[front]));
+                front += 1;
+                if front < back {
+                    back -= 1;
+                    assert_eq!(iter.next_back().map(|(k, _)| *k), Some(want[back]));
+                }
+            }
+            assert_eq!(iter.next().map(|(k, _)| *k), None);
+            assert_eq!(iter.next_back().map(|(k, _)| *k), None);
+            assert_eq!(iter.len(), 0);
+        }
+        let res: Vec<i32> = tree.range(3..=9).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![4, 6, 8]);
+    }
+
+    #[test]
+    fn order_statistics() {
+        let mut tree = AVLTree::new();
+        let mut expected = BTreeSet::new();
+        // 插入、删除以及通过入口增删穿插进行，覆盖各种旋转
+        for i in 0..2000 {
+            let key = i * 37 % 1000;
+            match i % 5 {
+                0 | 1 => {
+                    tree.insert(key, key);
+                    expected.insert(key);
+                }
+                2 => {
+                    tree.entry(key).or_insert(key);
+                    expected.insert(key);
+                }
+                3 => {
+                    tree.delete(&(key / 2));
+                    expected.remove(&(key / 2));
+                }
+                _ => {
+                    if let Entry::Occupied(entry) = tree.entry(key / 3) {
+                        entry.remove();
+                    }
+                    expected.remove(&(key / 3));
+                }
+            }
+        }
+        assert!(tree.is_avl_tree());
+        assert_eq!(tree.len(), expected.len());
+        for (i, key) in expected.iter().enumerate() {
+            assert_eq!(tree.select(i), Some((key, key)));
+            assert_eq!(tree.rank(key), i);
+            assert_eq!(tree.rank(&(key + 1)), expected.range(..key + 1).count());
+        }
+        assert_eq!(tree.select(expected.len()), None);
+        assert_eq!(tree.rank(&-1), 0);
+        assert_eq!(tree.rank(&1000), expected.len());
+
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            assert_eq!(tree.count_range((lower, upper)), expected.range((lower, upper)).count());
+        }
+        assert_eq!(tree.count_range((Bound::Included(600), Bound::Excluded(400))), 0);
+    }
+
+    #[test]
+    fn split_off_append() {
+        let build = || {
+            let mut tree = AVLTree::new();
+            for i in 0..1000 {
+                let key = i * 37 % 1000;
+                tree.insert(key, key);
+            }
+            tree
+        };
+        for &at in [-1, 0, 1, 137, 500, 512, 999, 1000, 2000].iter() {
+            let mut tree = build();
+            let mut other = tree.split_off(&at);
+            let split = at.clamp(0, 1000) as usize;
+            assert_eq!(tree.len(), split);
+            assert_eq!(other.len(), 1000 - split);
+            assert!(tree.is_empty() || tree.is_avl_tree());
+            assert!(other.is_empty() || other.is_avl_tree());
+            let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+            assert_eq!(keys, (0..split as i32).collect::<Vec<i32>>());
+            let keys: Vec<i32> = other.inorder_iter().map(|(k, _)| *k).collect();
+            assert_eq!(keys, (split as i32..1000).collect::<Vec<i32>>());
+
+            tree.append(&mut other);
+            assert!(other.is_empty());
+            assert_eq!(tree.len(), 1000);
+            assert!(tree.is_empty() || tree.is_avl_tree());
+            // 连接后的树可以继续增删
+            for key in (0..1000).step_by(7) {
+                tree.delete(&key);
+            }
+            tree.insert(2000, 2000);
+            assert!(tree.is_empty() || tree.is_avl_tree());
+            assert_eq!(tree.select(0), Some((&1, &1)));
+            assert_eq!(tree.rank(&2000), tree.len() - 1);
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let sets: Vec<BTreeSet<i32>> = vec![
+            BTreeSet::new(),
+            (0..100).collect(),
+            (0..100).filter(|key| key % 3 == 0).collect(),
+            (50..150).filter(|key| key % 2 == 0).collect(),
+            (200..210).collect(),
+        ];
+        let build = |set: &BTreeSet<i32>| {
+            let mut tree = AVLTree::new();
+            for key in set.iter() {
+                tree.insert(*key, *key * 10);
+            }
+            tree
+        };
+        // 检查值与键对应，返回键
+        let key_of = |(key, value): (&i32, &i32)| {
+            assert_eq!(*value, *key * 10);
+            *key
+        };
+        let tree_keys = |tree: AVLTree<i32, i32>| -> Vec<i32> { tree.inorder_iter().map(|(k, _)| *k).collect() };
+        for x in sets.iter() {
+            for y in sets.iter() {
+                let (a, b) = (build(x), build(y));
+                let union: Vec<i32> = x.union(y).cloned().collect();
+                let intersection: Vec<i32> = x.intersection(y).cloned().collect();
+                let difference: Vec<i32> = x.difference(y).cloned().collect();
+                let symmetric_difference: Vec<i32> = x.symmetric_difference(y).cloned().collect();
+                assert_eq!(a.union(&b).map(key_of).collect::<Vec<i32>>(), union);
+                assert_eq!(a.intersection(&b).map(key_of).collect::<Vec<i32>>(), intersection);
+                assert_eq!(a.difference(&b).map(key_of).collect::<Vec<i32>>(), difference);
+                assert_eq!(a.symmetric_difference(&b).map(key_of).collect::<Vec<i32>>(), symmetric_difference);
+                assert_eq!(tree_keys(&a | &b), union);
+                assert_eq!(tree_keys(&a & &b), intersection);
+                assert_eq!(tree_keys(&a - &b), difference);
+                assert_eq!(tree_keys(&a ^ &b), symmetric_difference);
+            }
+        }
+
+        // 两棵树都有的键取左边树的值
+        let (mut a, mut b) = (AVLTree::new(), AVLTree::new());
+        a.insert(1, 'a');
+        b.insert(1, 'b');
+        b.insert(2, 'b');
+        assert_eq!(a.union(&b).collect::<Vec<_>>(), vec![(&1, &'a'), (&2, &'b')]);
+        assert_eq!((&a & &b).get(&1), Some(&'a'));
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        for n in 0..70 {
+            let tree = AVLTree::from_sorted_iter((0..n).map(|key| (key, key * 2)));
+            assert_eq!(tree.len(), n as usize);
+            assert_eq!(tree.is_avl_tree(), n > 0);
+            assert!(tree.inorder_iter().map(|(k, v)| (*k, *v)).eq((0..n).map(|key| (key, key * 2))));
+            // 完全平衡：根节点是中位数
+            assert_eq!(tree.preorder_iter().next().map(|(k, _)| *k), if n > 0 { Some((n - 1) / 2) } else { None });
+        }
+        // 无序输入先排序，重复的键保留最后一个值
+        let pairs = vec![(5, 'e'), (1, 'a'), (3, 'c'), (1, 'z'), (4, 'd'), (2, 'b')];
+        let tree = AVLTree::from_sorted_iter(pairs);
+        assert!(tree.is_avl_tree());
+        let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
+        assert_eq!(res, vec![(&1, &'z'), (&2, &'b'), (&3, &'c'), (&4, &'d'), (&5, &'e')]);
+        let mut tree = tree;
+        tree.insert(0, 'o');
+        tree.delete(&3);
+        assert!(tree.is_avl_tree());
+        assert_eq!(tree.len(), 5);
+    }
+    // 按绝对值比较的整数键，-3与3视为同一个键
+    struct Abs;
+
+    impl Compare<i32> for Abs {
+        fn compare(a: &i32, b: &i32) -> Ordering {
+            a.abs().cmp(&b.abs())
+        }
+    }
+
+    #[test]
+    fn comparator() {
+        let mut tree: AVLTree<i32, i32, Reverse> = AVLTree::with_comparator();
+        for key in 0..20 {
+            tree.insert(key, key * 10);
+        }
+        assert!(tree.is_avl_tree());
+        let res: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).take(3).collect();
+        assert_eq!(res, vec![19, 18, 17]);
+        assert_eq!(tree.select(0), Some((&19, &190)));
+        assert_eq!(tree.rank(&15), 4);
+        assert_eq!(tree.count_range((Bound::Included(15), Bound::Included(10))), 6);
+        let res: Vec<i32> = tree.range((Bound::Included(12), Bound::Excluded(9))).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![12, 11, 10]);
+        let low = tree.split_off(&9);
+        assert_eq!(tree.max_pair(), Some((&10, &100)));
+        assert_eq!(low.min_pair(), Some((&9, &90)));
+        let evens: AVLTree<i32, i32, Reverse> = AVLTree::from_sorted_iter_with_comparator((0..20).map(|key| (key * 2, key)));
+        let res: Vec<i32> = (&tree & &evens).inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![18, 16, 14, 12, 10]);
+
+        let mut tree: AVLTree<i32, char, Abs> = AVLTree::with_comparator();
+        tree.insert(-3, 'a');
+        tree.insert(2, 'b');
+        tree.insert(3, 'c');
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get(&-3), Some(&'c'));
+        assert_eq!(tree.successor(&-2), Some((&-3, &'c')));
+        tree.delete(&-2);
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn borrowed_lookup() {
+        let mut tree = AVLTree::new();
+        for word in ["b", "a", "c"].iter() {
+            tree.insert(word.to_string(), word.len());
+        }
+        assert_eq!(tree.get("a"), Some(&1));
+        assert_eq!(tree.get_or("z", &0), &0);
+        assert!(tree.contains("c"));
+        *tree.get_mut("b").unwrap() += 1;
+        assert_eq!(tree.get_pair("b"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.successor("a"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.predecessor("b"), Some((&String::from("a"), &1)));
+        assert_eq!(tree.rank("c"), 2);
+        let bounds: (Bound<&str>, Bound<&str>) = (Bound::Excluded("a"), Bound::Unbounded);
+        assert_eq!(tree.count_range::<str, _>(bounds), 2);
+        let res: Vec<&String> = tree.range::<str, _>(bounds).map(|(k, _)| k).collect();
+        assert_eq!(res, vec!["b", "c"]);
+        let other = tree.split_off("c");
+        assert_eq!(other.len(), 1);
+        tree.delete("a");
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn validate() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.validate(), Ok(()));
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in (0..1000).step_by(3) {
+            tree.delete(&key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in 0..100 {
+            *tree.entry(key).or_insert(0) += 1;
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        let mut other = tree.split_off(&500);
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(other.validate(), Ok(()));
+        tree.append(&mut other);
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(other.validate(), Ok(()));
+
+        let violation = InvariantViolation::Unbalanced { left: 3, right: 1 };
+        assert_eq!(violation.to_string(), "左右子树的高度差超过1：左边3，右边1");
+    }
 }
@@ -1,6 +1,7 @@
 use an_ok_stack::List as Stack;
 use an_unsafe_queue::List as Queue;
-use std::fmt::Debug;
+use ordered_map::{dot_escape, draw_tree};
+use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(Clone)]
 pub struct BinaryTree<T> {
@@ -172,17 +173,17 @@ impl<T: Debug + PartialEq + Clone> BinaryTree<T> {
     pub fn level_order(&self) -> Vec<T>{
         let mut res = Vec::new();
         let mut queue = Queue::new();
-        if self.root.is_some() {
-            queue.push(self.root.as_ref().unwrap());
+        if let Some(root) = self.root.as_ref() {
+            queue.push(root);
         }
         while !queue.is_empty() {
             if let Some(node) = queue.pop() {
                 res.push(node.elem.clone());
-                if node.left.is_some() {
-                    queue.push(node.left.as_ref().unwrap());
+                if let Some(left) = node.left.as_ref() {
+                    queue.push(left);
                 }
-                if node.right.is_some() {
-                    queue.push(node.right.as_ref().unwrap());
+                if let Some(right) = node.right.as_ref() {
+                    queue.push(right);
                 }
             }
         }
@@ -233,7 +234,7 @@ impl<T: Debug + PartialEq + Clone> BinaryTree<T> {
         }
         let left_height = Self::tree_height_help(&root.as_ref().unwrap().left);
         let right_height = Self::tree_height_help(&root.as_ref().unwrap().right);
-        return if left_height > right_height {
+        if left_height > right_height {
             left_height + 1
         } else {
             right_height + 1
@@ -287,27 +288,27 @@ impl<T: Debug + PartialEq + Clone> BinaryTree<T> {
     //判断二叉树是否为完全二叉树
     pub fn is_complete_tree(&self) -> bool {
         let mut queue = Queue::new();
-        if self.root.is_some() {
-            queue.push(self.root.as_ref().unwrap());
+        if let Some(root) = self.root.as_ref() {
+            queue.push(root);
         }
         let mut flag = true;
         while !queue.is_empty() {
             let head = queue.pop().unwrap();
-            if head.left.is_some() {
+            if let Some(left) = head.left.as_ref() {
                 if !flag {
                     return false;
                 }
-                queue.push(head.left.as_ref().unwrap());
+                queue.push(left);
             }
             else {
                 flag = false;
             }
 
-            if head.right.is_some() {
+            if let Some(right) = head.right.as_ref() {
                 if !flag {
                     return false;
                 }
-                queue.push(head.right.as_ref().unwrap());
+                queue.push(right);
             }
             else {
                 flag = false;
@@ -316,6 +317,34 @@ impl<T: Debug + PartialEq + Clone> BinaryTree<T> {
         true
     }
 
+    //生成DOT格式的图，可以保存为文件后用Graphviz绘制：dot -Tpng tree.dot -o tree.png
+    //只有一个孩子的节点用一个点表示空的孩子，以便区分左右
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph BinaryTree {\n    ordering=out;\n    node [shape=circle];\n");
+        Self::to_dot_help(&self.root, &mut out, &mut 0);
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_dot_help(root: &Link<T>, out: &mut String, id: &mut usize) {
+        if root.is_none() {
+            return;
+        }
+        let node = root.as_ref().unwrap();
+        let me = *id;
+        *id += 1;
+        out.push_str(&format!("    {} [label=\"{}\"];\n", me, dot_escape(&format!("{:?}", node.elem))));
+        for child in [&node.left, &node.right].iter() {
+            if child.is_some() {
+                out.push_str(&format!("    {} -> {};\n", me, *id));
+                Self::to_dot_help(child, out, id);
+            }
+            else if node.left.is_some() || node.right.is_some() {
+                out.push_str(&format!("    nil{0} [shape=point];\n    {0} -> nil{0};\n", me));
+            }
+        }
+    }
+
     pub fn destroy_tree(self) {
         Self::destroy_tree_help(self.root);
     }
@@ -330,6 +359,51 @@ impl<T: Debug + PartialEq + Clone> BinaryTree<T> {
     }
 }
 
+impl<T> BinaryTree<T> {
+    //{}和{:?}打印成一行嵌套的形式，{:#}和{:#?}把树画成多行，elem写出节点中的元素
+    fn fmt_with(&self, f: &mut Formatter<'_>, elem: &dyn Fn(&T, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        match self.root.as_deref() {
+            None if f.alternate() => write!(f, "Ø"),
+            None => write!(f, "None"),
+            Some(root) if f.alternate() => {
+                draw_tree(f, root, &|node: &&Node<T>, f: &mut Formatter<'_>| elem(&node.elem, f), &|node: &&Node<T>| {
+                    if node.left.is_none() && node.right.is_none() {
+                        return Vec::new();
+                    }
+                    vec![node.left.as_deref(), node.right.as_deref()]
+                })
+            }
+            Some(_) => Self::fmt_help(&self.root, f, elem),
+        }
+    }
+
+    fn fmt_help(root: &Link<T>, f: &mut Formatter<'_>, elem: &dyn Fn(&T, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        if root.is_none() {
+            return write!(f, "Ø");
+        }
+        let node = root.as_ref().unwrap();
+        write!(f, "[E: ")?;
+        elem(&node.elem, f)?;
+        write!(f, ", L: ")?;
+        Self::fmt_help(&node.left, f, elem)?;
+        write!(f, ", R: ")?;
+        Self::fmt_help(&node.right, f, elem)?;
+        write!(f, "]")
+    }
+}
+
+impl<T: Display> Display for BinaryTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &|elem, f| write!(f, "{}", elem))
+    }
+}
+
+impl<T: Debug> Debug for BinaryTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &|elem, f| write!(f, "{:?}", elem))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::BinaryTree;
@@ -387,16 +461,16 @@ mod tests {
         assert_eq!(tree.get_level_node_size(3), Some(3));
         assert_eq!(tree.get_level_node_size(4), None);
 
-        assert_eq!(tree.find(0), false);
-        assert_eq!(tree.find(1), true);
-        assert_eq!(tree.find(2), true);
-        assert_eq!(tree.find(3), true);
-        assert_eq!(tree.find(4), true);
-        assert_eq!(tree.find(5), true);
-        assert_eq!(tree.find(6), true);
-        assert_eq!(tree.find(7), false);
+        assert!(!tree.find(0));
+        assert!(tree.find(1));
+        assert!(tree.find(2));
+        assert!(tree.find(3));
+        assert!(tree.find(4));
+        assert!(tree.find(5));
+        assert!(tree.find(6));
+        assert!(!tree.find(7));
 
-        assert_eq!(tree.is_complete_tree(), false);
+        assert!(!tree.is_complete_tree());
     }
 
     #[test]
@@ -404,7 +478,38 @@ mod tests {
         let array = [1, 2, 4,i32::MIN, i32::MIN, 5, i32::MIN, i32::MIN
             ,3, 6, i32::MIN, i32::MIN, 7, i32::MIN, i32::MIN];
         let tree = BinaryTree::new(&array, i32::MIN);
-        assert_eq!(tree.is_complete_tree(), true);
+        assert!(tree.is_complete_tree());
         tree.destroy_tree();
     }
+
+    #[test]
+    fn to_dot() {
+        let array = ['A', 'B', '#', 'D', '#', '#', 'C' ,'#', '#'];
+        let tree = BinaryTree::new(&array, '#');
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph BinaryTree {"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    0 [label=\"'A'\"];\n    0 -> 1;\n    1 [label=\"'B'\"];\n"));
+        // B只有右孩子D，左边用一个点表示空的孩子
+        assert!(dot.contains("    nil1 [shape=point];\n    1 -> nil1;\n    1 -> 2;\n    2 [label=\"'D'\"];\n"));
+        assert!(dot.contains("    0 -> 3;\n    3 [label=\"'C'\"];\n"));
+
+        let array = ["say \"hi\"".to_string(), "no".to_string(), "no".to_string()];
+        let tree = BinaryTree::new(&array, "no".to_string());
+        assert!(tree.to_dot().contains(r#"0 [label="\"say \\\"hi\\\"\""];"#));
+    }
+
+    #[test]
+    fn display() {
+        let array = ['A', 'B', '#', 'D', '#', '#', 'C' ,'#', '#'];
+        let tree = BinaryTree::new(&array, '#');
+        assert_eq!(tree.to_string(), "[E: A, L: [E: B, L: Ø, R: [E: D, L: Ø, R: Ø]], R: [E: C, L: Ø, R: Ø]]");
+        assert_eq!(format!("{:?}", tree), "[E: 'A', L: [E: 'B', L: Ø, R: [E: 'D', L: Ø, R: Ø]], R: [E: 'C', L: Ø, R: Ø]]");
+        assert_eq!(format!("{:#}", tree), "A\n├── B\n│   ├── Ø\n│   └── D\n└── C");
+        assert_eq!(format!("{:#?}", tree), "'A'\n├── 'B'\n│   ├── Ø\n│   └── 'D'\n└── 'C'");
+
+        let tree = BinaryTree::new(&['#'], '#');
+        assert_eq!(tree.to_string(), "None");
+        assert_eq!(format!("{:#}", tree), "Ø");
+    }
 }
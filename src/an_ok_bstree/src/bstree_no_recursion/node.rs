@@ -1,3 +1,7 @@
+use ordered_map::{dot_escape, draw_tree, Compare, InvariantViolation};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
 
 pub type Link<K, V> = Option<Box<Node<K, V>>>;
 
@@ -15,7 +19,7 @@ impl<K: PartialEq, V> PartialEq for Node<K, V> {
 }
 
 
-impl<K: PartialOrd + Clone, V> Node<K, V> {
+impl<K, V> Node<K, V> {
     pub fn new(key: K, value: V) -> Self {
         Node {
             key,
@@ -56,8 +60,83 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         new_root
     }
 
+    // 统计以link为根的树中节点的个数
+    pub fn count(link: &Link<K, V>) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<&Node<K, V>> = link.iter().map(|node| node.as_ref()).collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+        count
+    }
+
+    // 用栈按中序遍历以link为根的树，检查键是否严格递增，返回节点的个数
+    pub fn validate<C: Compare<K>>(link: &Link<K, V>) -> Result<usize, InvariantViolation> {
+        let mut count = 0;
+        let mut prev: Option<&K> = None;
+        let mut stack: Vec<&Node<K, V>> = Vec::new();
+        let mut cur = link.as_deref();
+        loop {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = match stack.pop() {
+                None => return Ok(count),
+                Some(node) => node,
+            };
+            if prev.is_some_and(|prev| C::compare(prev, &node.key) != Ordering::Less) {
+                return Err(InvariantViolation::KeyOrder);
+            }
+            prev = Some(&node.key);
+            count += 1;
+            cur = node.right.as_deref();
+        }
+    }
+
+    // 查找key所在的链接位置，key不存在时返回应当插入的空链接
+    pub fn search_link<'a, Q: ?Sized, C: Compare<Q>>(mut link: &'a mut Link<K, V>, key: &Q) -> &'a mut Link<K, V>
+    where
+        K: Borrow<Q>,
+    {
+        loop {
+            match link.as_deref().map(|node| C::compare(key, node.key.borrow())) {
+                Some(Ordering::Less) => link = &mut link.as_mut().unwrap().left,
+                Some(Ordering::Greater) => link = &mut link.as_mut().unwrap().right,
+                _ => return link,
+            }
+        }
+    }
+
+    // 返回当前节点的键值对
+    pub fn pair(&self) -> (&K, &V) {
+        (&self.key, &self.value)
+    }
+
+    // 返回当前节点的左右子树
+    pub fn children(&self) -> (&Link<K, V>, &Link<K, V>) {
+        (&self.left, &self.right)
+    }
+
+    // 返回当前节点的键和值的可变借用
+    pub fn pair_mut(&mut self) -> (&K, &mut V) {
+        (&self.key, &mut self.value)
+    }
+
+    // 同时借出键、值的可变借用以及左右子树，供可变迭代器使用，键始终只读
+    pub fn parts_mut(&mut self) -> (&K, &mut V, &mut Link<K, V>, &mut Link<K, V>) {
+        (&self.key, &mut self.value, &mut self.left, &mut self.right)
+    }
+
+    // 拆出当前节点的键值对
+    pub fn into_pair(self) -> (K, V) {
+        (self.key, self.value)
+    }
+
     //删除当前节点，并返回新的根节点
-    pub fn delete_root(mut self) -> Link<K, V> {
+    pub fn delete_root(&mut self) -> Link<K, V> {
         // 二叉搜索树树删除节点的三种情况：
         // 1.如果是叶子节点，则直接删除
         // 2.如果待删除节点只有左子树或只有右子树，删除该节点，然后将左子树或右子树移动到该节点
@@ -69,5 +148,68 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
             (Some(left), Some(right)) => Some(Self::combine_two_subtrees(*left, *right)),
         }
     }
+
+    // 按前序把以link为根的树写成DOT格式，用栈代替递归。
+    // 只有一个孩子时用一个点表示空的孩子，以便区分左右
+    pub fn write_dot(link: &Link<K, V>, out: &mut String)
+    where
+        K: Display,
+        V: Display,
+    {
+        let mut id = 0;
+        // 栈中保存双亲的编号和孩子，孩子为None表示需要画出的空孩子
+        let mut stack = Vec::new();
+        if let Some(root) = link.as_deref() {
+            stack.push((None, Some(root)));
+        }
+        while let Some((parent, child)) = stack.pop() {
+            match child {
+                Some(node) => {
+                    if let Some(parent) = parent {
+                        out.push_str(&format!("    {} -> {};\n", parent, id));
+                    }
+                    let label = dot_escape(&format!("{}: {}", node.key, node.value));
+                    out.push_str(&format!("    {} [label=\"{}\"];\n", id, label));
+                    if node.left.is_some() || node.right.is_some() {
+                        stack.push((Some(id), node.right.as_deref()));
+                        stack.push((Some(id), node.left.as_deref()));
+                    }
+                    id += 1;
+                }
+                None => {
+                    let parent = parent.unwrap();
+                    out.push_str(&format!("    nil{0} [shape=point];\n    {0} -> nil{0};\n", parent));
+                }
+            }
+        }
+    }
 }
 
+impl<K, V> Node<K, V> {
+    // 把以该节点为根的树画成多行，label写出每个节点的键值对
+    pub fn draw(&self, f: &mut Formatter<'_>, label: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        draw_tree(f, self, &|node: &&Self, f: &mut Formatter<'_>| label(&node.key, &node.value, f), &|node: &&Self| {
+            if node.left.is_none() && node.right.is_none() {
+                return Vec::new();
+            }
+            vec![node.left.as_deref(), node.right.as_deref()]
+        })
+    }
+}
+
+impl<K: Display, V: Display> Display for Node<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[K: {}, V: {}, L: ", self.key, self.value)?;
+        fmt_link(&self.left, f)?;
+        write!(f, ", R: ")?;
+        fmt_link(&self.right, f)?;
+        write!(f, "]")
+    }
+}
+
+fn fmt_link<K: Display, V: Display>(node: &Link<K, V>, f: &mut Formatter<'_>) -> fmt::Result {
+    match node {
+        None => write!(f, "Ø"),
+        Some(box_node) => write!(f, "{}", box_node),
+    }
+}
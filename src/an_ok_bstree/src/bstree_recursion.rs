@@ -1,3 +1,3 @@
-mod node;
+pub(crate) mod node;
 mod bstree;
 pub use bstree::BSTree;
\ No newline at end of file
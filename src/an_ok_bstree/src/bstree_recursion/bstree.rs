@@ -1,28 +1,186 @@
-use std::collections::VecDeque;
 use crate::bstree_recursion::node::{Link, Node};
-use crate::iterator::TraverseIter;
+use crate::entry::Entry;
+use crate::iterator::{Iter, IterMut, Order, RangePairIter, RangePairIterMut, TraverseIter, ValuesMut};
+use ordered_map::{Compare, InvariantViolation, Natural, OrderedMap};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
 
-pub struct BSTree<K, V> {
+/// 二叉查找树，键的顺序由比较器C决定，默认按`Ord`比较
+pub struct BSTree<K, V, C = Natural> {
     root: Link<K, V>,
+    len: usize,
+    marker: PhantomData<C>,
 }
 
-impl<K: PartialOrd + Clone, V> Default for BSTree<K, V> {
+impl<K, V, C: Compare<K>> Default for BSTree<K, V, C> {
     fn default() -> Self {
-        Self::new()
+        Self::with_comparator()
     }
 }
 
-impl<K: PartialOrd + Clone, V> BSTree<K, V> {
-    /// 构建一棵空的二叉查找树
+impl<K: Clone, V, C: Compare<K>> OrderedMap<K, V> for BSTree<K, V, C> {
+    type TraverseIter<'a> = TraverseIter<'a, K, V> where Self: 'a;
+    type Iter<'a> = Iter<'a, K, V> where Self: 'a;
+
+    fn new() -> Self {
+        BSTree::with_comparator()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        BSTree::insert(self, key, value)
+    }
+
+    fn delete(&mut self, key: K) {
+        BSTree::delete(self, &key)
+    }
+
+    fn len(&self) -> usize {
+        BSTree::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        BSTree::is_empty(self)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        BSTree::get(self, key)
+    }
+
+    fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
+        BSTree::get_pair(self, key)
+    }
+
+    fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+        BSTree::get_or(self, key, default)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        BSTree::contains(self, key)
+    }
+
+    fn min_pair(&self) -> Option<(&K, &V)> {
+        BSTree::min_pair(self)
+    }
+
+    fn max_pair(&self) -> Option<(&K, &V)> {
+        BSTree::max_pair(self)
+    }
+
+    fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        BSTree::successor(self, key)
+    }
+
+    fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        BSTree::predecessor(self, key)
+    }
+
+    fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        BSTree::preorder_iter(self)
+    }
+
+    fn inorder_iter(&self) -> Iter<'_, K, V> {
+        BSTree::inorder_iter(self)
+    }
+
+    fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        BSTree::postorder_iter(self)
+    }
+
+    fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        BSTree::levelorder_iter(self)
+    }
+}
+
+impl<K: Ord, V> BSTree<K, V> {
+    /// 构建一棵空的二叉查找树，按`Ord`比较键
     /// # Examples
     /// ```
     /// use an_ok_bstree::BSTree;
     /// let mut tree: BSTree<i32, i32> = BSTree::new();
     /// ```
     pub fn new() -> Self {
-        BSTree { root: None }
+        Self::with_comparator()
+    }
+}
+
+impl<K, V, C: Compare<K>> BSTree<K, V, C> {
+    /// 构建一棵空的二叉查找树，按比较器C比较键
+    /// # Examples
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// use ordered_map::Reverse;
+    /// let mut tree: BSTree<i32, char, Reverse> = BSTree::with_comparator();
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// assert_eq!(tree.min_pair(), Some((&2, &'b')));
+    /// ```
+    pub fn with_comparator() -> Self {
+        BSTree {
+            root: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// 检查二叉查找树的性质：键按比较器C严格递增，以及键值对个数正确。不满足时返回第一个被发现的问题
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// for key in [5, 2, 8, 1, 4].iter() {
+    ///     tree.insert(*key, *key);
+    /// }
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let count = Node::validate::<C>(&self.root, &mut None)?;
+        if count != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                cached: self.len,
+                actual: count,
+            });
+        }
+        Ok(())
     }
 
+    // 开启checked特性时，每次修改之后检查二叉查找树的性质，不满足时panic。
+    // 入口只持有子树的链接，通过入口所做的修改在下一次获取入口或修改树时检查
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("BSTree broken: {}", violation);
+        }
+    }
+}
+
+impl<K: Display, V: Display, C> BSTree<K, V, C> {
+    /// 生成DOT格式的图，可以保存为文件后用Graphviz绘制：`dot -Tpng tree.dot -o tree.png`。
+    /// 只有一个孩子的节点用一个点表示空的孩子，以便区分左右
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(2, 'b');
+    /// tree.insert(3, 'c');
+    /// let dot = tree.to_dot();
+    /// assert!(dot.starts_with("digraph BSTree {"));
+    /// assert!(dot.contains("0 [label=\"2: b\"];\n    nil0 [shape=point];\n    0 -> nil0;\n    0 -> 1;\n    1 [label=\"3: c\"];\n"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph BSTree {\n    ordering=out;\n    node [shape=circle];\n");
+        if let Some(root) = self.root.as_ref() {
+            root.write_dot(&mut out, &mut 0);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> BSTree<K, V, C> {
     /// 判断当前树是否为空
     /// # Example
     /// ```
@@ -34,6 +192,25 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
         self.root.is_none()
     }
 
+    /// 返回树中键值对的个数，时间复杂度O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'a');
+    /// tree.insert(3, 'c');
+    /// tree.insert(1, 'z');
+    /// assert_eq!(tree.len(), 3);
+    /// tree.delete(&1);
+    /// assert_eq!(tree.len(), 2);
+    /// let rm_tree = tree.remove_tree(3);
+    /// assert_eq!((tree.len(), rm_tree.len()), (1, 1));
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
     /// 向树中插入键值对，如果键已经存在，则替换旧值为新值
     /// # Example
     /// ```
@@ -45,10 +222,34 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// assert_eq!(tree.get(&2), Some(&'b'));
     /// ```
     pub fn insert(&mut self, key: K, value: V) {
-        match self.root {
-            None => self.root = Some(Box::new(Node::new(key, value))),
-            Some(ref mut node) => node.insert(key, value),
+        let inserted = match self.root {
+            None => {
+                self.root = Some(Box::new(Node::new(key, value)));
+                true
+            }
+            Some(ref mut node) => node.insert::<C>(key, value),
+        };
+        if inserted {
+            self.len += 1;
         }
+        self.check();
+    }
+
+    /// 获取键对应的入口，只需查找一次即可完成“不存在则插入，存在则修改”
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// for c in "abcab".chars() {
+    ///     *tree.entry(c).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(tree.get(&'a'), Some(&2));
+    /// assert_eq!(tree.get(&'c'), Some(&1));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        self.check();
+        let link = Node::search_link::<K, C>(&mut self.root, &key);
+        Entry::new(link, &mut self.len, key)
     }
 
     /// 从树中删除键值对，如果找不到键值对，则忽略
@@ -57,27 +258,40 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// use an_ok_bstree::BSTree;
     /// let mut tree = BSTree::new();
     /// tree.insert(1, 'a');
-    /// tree.delete(1);
+    /// tree.delete(&1);
     /// assert!(tree.is_empty());
-    /// tree.delete(2);
+    /// tree.delete(&2);
     /// assert!(tree.is_empty());
     /// ```
-    pub fn delete(&mut self, key: K) {
+    pub fn delete<Q: ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         if let Some(node) = self.root.take() {
-            self.root = node.delete(key)
+            let (root, deleted) = node.delete::<Q, C>(key);
+            self.root = root;
+            if deleted {
+                self.len -= 1;
+            }
         }
+        self.check();
     }
 
-    /// 根据键获取相应键值对
+    /// 根据键获取相应键值对，key可以是K的任意借用形式
     /// # Example
     /// ```
     /// use an_ok_bstree::BSTree;
     /// let mut tree = BSTree::new();
-    /// tree.insert(1, 'a');
-    /// assert_eq!(tree.get_pair(&1), Some((&1, &'a')));
-    /// ```
-    pub fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.search_pair(key))
+    /// tree.insert(String::from("a"), 1);
+    /// assert_eq!(tree.get_pair("a"), Some((&String::from("a"), &1)));
+    /// ```
+    pub fn get_pair<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.search_pair::<Q, C>(key))
     }
 
     /// 根据键查找对应的值，找不到返回None，返回值的不可变借用
@@ -88,8 +302,33 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.get(&1), Some(&'a'));
     /// ```
-    pub fn get(&self, key: &K) -> Option<&V> {
-        self.root.as_ref().and_then(|node| node.search(key))
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.search::<Q, C>(key))
+    }
+
+    /// 根据键查找对应的值，找不到返回None，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(1, 'a');
+    /// if let Some(value) = tree.get_mut(&1) {
+    ///     *value = 'b';
+    /// }
+    /// assert_eq!(tree.get(&1), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        Node::search_link::<Q, C>(&mut self.root, key)
+            .as_mut()
+            .map(|node| node.pair_mut().1)
     }
 
     /// 据键查找对应的值，找不到返回默认值
@@ -101,7 +340,11 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// assert_eq!(tree.get_or(&1, &'z'), &'a');
     /// assert_eq!(tree.get_or(&2, &'z'), &'z');
     /// ```
-    pub fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+    pub fn get_or<'a, Q: ?Sized>(&'a self, key: &Q, default: &'a V) -> &'a V
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         self.get(key).map_or(default, |data| data)
     }
 
@@ -114,7 +357,11 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// assert_eq!(tree.contains(&1), true);
     /// assert_eq!(tree.contains(&2), false);
     /// ```
-    pub fn contains(&self, key: &K) -> bool {
+    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         self.get(key).is_some()
     }
 
@@ -159,8 +406,12 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// assert_eq!(tree.successor(&0), Some((&1, &'a')));
     /// assert_eq!(tree.successor(&3), None);
     /// ```
-    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.successor(key))
+    pub fn successor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.successor::<Q, C>(key))
     }
 
     ///返回第一个小于key的键值对
@@ -175,8 +426,12 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// assert_eq!(tree.predecessor(&5), Some((&3, &'c')));
     /// assert_eq!(tree.predecessor(&1), None);
     /// ```
-    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
-        self.root.as_ref().and_then(|node| node.predecessor(key))
+    pub fn predecessor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.as_ref().and_then(|node| node.predecessor::<Q, C>(key))
     }
 
     ///删除以val为根节点的树枝
@@ -197,11 +452,13 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     pub fn delete_tree(&mut self, key: K) {
         match self.root {
             None => {},
-            Some(ref mut node) if node.key == key => {
+            Some(ref mut node) if C::compare(&node.key, &key) == Ordering::Equal => {
                 self.root = None;
+                self.len = 0;
             }
-            Some(ref mut node) => node.delete_tree(key),
+            Some(ref mut node) => self.len -= node.delete_tree::<C>(key),
         }
+        self.check();
     }
 
     ///删除以val为根节点的树枝, 并返回切掉的树枝
@@ -222,38 +479,110 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     pub fn remove_tree(&mut self, key: K) -> Self {
         let ret_node = match self.root {
             None => None,
-            Some(ref mut node) if node.key == key => self.root.take(),
-            Some(ref mut node) => node.remove_tree(key),
+            Some(ref mut node) if C::compare(&node.key, &key) == Ordering::Equal => self.root.take(),
+            Some(ref mut node) => node.remove_tree::<C>(key),
         };
-        Self { root: ret_node }
+        self.cut_off(ret_node)
     }
 
-    ///前序遍历
-    fn prev_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::prev_order(&self.root, &mut buf);
-        buf
+    // 用切下的树枝构造一棵新树，并从当前树的键值对个数中减去树枝的大小
+    fn cut_off(&mut self, root: Link<K, V>) -> Self {
+        let len = Node::count(&root);
+        self.len -= len;
+        self.check();
+        let tree = Self {
+            root,
+            len,
+            marker: PhantomData,
+        };
+        tree.check();
+        tree
+    }
+
+    /// 范围迭代器，按键的顺序返回范围内的键值对，可以从两端迭代。创建时只查找范围的两端，时间复杂度O(h)
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// for key in [5, 2, 8, 1, 4, 7, 9, 3, 6].iter() {
+    ///     tree.insert(*key, *key * 10);
+    /// }
+    /// let res: Vec<i32> = tree.range(3..=5).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![3, 4, 5]);
+    /// let res: Vec<i32> = tree.range(..3).rev().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// assert_eq!(tree.range(10..).next(), None);
+    /// ```
+    pub fn range<Q: ?Sized, R: RangeBounds<Q>>(&self, range: R) -> RangePairIter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        RangePairIter::new(&self.root, range.start_bound(), range.end_bound(), C::compare)
     }
 
-    ///中序遍历
-    fn in_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::in_order(&self.root, &mut buf);
-        buf
+    /// 范围可变迭代器，按键的顺序返回范围内的键值对，只能修改值。创建时只查找范围的两端，时间复杂度O(h)
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// for key in [5, 2, 8, 1, 4, 7, 9, 3, 6].iter() {
+    ///     tree.insert(*key, *key * 10);
+    /// }
+    /// for (_, value) in tree.range_mut(3..=5) {
+    ///     *value += 1;
+    /// }
+    /// let res: Vec<i32> = tree.range(2..=6).map(|(_, v)| *v).collect();
+    /// assert_eq!(res, vec![20, 31, 41, 51, 60]);
+    /// assert_eq!(tree.range_mut(10..).next(), None);
+    /// ```
+    pub fn range_mut<Q: ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> RangePairIterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        RangePairIterMut::new(&mut self.root, range.start_bound(), range.end_bound(), C::compare)
     }
 
-    ///后序遍历
-    fn post_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::post_order(&self.root, &mut buf);
-        buf
+    /// 中序可变迭代器，按键的顺序返回键值对，只能修改值
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'a');
+    /// for (key, value) in tree.iter_mut() {
+    ///     if *key == 2 {
+    ///         *value = 'z';
+    ///     }
+    /// }
+    /// assert_eq!(tree.get(&2), Some(&'z'));
+    /// ```
+    /// 键是只读的，修改键无法通过编译
+    /// ```compile_fail
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(1, 'a');
+    /// for (key, _) in tree.iter_mut() {
+    ///     *key = 2;
+    /// }
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut::new(&mut self.root, self.len)
     }
 
-    ///层序遍历
-    fn level_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        Node::level_order(&self.root, &mut buf);
-        buf
+    /// 按键的顺序返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(1, 10);
+    /// tree.insert(2, 20);
+    /// tree.values_mut().for_each(|value| *value *= 2);
+    /// assert_eq!(tree.get(&2), Some(&40));
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut::new(self.iter_mut())
     }
 
     /// 前序遍历迭代器
@@ -267,18 +596,11 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.preorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn preorder_iter(&self) -> TraverseIter<K, V> {
-        let pre_order = self.prev_order();
-        let mut queue = VecDeque::new();
-        for key in pre_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Pre, self.len)
     }
 
-    /// 中序遍历迭代器
+    /// 中序遍历迭代器，可以从两端迭代
     /// # Example
     /// ```
     /// use an_ok_bstree::BSTree;
@@ -288,16 +610,12 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// tree.insert(1, 'a');
     /// let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    /// let res: Vec<(&i32, &char)> = tree.inorder_iter().rev().collect();
+    /// assert_eq!(res, vec![(&3, &'c'), (&2, &'b'), (&1, &'a')]);
+    /// assert_eq!(tree.inorder_iter().len(), 3);
     /// ```
-    pub fn inorder_iter(&self) -> TraverseIter<K, V> {
-        let in_order = self.in_order();
-        let mut queue = VecDeque::new();
-        for key in in_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn inorder_iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.root, self.len)
     }
 
     /// 后序遍历迭代器
@@ -311,15 +629,8 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.postorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&3, &'c'), (&2, &'b')]);
     /// ```
-    pub fn postorder_iter(&self) -> TraverseIter<K, V> {
-        let post_order = self.post_order();
-        let mut queue = VecDeque::new();
-        for key in post_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Post, self.len)
     }
 
     /// 层序遍历迭代器
@@ -333,14 +644,49 @@ impl<K: PartialOrd + Clone, V> BSTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.levelorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn levelorder_iter(&self) -> TraverseIter<K, V> {
-        let level_order = self.level_order();
-        let mut queue = VecDeque::new();
-        for key in level_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
+    pub fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, Order::Level, self.len)
+    }
+}
+
+/// 将二叉查找树打印成字符串，`{}`打印成一行嵌套的形式，`{:#}`把树画成多行，空的孩子画成Ø
+/// # Example
+/// ```
+/// use an_ok_bstree::BSTree;
+/// let mut tree = BSTree::new();
+/// tree.insert(1, 'a');
+/// assert_eq!(tree.to_string(), "[K: 1, V: a, L: Ø, R: Ø]");
+/// tree.insert(2, 'b');
+/// assert_eq!(format!("{:#}", tree), "1: a\n├── Ø\n└── 2: b");
+/// ```
+impl<K: Display, V: Display, C> Display for BSTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.root {
+            None if f.alternate() => write!(f, "Ø"),
+            None => write!(f, "None"),
+            Some(ref node) if f.alternate() => node.draw(f, &|key, value, f| write!(f, "{}: {}", key, value)),
+            Some(ref node) => write!(f, "{}", node),
+        }
+    }
+}
+
+/// `{:?}`按键的顺序打印所有键值对，`{:#?}`把树画成多行
+/// # Example
+/// ```
+/// use an_ok_bstree::BSTree;
+/// let mut tree = BSTree::new();
+/// for key in [2, 1, 3].iter() {
+///     tree.insert(*key, 'a');
+/// }
+/// assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'a', 3: 'a'}");
+/// assert_eq!(format!("{:#?}", tree), "2: 'a'\n├── 1: 'a'\n└── 3: 'a'");
+/// ```
+impl<K: Debug + Clone, V: Debug, C: Compare<K>> Debug for BSTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.root {
+            _ if !f.alternate() => f.debug_map().entries(self.inorder_iter()).finish(),
+            None => write!(f, "Ø"),
+            Some(ref node) => node.draw(f, &|key, value, f| write!(f, "{:?}: {:?}", key, value)),
         }
-        TraverseIter::new(queue)
     }
 }
@@ -1,4 +1,8 @@
-use std::collections::VecDeque;
+
+use ordered_map::{dot_escape, draw_tree, Compare, InvariantViolation};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
 
 pub type Link<K, V> = Option<Box<Node<K, V>>>;
 pub struct Node<K, V> {
@@ -8,7 +12,7 @@ pub struct Node<K, V> {
     right: Link<K, V>,
 }
 
-impl<K: PartialOrd + Clone, V> Node<K, V> {
+impl<K, V> Node<K, V> {
     pub fn new(key: K, value: V) -> Self {
         Node {
             key,
@@ -18,43 +22,72 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         }
     }
 
-    // 插入键值对
-    pub fn insert(&mut self, key: K, value: V) {
-        if self.key > key {
-            match self.left {
+    // 插入键值对，新增了节点返回true，键已存在只替换值时返回false
+    pub fn insert<C: Compare<K>>(&mut self, key: K, value: V) -> bool {
+        match C::compare(&key, &self.key) {
+            Ordering::Less => match self.left {
                 None => {
                     self.left = Some(Box::new(Node::new(key, value)));
+                    true
                 }
-                Some(ref mut node) => node.insert(key, value),
-            }
-        } else if self.key < key {
-            match self.right {
+                Some(ref mut node) => node.insert::<C>(key, value),
+            },
+            Ordering::Greater => match self.right {
                 None => {
                     self.right = Some(Box::new(Node::new(key, value)));
+                    true
                 }
-                Some(ref mut node) => node.insert(key, value),
+                Some(ref mut node) => node.insert::<C>(key, value),
+            },
+            Ordering::Equal => {
+                self.value = value;
+                false
             }
-        } else {
-            self.value = value;
         }
     }
 
+    // 统计以link为根的树中节点的个数
+    pub fn count(link: &Link<K, V>) -> usize {
+        link.as_ref()
+            .map_or(0, |node| 1 + Self::count(&node.left) + Self::count(&node.right))
+    }
+
+    // 按中序检查以link为根的树中的键是否严格递增，prev为中序遍历中上一个节点的键，返回节点的个数
+    pub fn validate<'a, C: Compare<K>>(link: &'a Link<K, V>, prev: &mut Option<&'a K>) -> Result<usize, InvariantViolation> {
+        let node = match link {
+            None => return Ok(0),
+            Some(node) => node,
+        };
+        let left = Self::validate::<C>(&node.left, prev)?;
+        if prev.is_some_and(|prev| C::compare(prev, &node.key) != Ordering::Less) {
+            return Err(InvariantViolation::KeyOrder);
+        }
+        *prev = Some(&node.key);
+        let right = Self::validate::<C>(&node.right, prev)?;
+        Ok(left + right + 1)
+    }
+
     // 返回查找的键值对的不可变借用
-    pub fn search_pair(&self, key: &K,) -> Option<(&K, &V)> {
-        if self.key < *key {
-            self.right
+    pub fn search_pair<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => self
+                .right
                 .as_ref()
-                .and_then(|right| right.search_pair(key))
-        } else if self.key > *key {
-            self.left.as_ref().and_then(|left| left.search_pair(key))
-        } else {
-            Some((&self.key, &self.value))
+                .and_then(|right| right.search_pair::<Q, C>(key)),
+            Ordering::Less => self.left.as_ref().and_then(|left| left.search_pair::<Q, C>(key)),
+            Ordering::Equal => Some((&self.key, &self.value)),
         }
     }
 
     // 根据键查找对应的值
-    pub fn search(&self, key: &K) -> Option<&V> {
-        self.search_pair(key).map(|(_, v)| v)
+    pub fn search<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.search_pair::<Q, C>(key).map(|(_, v)| v)
     }
 
     // 返回AVL树中的最小键值对
@@ -76,30 +109,32 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
     }
 
     // 返回第一个大于key的键值对,key可以不存在树中
-    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
-        if self.key > *key {
-            match self.left {
+    pub fn successor<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Less => match self.left {
                 None => Some((&self.key, &self.value)),
-                Some(ref succ) => succ.successor(key).or(Some((&self.key, &self.value))),
-            }
-        } else if self.key < *key {
-            self.right.as_ref().and_then(|right| right.successor(key))
-        } else {
-            self.right.as_ref().map(|right| right.min_pair())
+                Some(ref succ) => succ.successor::<Q, C>(key).or(Some((&self.key, &self.value))),
+            },
+            Ordering::Greater => self.right.as_ref().and_then(|right| right.successor::<Q, C>(key)),
+            Ordering::Equal => self.right.as_ref().map(|right| right.min_pair()),
         }
     }
 
     // 返回第一个小于key的键值对,key可以不存在树中
-    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
-        if self.key < *key {
-            match self.right {
+    pub fn predecessor<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => match self.right {
                 None => Some((&self.key, &self.value)),
-                Some(ref succ) => succ.predecessor(key).or(Some((&self.key, &self.value))),
-            }
-        } else if self.key > *key {
-            self.left.as_ref().and_then(|left| left.predecessor(key))
-        } else {
-            self.left.as_ref().map(|left| left.max_pair())
+                Some(ref succ) => succ.predecessor::<Q, C>(key).or(Some((&self.key, &self.value))),
+            },
+            Ordering::Less => self.left.as_ref().and_then(|left| left.predecessor::<Q, C>(key)),
+            Ordering::Equal => self.left.as_ref().map(|left| left.max_pair()),
         }
     }
 
@@ -129,8 +164,45 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         new_root
     }
 
+    // 查找key所在的链接位置，key不存在时返回应当插入的空链接
+    pub fn search_link<'a, Q: ?Sized, C: Compare<Q>>(link: &'a mut Link<K, V>, key: &Q) -> &'a mut Link<K, V>
+    where
+        K: Borrow<Q>,
+    {
+        match link.as_deref().map(|node| C::compare(key, node.key.borrow())) {
+            Some(Ordering::Less) => Self::search_link::<Q, C>(&mut link.as_mut().unwrap().left, key),
+            Some(Ordering::Greater) => Self::search_link::<Q, C>(&mut link.as_mut().unwrap().right, key),
+            _ => link,
+        }
+    }
+
+    // 返回当前节点的键值对
+    pub fn pair(&self) -> (&K, &V) {
+        (&self.key, &self.value)
+    }
+
+    // 返回当前节点的左右子树
+    pub fn children(&self) -> (&Link<K, V>, &Link<K, V>) {
+        (&self.left, &self.right)
+    }
+
+    // 返回当前节点的键和值的可变借用
+    pub fn pair_mut(&mut self) -> (&K, &mut V) {
+        (&self.key, &mut self.value)
+    }
+
+    // 同时借出键、值的可变借用以及左右子树，供可变迭代器使用，键始终只读
+    pub fn parts_mut(&mut self) -> (&K, &mut V, &mut Link<K, V>, &mut Link<K, V>) {
+        (&self.key, &mut self.value, &mut self.left, &mut self.right)
+    }
+
+    // 拆出当前节点的键值对
+    pub fn into_pair(self) -> (K, V) {
+        (self.key, self.value)
+    }
+
     //删除当前节点，并返回新的根节点
-    pub fn delete_root(mut self) -> Link<K, V> {
+    pub fn delete_root(&mut self) -> Link<K, V> {
         // 二叉搜索树树删除节点的三种情况：
         // 1.如果是叶子节点，则直接删除
         // 2.如果待删除节点只有左子树或只有右子树，删除该节点，然后将左子树或右子树移动到该节点
@@ -143,119 +215,102 @@ impl<K: PartialOrd + Clone, V> Node<K, V> {
         }
     }
 
-    //删除节点key，返回的新的根节点
-    pub fn delete(mut self, key: K) -> Link<K, V> {
-        if self.key < key {
-            if let Some(right) = self.right.take() {
-                self.right = right.delete(key);
-                return Some(Box::new(self));
-            }
-        } else if self.key > key {
-            if let Some(left) = self.left.take() {
-                self.left = left.delete(key);
-                return Some(Box::new(self));
-            }
-        }
-        else {
-            return self.delete_root()
-        }
-        Some(Box::new(self))
-    }
-
-    // 删除以key为根节点的树枝,无法直接删除根节点
-    pub fn delete_tree(&mut self, key: K) {
-        if self.key < key {
-            if let Some(ref mut right) = self.right {
-                if right.key == key {
-                    self.right = None;
-                }
-                else {
-                    right.delete_tree(key);
+    //删除节点key，返回的新的根节点，以及是否删除了节点
+    pub fn delete<Q: ?Sized, C: Compare<Q>>(mut self, key: &Q) -> (Link<K, V>, bool)
+    where
+        K: Borrow<Q>,
+    {
+        match C::compare(key, self.key.borrow()) {
+            Ordering::Greater => {
+                if let Some(right) = self.right.take() {
+                    let (right, deleted) = right.delete::<Q, C>(key);
+                    self.right = right;
+                    return (Some(Box::new(self)), deleted);
                 }
             }
-        }
-        else if self.key > key {
-            if let Some(ref mut left) = self.left {
-                if left.key == key {
-                    self.left = None;
-                }
-                else {
-                    left.delete_tree(key);
+            Ordering::Less => {
+                if let Some(left) = self.left.take() {
+                    let (left, deleted) = left.delete::<Q, C>(key);
+                    self.left = left;
+                    return (Some(Box::new(self)), deleted);
                 }
             }
+            Ordering::Equal => return (self.delete_root(), true),
         }
+        (Some(Box::new(self)), false)
+    }
+
+    // 删除以key为根节点的树枝,无法直接删除根节点，返回删除的节点个数
+    pub fn delete_tree<C: Compare<K>>(&mut self, key: K) -> usize {
+        Self::count(&self.remove_tree::<C>(key))
     }
 
     // 删除以key为根节点的树枝, 并返回切掉的树枝
     // 无法直接删除根节点
-    pub fn remove_tree(&mut self, key: K) -> Link<K, V> {
-        if self.key < key {
-            if let Some(ref mut right) = self.right {
-                if right.key == key {
-                    return self.right.take();
-                }
-                else {
-                    return right.remove_tree(key);
-                }
-            }
+    pub fn remove_tree<C: Compare<K>>(&mut self, key: K) -> Link<K, V> {
+        let child = match C::compare(&key, &self.key) {
+            Ordering::Greater => &mut self.right,
+            Ordering::Less => &mut self.left,
+            Ordering::Equal => return None,
+        };
+        match child {
+            Some(node) if C::compare(&key, &node.key) == Ordering::Equal => child.take(),
+            Some(node) => node.remove_tree::<C>(key),
+            None => None,
         }
-        else if self.key > key {
-            if let Some(ref mut left) = self.left {
-                if left.key == key {
-                    return self.left.take();
+    }
+
+    // 按前序把以该节点为根的子树写成DOT格式，id为下一个可用的节点编号。
+    // 只有一个孩子时用一个点表示空的孩子，以便区分左右
+    pub fn write_dot(&self, out: &mut String, id: &mut usize)
+    where
+        K: Display,
+        V: Display,
+    {
+        let me = *id;
+        *id += 1;
+        let label = dot_escape(&format!("{}: {}", self.key, self.value));
+        out.push_str(&format!("    {} [label=\"{}\"];\n", me, label));
+        for child in [&self.left, &self.right].iter() {
+            match child {
+                Some(node) => {
+                    out.push_str(&format!("    {} -> {};\n", me, *id));
+                    node.write_dot(out, id);
                 }
-                else {
-                    return left.remove_tree(key);
+                None if self.left.is_some() || self.right.is_some() => {
+                    out.push_str(&format!("    nil{0} [shape=point];\n    {0} -> nil{0};\n", me));
                 }
+                None => {}
             }
         }
-        None
-    }
-
-    // 前序遍历
-    pub fn prev_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            buf.push(node.key.clone());
-            Self::prev_order(&node.left, buf);
-            Self::prev_order(&node.right, buf);
-        }
     }
+}
 
-    // 中序遍历
-    pub fn in_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            Self::in_order(&node.left, buf);
-            buf.push(node.key.clone());
-            Self::in_order(&node.right, buf);
-        }
+impl<K, V> Node<K, V> {
+    // 把以该节点为根的树画成多行，label写出每个节点的键值对
+    pub fn draw(&self, f: &mut Formatter<'_>, label: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        draw_tree(f, self, &|node: &&Self, f: &mut Formatter<'_>| label(&node.key, &node.value, f), &|node: &&Self| {
+            if node.left.is_none() && node.right.is_none() {
+                return Vec::new();
+            }
+            vec![node.left.as_deref(), node.right.as_deref()]
+        })
     }
+}
 
-    // 后序遍历
-    pub fn post_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        if let Some(node) = root {
-            Self::post_order(&node.left, buf);
-            Self::post_order(&node.right, buf);
-            buf.push(node.key.clone());
-        }
+impl<K: Display, V: Display> Display for Node<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[K: {}, V: {}, L: ", self.key, self.value)?;
+        fmt_link(&self.left, f)?;
+        write!(f, ", R: ")?;
+        fmt_link(&self.right, f)?;
+        write!(f, "]")
     }
+}
 
-    // 层序遍历
-    pub fn level_order(root: &Link<K, V>, buf: &mut Vec<K>) {
-        let mut queue = VecDeque::new();
-        if let Some(node) = root {
-            queue.push_back(node);
-        }
-        while !queue.is_empty() {
-            if let Some(node) = queue.pop_front() {
-                buf.push(node.key.clone());
-                if let Some(left) = node.left.as_ref() {
-                    queue.push_back(left);
-                }
-                if let Some(right) = node.right.as_ref() {
-                    queue.push_back(right);
-                }
-            }
-        }
+fn fmt_link<K: Display, V: Display>(node: &Link<K, V>, f: &mut Formatter<'_>) -> fmt::Result {
+    match node {
+        None => write!(f, "Ø"),
+        Some(box_node) => write!(f, "{}", box_node),
     }
 }
-
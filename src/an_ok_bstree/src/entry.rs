@@ -0,0 +1,175 @@
+#[cfg(feature = "no_recur")]
+use crate::bstree_no_recursion::node::{Link, Node};
+#[cfg(not(feature = "no_recur"))]
+use crate::bstree_recursion::node::{Link, Node};
+
+/// 二叉搜索树中某个键的入口，由`BSTree::entry`返回
+pub enum Entry<'a, K, V> {
+    /// 键不存在
+    Vacant(VacantEntry<'a, K, V>),
+    /// 键已存在
+    Occupied(OccupiedEntry<'a, K, V>),
+}
+
+/// 不存在的键的入口，持有应当插入新节点的空链接
+pub struct VacantEntry<'a, K, V> {
+    link: &'a mut Link<K, V>,
+    len: &'a mut usize, // 树中键值对的个数
+    key: K,
+}
+
+/// 已存在的键的入口，持有指向该节点的链接
+pub struct OccupiedEntry<'a, K, V> {
+    link: &'a mut Link<K, V>,
+    len: &'a mut usize, // 树中键值对的个数
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub(crate) fn new(link: &'a mut Link<K, V>, len: &'a mut usize, key: K) -> Self {
+        if link.is_some() {
+            Entry::Occupied(OccupiedEntry { link, len })
+        } else {
+            Entry::Vacant(VacantEntry { link, len, key })
+        }
+    }
+
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// 键不存在时插入default，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// *tree.entry(1).or_insert(10) += 1;
+    /// *tree.entry(1).or_insert(10) += 1;
+    /// assert_eq!(tree.get(&1), Some(&12));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入f的返回值，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.entry(1).or_insert_with(Vec::new).push('a');
+    /// tree.entry(1).or_insert_with(Vec::new).push('b');
+    /// assert_eq!(tree.get(&1), Some(&vec!['a', 'b']));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(f()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入默认值，返回值的可变借用
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// 键存在时用f原地修改值，返回修改后的入口
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::BSTree;
+    /// let mut tree = BSTree::new();
+    /// tree.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// tree.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// assert_eq!(tree.get(&1), Some(&1));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 取回入口对应的键
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 在空链接处插入新节点，返回值的可变借用
+    pub fn insert(self, value: V) -> &'a mut V {
+        *self.len += 1;
+        let node = self.link.insert(Box::new(Node::new(self.key, value)));
+        node.pair_mut().1
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        self.node().pair().0
+    }
+
+    /// 返回值的不可变借用
+    pub fn get(&self) -> &V {
+        self.node().pair().1
+    }
+
+    /// 返回值的可变借用
+    pub fn get_mut(&mut self) -> &mut V {
+        self.link.as_mut().expect("BSTree broken").pair_mut().1
+    }
+
+    /// 将入口转换为值的可变借用，生命周期与树的借用相同
+    pub fn into_mut(self) -> &'a mut V {
+        self.link.as_mut().expect("BSTree broken").pair_mut().1
+    }
+
+    /// 替换为新值，返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 从树中删除该键值对并返回
+    /// # Example
+    /// ```
+    /// use an_ok_bstree::{BSTree, Entry};
+    /// let mut tree = BSTree::new();
+    /// tree.insert(1, 'a');
+    /// if let Entry::Occupied(entry) = tree.entry(1) {
+    ///     assert_eq!(entry.remove_entry(), (1, 'a'));
+    /// }
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        let mut node = self.link.take().expect("BSTree broken");
+        *self.link = node.delete_root();
+        *self.len -= 1;
+        node.into_pair()
+    }
+
+    /// 从树中删除该键值对，返回值
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    fn node(&self) -> &Node<K, V> {
+        self.link.as_ref().expect("BSTree broken")
+    }
+}
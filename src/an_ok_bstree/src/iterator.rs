@@ -1,20 +1,401 @@
-use std::collections::VecDeque;
+#[cfg(feature = "no_recur")]
+use crate::bstree_no_recursion::node::{Link, Node};
+#[cfg(not(feature = "no_recur"))]
+use crate::bstree_recursion::node::{Link, Node};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{Bound, VecDeque};
+use std::ptr;
 
-//遍历迭代器，包括前序、中序、后序、层序
+// 将节点及其左侧路径上的节点依次入栈
+fn push_left<'a, K, V>(stack: &mut Vec<&'a Node<K, V>>, mut link: &'a Link<K, V>) {
+    while let Some(node) = link {
+        stack.push(node);
+        link = node.children().0;
+    }
+}
+
+// 将节点及其右侧路径上的节点依次入栈
+fn push_right<'a, K, V>(stack: &mut Vec<&'a Node<K, V>>, mut link: &'a Link<K, V>) {
+    while let Some(node) = link {
+        stack.push(node);
+        link = node.children().1;
+    }
+}
+
+// key是否不小于下边界
+fn above_lower<Q: ?Sized>(from: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match from {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Less,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Greater,
+        Bound::Unbounded => true,
+    }
+}
+
+// key是否不大于上边界
+fn below_upper<Q: ?Sized>(to: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match to {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Greater,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Less,
+        Bound::Unbounded => true,
+    }
+}
+
+// 中序迭代器，可以从两端迭代。front栈顶是下一个最小的节点，back栈顶是下一个最大的节点
+pub struct Iter<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+    remaining: usize, // 剩余未输出的键值对个数，为0时两端相遇
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    pub fn new(root: &'a Link<K, V>, len: usize) -> Self {
+        let mut iter = Iter {
+            front: Vec::new(),
+            back: Vec::new(),
+            remaining: len,
+        };
+        push_left(&mut iter.front, root);
+        push_right(&mut iter.back, root);
+        iter
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.front.pop()?;
+        push_left(&mut self.front, node.children().1);
+        self.remaining -= 1;
+        Some(node.pair())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back.pop()?;
+        push_right(&mut self.back, node.children().0);
+        self.remaining -= 1;
+        Some(node.pair())
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+// 判断a是否就是节点b本身，按地址比较而不是按键比较
+fn same_node<K, V>(a: Option<&Node<K, V>>, b: &Node<K, V>) -> bool {
+    a.is_some_and(|a| ptr::eq(a, b))
+}
+
+// 范围迭代器，可以从两端迭代。创建时找到范围内的第一个和最后一个节点，
+// 两端按节点的地址判断是否到达范围的另一端或者对方已经输出的节点，不需要保存边界
+pub struct RangePairIter<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+    first: Option<&'a Node<K, V>>,      // 范围内的第一个节点
+    last: Option<&'a Node<K, V>>,       // 范围内的最后一个节点
+    front_last: Option<&'a Node<K, V>>, // 正向迭代上一次输出的节点
+    back_last: Option<&'a Node<K, V>>,  // 反向迭代上一次输出的节点
+    finished: bool,
+}
+
+impl<'a, K, V> RangePairIter<'a, K, V> {
+    pub fn new<Q: ?Sized>(root: &'a Link<K, V>, lower: Bound<&Q>, upper: Bound<&Q>, cmp: fn(&Q, &Q) -> Ordering) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut iter = RangePairIter {
+            front: Vec::new(),
+            back: Vec::new(),
+            first: None,
+            last: None,
+            front_last: None,
+            back_last: None,
+            finished: false,
+        };
+        // 只保留不小于下边界的节点，第一个出栈的就是范围内最小的节点
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (left, right) = node.children();
+            if above_lower(lower, node.pair().0.borrow(), cmp) {
+                iter.front.push(node);
+                cur = left;
+            } else {
+                cur = right;
+            }
+        }
+        // 只保留不大于上边界的节点
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (left, right) = node.children();
+            if below_upper(upper, node.pair().0.borrow(), cmp) {
+                iter.back.push(node);
+                cur = right;
+            } else {
+                cur = left;
+            }
+        }
+        iter.first = iter.front.last().copied();
+        iter.last = iter.back.last().copied();
+        iter.finished = match (iter.first, iter.last) {
+            (Some(first), Some(last)) => cmp(first.pair().0.borrow(), last.pair().0.borrow()) == Ordering::Greater,
+            _ => true,
+        };
+        iter
+    }
+}
+
+impl<'a, K, V> Iterator for RangePairIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let node = self.front.pop()?;
+        if same_node(self.back_last, node) {
+            self.finished = true;
+            return None;
+        }
+        push_left(&mut self.front, node.children().1);
+        self.finished = same_node(self.last, node);
+        self.front_last = Some(node);
+        Some(node.pair())
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangePairIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let node = self.back.pop()?;
+        if same_node(self.front_last, node) {
+            self.finished = true;
+            return None;
+        }
+        push_right(&mut self.back, node.children().0);
+        self.finished = same_node(self.first, node);
+        self.back_last = Some(node);
+        Some(node.pair())
+    }
+}
+
+// 遍历的顺序，中序遍历由Iter完成
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Order {
+    Pre,
+    Post,
+    Level,
+}
+
+//遍历迭代器，包括前序、后序、层序。迭代器只保存待访问的节点，按需产生键值对
 pub struct TraverseIter<'a, K, V> {
-    data: VecDeque<(&'a K, &'a V)>,
+    order: Order,
+    // 前序、后序遍历时作为栈使用，只保存根到叶子路径附近的节点；层序遍历时作为队列使用
+    // bool表示节点的孩子是否已经展开，只在后序遍历时使用
+    nodes: VecDeque<(&'a Node<K, V>, bool)>,
+    remaining: usize, // 剩余未输出的键值对个数
 }
 
 impl<'a, K, V> TraverseIter<'a, K, V> {
-    pub fn new(queue: VecDeque<(&'a K, &'a V)>) -> Self {
-        TraverseIter { data: queue }
+    pub fn new(root: &'a Link<K, V>, order: Order, len: usize) -> Self {
+        let mut iter = TraverseIter {
+            order,
+            nodes: VecDeque::new(),
+            remaining: len,
+        };
+        if let Some(node) = root {
+            iter.nodes.push_back((node, false));
+        }
+        iter
+    }
+
+    // 将孩子节点加入待访问序列，先加入的后访问
+    fn push_children(&mut self, first: &'a Link<K, V>, second: &'a Link<K, V>) {
+        if let Some(node) = first {
+            self.nodes.push_back((node, false));
+        }
+        if let Some(node) = second {
+            self.nodes.push_back((node, false));
+        }
+    }
+
+    fn next_node(&mut self) -> Option<&'a Node<K, V>> {
+        match self.order {
+            Order::Pre => {
+                let (node, _) = self.nodes.pop_back()?;
+                let (left, right) = node.children();
+                self.push_children(right, left);
+                Some(node)
+            }
+            Order::Post => loop {
+                let (node, expanded) = self.nodes.pop_back()?;
+                let (left, right) = node.children();
+                if expanded || (left.is_none() && right.is_none()) {
+                    return Some(node);
+                }
+                self.nodes.push_back((node, true));
+                self.push_children(right, left);
+            },
+            Order::Level => {
+                let (node, _) = self.nodes.pop_front()?;
+                let (left, right) = node.children();
+                self.push_children(left, right);
+                Some(node)
+            }
+        }
     }
 }
 
-impl<'a, K: PartialOrd + Clone, V> Iterator for TraverseIter<'a, K, V> {
+impl<'a, K, V> Iterator for TraverseIter<'a, K, V> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.data.pop_front()
+        let node = self.next_node()?;
+        self.remaining -= 1;
+        Some(node.pair())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for TraverseIter<'a, K, V> {}
+
+// 中序可变迭代器，只能修改值，不能修改键
+pub struct IterMut<'a, K, V> {
+    stack: Vec<(&'a K, &'a mut V, &'a mut Link<K, V>)>, // 待访问节点的键、值以及右子树
+    remaining: usize, // 剩余未输出的键值对个数，范围迭代时不使用
+}
+
+impl<'a, K, V> IterMut<'a, K, V> {
+    pub fn new(root: &'a mut Link<K, V>, len: usize) -> Self {
+        let mut iter = IterMut {
+            stack: Vec::new(),
+            remaining: len,
+        };
+        iter.push_left(root);
+        iter
+    }
+
+    // 从第一个不小于下边界的节点开始迭代
+    fn with_lower_bound<Q: ?Sized>(root: &'a mut Link<K, V>, from: Bound<&Q>, cmp: fn(&Q, &Q) -> Ordering) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut iter = IterMut {
+            stack: Vec::new(),
+            remaining: 0,
+        };
+        let mut cur = root;
+        while let Some(node) = cur {
+            let (key, value, left, right) = node.parts_mut();
+            if above_lower(from, key.borrow(), cmp) {
+                iter.stack.push((key, value, right));
+                cur = left;
+            } else {
+                cur = right;
+            }
+        }
+        iter
+    }
+
+    // 将节点及其左侧路径上的节点依次入栈
+    fn push_left(&mut self, mut link: &'a mut Link<K, V>) {
+        while let Some(node) = link {
+            let (key, value, left, right) = node.parts_mut();
+            self.stack.push((key, value, right));
+            link = left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value, right) = self.stack.pop()?;
+        self.push_left(right);
+        self.remaining = self.remaining.saturating_sub(1);
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
-}
\ No newline at end of file
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+// 范围可变迭代器，从第一个不小于下边界的节点开始中序迭代。节点不记录子树大小，
+// 创建时先找到范围内的最后一个节点，输出它之后结束，按键的地址判断，不需要保存上边界
+pub struct RangePairIterMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+    last: Option<usize>, // 范围内最后一个节点的键的地址，只用于比较，为None时迭代结束
+}
+
+impl<'a, K, V> RangePairIterMut<'a, K, V> {
+    pub fn new<Q: ?Sized>(root: &'a mut Link<K, V>, lower: Bound<&Q>, upper: Bound<&Q>, cmp: fn(&Q, &Q) -> Ordering) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let last = RangePairIter::new(root, lower, upper, cmp)
+            .next_back()
+            .map(|(key, _)| ptr::from_ref(key).addr());
+        RangePairIterMut {
+            inner: IterMut::with_lower_bound(root, lower, cmp),
+            last,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for RangePairIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        let (key, value) = self.inner.next()?;
+        if ptr::from_ref(key).addr() == last {
+            self.last = None;
+        }
+        Some((key, value))
+    }
+}
+
+// 按键的顺序返回值的可变借用
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> ValuesMut<'a, K, V> {
+    pub fn new(inner: IterMut<'a, K, V>) -> Self {
+        ValuesMut { inner }
+    }
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
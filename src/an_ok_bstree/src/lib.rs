@@ -1,4 +1,6 @@
 mod iterator;
+mod entry;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
 
 
 #[cfg(feature = "no_recur")]
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use an_ok_bstree::BSTree;
+    use an_ok_bstree::{BSTree, Entry};
+    use ordered_map::{Compare, InvariantViolation, Reverse};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, Bound};
 
     /*
                 10
@@ -28,9 +31,9 @@ mod tests {
         tree.insert(17, 'q');
         tree.insert(10, 'j');
         tree.insert(14, 'n');
-        tree.delete(12);
-        tree.delete(6);
-        tree.delete(8);
+        tree.delete(&12);
+        tree.delete(&6);
+        tree.delete(&8);
         assert!(tree.contains(&5));
         assert!(!tree.contains(&12));
         assert!(!tree.contains(&6));
@@ -219,4 +222,345 @@ mod tests {
         let res: Vec<(&i32, &char)> = tree.levelorder_iter().collect();
         assert_eq!(res, vec![(&3, &'c'), (&2, &'b'), (&4, &'d'), (&1, &'a')]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn entry() {
+        let mut tree = BSTree::new();
+        for key in [10, 5, 15, 3, 7, 14, 17, 2, 4] {
+            *tree.entry(key).or_insert(0) += key;
+        }
+        for key in 1..=17 {
+            tree.entry(key).and_modify(|v| *v *= 10).or_insert_with(|| 0);
+        }
+        assert_eq!(tree.get(&7), Some(&70));
+        assert_eq!(tree.get(&8), Some(&0));
+        assert_eq!(*tree.entry(6).key(), 6);
+
+        // 删除有两个孩子的节点，由右子树中最小的节点顶替
+        match tree.entry(5) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), &5);
+                assert_eq!(entry.get(), &50);
+                assert_eq!(entry.insert(55), 50);
+                assert_eq!(entry.remove_entry(), (5, 55));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match tree.entry(5) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), 5),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        for key in [10, 17, 1] {
+            if let Entry::Occupied(entry) = tree.entry(key) {
+                entry.remove();
+            }
+        }
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![2, 3, 4, 6, 7, 8, 9, 11, 12, 13, 14, 15, 16]);
+    }
+
+    #[test]
+    fn mutable_access() {
+        let mut tree = BSTree::new();
+        for key in [10, 5, 15, 3, 7, 14, 17, 2, 4] {
+            tree.insert(key, key);
+        }
+        assert_eq!(tree.get_mut(&1), None);
+        if let Some(value) = tree.get_mut(&7) {
+            *value = 70;
+        }
+        assert_eq!(tree.get(&7), Some(&70));
+
+        let keys: Vec<i32> = tree
+            .iter_mut()
+            .map(|(key, value)| {
+                *value += 1;
+                *key
+            })
+            .collect();
+        assert_eq!(keys, vec![2, 3, 4, 5, 7, 10, 14, 15, 17]);
+        for value in tree.values_mut() {
+            *value *= 2;
+        }
+        let res: Vec<(&i32, &i32)> = tree.inorder_iter().collect();
+        assert_eq!(
+            res,
+            vec![(&2, &6), (&3, &8), (&4, &10), (&5, &12), (&7, &142), (&10, &22), (&14, &30), (&15, &32), (&17, &36)]
+        );
+
+        let mut empty: BSTree<i32, i32> = BSTree::new();
+        assert_eq!(empty.iter_mut().next(), None);
+        assert_eq!(empty.values_mut().next(), None);
+    }
+
+    #[test]
+    fn lazy_traverse() {
+        let mut tree = BSTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        let res: Vec<(&i32, &i32)> = tree.inorder_iter().take(3).collect();
+        assert_eq!(res, vec![(&0, &0), (&1, &2), (&2, &4)]);
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+
+        // 前序遍历的第一个、后序遍历的最后一个以及层序遍历的第一个都是根节点
+        let root = tree.preorder_iter().next();
+        assert_eq!(root, tree.levelorder_iter().next());
+        assert_eq!(root, tree.postorder_iter().last());
+        for iter in [tree.preorder_iter(), tree.postorder_iter(), tree.levelorder_iter()] {
+            let mut keys: Vec<i32> = iter.map(|(k, _)| *k).collect();
+            keys.sort();
+            assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+        }
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut tree = BSTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        tree.insert(1, 1);
+        assert_eq!(tree.len(), 1000);
+        for key in (0..1000).step_by(3) {
+            tree.delete(&key);
+        }
+        tree.delete(&1000);
+        let expected: Vec<i32> = (0..1000).filter(|key| key % 3 != 0).collect();
+        assert_eq!(tree.len(), expected.len());
+        let keys: Vec<i32> = tree.inorder_iter().rev().map(|(k, _)| *k).collect();
+        assert_eq!(keys, expected.iter().rev().cloned().collect::<Vec<i32>>());
+
+        // 两端交替迭代，在中间相遇后两端都结束
+        let mut iter = tree.inorder_iter();
+        let n = expected.len();
+        for i in 0..n / 2 {
+            assert_eq!(iter.len(), n - 2 * i);
+            assert_eq!(iter.next().map(|(k, _)| *k), Some(expected[i]));
+            assert_eq!(iter.next_back().map(|(k, _)| *k), Some(expected[n - 1 - i]));
+        }
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(tree.preorder_iter().len(), n);
+        assert_eq!(tree.postorder_iter().len(), n);
+        assert_eq!(tree.levelorder_iter().len(), n);
+
+        // 通过入口增删以及剪下树枝后个数仍然正确
+        tree.entry(0).or_insert(0);
+        assert_eq!(tree.len(), n + 1);
+        if let Entry::Occupied(entry) = tree.entry(0) {
+            entry.remove();
+        }
+        assert_eq!(tree.len(), n);
+        let root = *tree.preorder_iter().next().unwrap().0;
+        let left = *tree.preorder_iter().nth(1).unwrap().0;
+        let removed = tree.remove_tree(left);
+        assert_eq!(removed.len(), removed.inorder_iter().count());
+        assert_eq!(tree.len() + removed.len(), n);
+        tree.delete_tree(root);
+        assert_eq!(tree.len(), 0);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = BSTree::new();
+        let mut expected = BTreeSet::new();
+        // 37与1000互质，键以打乱的顺序插入，只保留偶数键
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            if key % 2 == 0 {
+                tree.insert(key, key);
+                expected.insert(key);
+            }
+        }
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Included(101), Bound::Excluded(199)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Excluded(500), Bound::Excluded(502)),
+            (Bound::Included(-5), Bound::Excluded(0)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            let want: Vec<i32> = expected.range((lower, upper)).cloned().collect();
+            let res: Vec<i32> = tree.range((lower, upper)).map(|(k, _)| *k).collect();
+            assert_eq!(res, want);
+            let res: Vec<i32> = tree.range((lower, upper)).rev().map(|(k, _)| *k).collect();
+            assert_eq!(res, want.iter().rev().cloned().collect::<Vec<i32>>());
+            // 两端交替迭代，相遇后两端都结束
+            let mut iter = tree.range((lower, upper));
+            let (mut front, mut back) = (0, want.len());
+            while front < back {
+                assert_eq!(iter.next().map(|(k, _)| *k), Some(want[front]));
+                front += 1;
+                if front < back {
+                    back -= 1;
+                    assert_eq!(iter.next_back().map(|(k, _)| *k), Some(want[back]));
+                }
+            }
+            assert_eq!(iter.next().map(|(k, _)| *k), None);
+            assert_eq!(iter.next_back().map(|(k, _)| *k), None);
+            // 可变范围迭代器与不可变范围迭代器覆盖相同的节点
+            let res: Vec<i32> = tree.range_mut((lower, upper)).map(|(k, _)| *k).collect();
+            assert_eq!(res, want);
+        }
+        let res: Vec<i32> = tree.range(3..=9).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![4, 6, 8]);
+        for (key, value) in tree.range_mut(3..=9) {
+            *value = -*key;
+        }
+        let res: Vec<i32> = tree.range(..=10).map(|(_, v)| *v).collect();
+        assert_eq!(res, vec![0, 2, -4, -6, -8, 10]);
+        assert_eq!(tree.range_mut((Bound::Included(9), Bound::Excluded(3))).next(), None);
+    }
+    // 忽略大小写比较字符串，同时支持按String和&str比较
+    struct CaseInsensitive;
+
+    impl Compare<str> for CaseInsensitive {
+        fn compare(a: &str, b: &str) -> Ordering {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        }
+    }
+
+    impl Compare<String> for CaseInsensitive {
+        fn compare(a: &String, b: &String) -> Ordering {
+            <Self as Compare<str>>::compare(a, b)
+        }
+    }
+
+    #[test]
+    fn comparator() {
+        let mut tree: BSTree<i32, i32, Reverse> = BSTree::with_comparator();
+        for key in [5, 2, 8, 1, 4, 7, 9, 3, 6].iter() {
+            tree.insert(*key, *key * 10);
+        }
+        let res: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(tree.min_pair(), Some((&9, &90)));
+        assert_eq!(tree.successor(&5), Some((&4, &40)));
+        assert_eq!(tree.predecessor(&5), Some((&6, &60)));
+        let res: Vec<i32> = tree.range((Bound::Included(7), Bound::Included(3))).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![7, 6, 5, 4, 3]);
+        tree.delete(&5);
+        tree.delete_tree(2);
+        let res: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![9, 8, 7, 6, 4]);
+
+        let mut tree: BSTree<String, i32, CaseInsensitive> = BSTree::with_comparator();
+        tree.insert(String::from("Apple"), 1);
+        tree.insert(String::from("banana"), 2);
+        tree.insert(String::from("APPLE"), 3);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get("apple"), Some(&3));
+        assert_eq!(tree.get_pair("BANANA"), Some((&String::from("banana"), &2)));
+        if let Some(value) = tree.get_mut("Banana") {
+            *value = 20;
+        }
+        assert_eq!(tree.get(&String::from("banana")), Some(&20));
+        assert!(!tree.contains("cherry"));
+    }
+
+    #[test]
+    fn borrowed_lookup() {
+        let mut tree = BSTree::new();
+        for word in ["b", "a", "c"].iter() {
+            tree.insert(word.to_string(), word.len());
+        }
+        assert_eq!(tree.get("a"), Some(&1));
+        assert_eq!(tree.get_or("z", &0), &0);
+        assert!(tree.contains("c"));
+        *tree.get_mut("b").unwrap() += 1;
+        assert_eq!(tree.get_pair("b"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.successor("a"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.predecessor("b"), Some((&String::from("a"), &1)));
+        let bounds: (Bound<&str>, Bound<&str>) = (Bound::Excluded("a"), Bound::Unbounded);
+        let res: Vec<&String> = tree.range::<str, _>(bounds).map(|(k, _)| k).collect();
+        assert_eq!(res, vec!["b", "c"]);
+        tree.delete("a");
+        assert_eq!(tree.len(), 2);
+        assert!(!tree.contains("a"));
+    }
+
+    #[test]
+    fn validate() {
+        let mut tree = BSTree::new();
+        assert_eq!(tree.validate(), Ok(()));
+        for i in 0..200 {
+            let key = i * 37 % 200;
+            tree.insert(key, key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in (0..200).step_by(3) {
+            tree.delete(&key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in 0..50 {
+            *tree.entry(key).or_insert(0) += 1;
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        tree.delete_tree(100);
+        assert_eq!(tree.validate(), Ok(()));
+
+        let violation = InvariantViolation::StaleSize { cached: 5, actual: 4 };
+        assert_eq!(violation.to_string(), "节点记录的子树大小为5，实际为4");
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut tree = BSTree::new();
+        assert_eq!(tree.to_dot(), "digraph BSTree {\n    ordering=out;\n    node [shape=circle];\n}\n");
+        for key in [3, 1, 2, 4].iter() {
+            tree.insert(*key, *key * 10);
+        }
+        let expected = [
+            "digraph BSTree {",
+            "    ordering=out;",
+            "    node [shape=circle];",
+            r#"    0 [label="3: 30"];"#,
+            "    0 -> 1;",
+            r#"    1 [label="1: 10"];"#,
+            "    nil1 [shape=point];",
+            "    1 -> nil1;",
+            "    1 -> 2;",
+            r#"    2 [label="2: 20"];"#,
+            "    0 -> 3;",
+            r#"    3 [label="4: 40"];"#,
+            "}",
+        ];
+        assert_eq!(tree.to_dot(), expected.join("\n") + "\n");
+
+        // 标签中的双引号和反斜杠需要转义
+        let mut tree = BSTree::new();
+        tree.insert(String::from(r#"a"b\c"#), 1);
+        assert!(tree.to_dot().contains(r#"0 [label="a\"b\\c: 1"];"#));
+    }
+
+    #[test]
+    fn pretty_print() {
+        let mut tree = BSTree::new();
+        assert_eq!(tree.to_string(), "None");
+        assert_eq!(format!("{:#}", tree), "Ø");
+        assert_eq!(format!("{:?}", tree), "{}");
+        for key in [3, 1, 2, 4].iter() {
+            tree.insert(*key, *key * 10);
+        }
+        assert_eq!(
+            tree.to_string(),
+            "[K: 3, V: 30, L: [K: 1, V: 10, L: Ø, R: [K: 2, V: 20, L: Ø, R: Ø]], R: [K: 4, V: 40, L: Ø, R: Ø]]"
+        );
+        let expected = ["3: 30", "├── 1: 10", "│   ├── Ø", "│   └── 2: 20", "└── 4: 40"];
+        assert_eq!(format!("{:#}", tree), expected.join("\n"));
+        assert_eq!(format!("{:?}", tree), "{1: 10, 2: 20, 3: 30, 4: 40}");
+        assert_eq!(format!("{:#?}", tree), expected.join("\n"));
+    }
+}
@@ -12,4 +12,13 @@ fn main() {
         "B Tree insert 10000 times took {} ms.",
         elapsed_time.as_millis()
     );
-}
\ No newline at end of file
+
+    let now = Instant::now();
+    let tree = BTree::from_sorted_iter(1024, 0..10000);
+    let elapsed_time = now.elapsed();
+    println!(
+        "B Tree from_sorted_iter with {} keys took {} ms.",
+        tree.len(),
+        elapsed_time.as_millis()
+    );
+}
@@ -0,0 +1,783 @@
+use crate::iterator::{above_lower, below_upper};
+use crate::node::{max_keys, split_index, Slots};
+use ordered_map::{draw_tree, Compare, InvariantViolation, Natural};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+// 叶子节点保存所有的键值对，没有孩子，prev和next把叶子按键的顺序串成双向链表
+struct Leaf<K, V> {
+    slots: Slots<K, V, usize>,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+// 内部节点只保存分隔键，值为单元类型，孩子是节点的下标，
+// children[i]中的键都小于keys[i]，children[i + 1]中的键都不小于keys[i]
+type Internal<K> = Slots<K, (), usize>;
+
+// 节点都存放在BPlusTree::nodes中，节点之间用下标相互引用
+enum BPlusNode<K, V> {
+    Leaf(Leaf<K, V>),
+    Internal(Internal<K>),
+}
+
+impl<K, V> Leaf<K, V> {
+    fn new(degree: usize) -> Self {
+        Leaf {
+            slots: Slots::new(Vec::with_capacity(max_keys(degree)), Vec::with_capacity(max_keys(degree)), Vec::new()),
+            prev: None,
+            next: None,
+        }
+    }
+}
+
+// 内部节点中key应当进入的孩子的下标，即不大于key的分隔键的个数
+fn child_index<K, Q: ?Sized, C: Compare<Q>>(keys: &[K], key: &Q) -> usize
+where
+    K: Borrow<Q>,
+{
+    keys.iter().take_while(|&sep| C::compare(sep.borrow(), key) != Ordering::Greater).count()
+}
+
+/// B+树，所有键值对都存放在叶子节点中，叶子节点按键的顺序链接在一起，内部节点只保存分隔键。
+/// 节点的度与`BTree`相同：除根节点外每个节点至少有t - 1个关键字，最多有2t - 1个关键字。
+/// 键的顺序由比较器C决定，默认使用键自身的`Ord`
+pub struct BPlusTree<K, V, C = Natural> {
+    nodes: Vec<BPlusNode<K, V>>,
+    free: Vec<usize>, // 合并节点后空出来的下标，分配新节点时优先使用
+    root: usize,
+    first_leaf: usize,
+    last_leaf: usize,
+    degree: usize,
+    len: usize,
+    marker: PhantomData<C>,
+}
+
+impl<K: Ord, V> BPlusTree<K, V> {
+    /// 构建一棵空的B+树，B+树的度t必须满足t ≥ 2
+    /// # Examples
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree: BPlusTree<i32, char> = BPlusTree::new(2);
+    /// ```
+    pub fn new(degree: usize) -> Self {
+        Self::with_comparator(degree)
+    }
+}
+
+impl<K, V, C: Compare<K>> BPlusTree<K, V, C> {
+    /// 构建一棵使用比较器C决定键顺序的空B+树
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// use ordered_map::Reverse;
+    /// let mut tree: BPlusTree<i32, char, Reverse> = BPlusTree::with_comparator(2);
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// assert_eq!(tree.iter().next(), Some((&2, &'b')));
+    /// ```
+    pub fn with_comparator(degree: usize) -> Self {
+        BPlusTree {
+            nodes: vec![BPlusNode::Leaf(Leaf::new(degree))],
+            free: Vec::new(),
+            root: 0,
+            first_leaf: 0,
+            last_leaf: 0,
+            degree,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// 判断B+树是否为空
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// assert!(tree.is_empty());
+    /// tree.insert(1, 'a');
+    /// assert!(!tree.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 返回键值对的个数，时间复杂度O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(1, 'a');
+    /// tree.insert(1, 'b');
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // 从根节点下降到可能包含key的叶子节点
+    fn find_leaf<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let mut id = self.root;
+        while let BPlusNode::Internal(node) = &self.nodes[id] {
+            id = node.children[child_index::<K, Q, C>(&node.keys, key)];
+        }
+        id
+    }
+
+    // 在叶子节点中查找key，返回叶子节点和key的下标
+    fn search<Q: ?Sized>(&self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let id = self.find_leaf(key);
+        let index = self.leaf(id).slots.keys.iter().position(|k| C::compare(k.borrow(), key) == Ordering::Equal)?;
+        Some((id, index))
+    }
+
+    /// 根据键查找对应的值，找不到返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(1, 'a');
+    /// assert_eq!(tree.get(&1), Some(&'a'));
+    /// assert_eq!(tree.get(&2), None);
+    /// ```
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let (id, index) = self.search(key)?;
+        Some(&self.leaf(id).slots.vals[index])
+    }
+
+    /// 根据键查找对应值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(1, 'a');
+    /// if let Some(value) = tree.get_mut(&1) {
+    ///     *value = 'b';
+    /// }
+    /// assert_eq!(tree.get(&1), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let (id, index) = self.search(key)?;
+        Some(&mut self.leaf_mut(id).slots.vals[index])
+    }
+
+    /// 查找是否存在键
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(1, 'a');
+    /// assert!(tree.contains_key(&1));
+    /// assert!(!tree.contains_key(&2));
+    /// ```
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.search(key).is_some()
+    }
+
+    /// 范围迭代器，先从根节点下降找到范围两端所在的叶子，之后只沿叶子链表移动，可以从两端迭代
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// let res: Vec<i32> = tree.range(3..=5).map(|(_, v)| *v).collect();
+    /// assert_eq!(res, vec![30, 40, 50]);
+    /// let res: Vec<i32> = tree.range(..3).rev().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// assert_eq!(tree.range(11..).next(), None);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> LeafRange<'_, K, V, C> {
+        let front = match range.start_bound() {
+            Bound::Unbounded => (self.first_leaf, 0),
+            Bound::Included(key) | Bound::Excluded(key) => {
+                let id = self.find_leaf(key);
+                let keys = &self.leaf(id).slots.keys;
+                let i = (0..keys.len())
+                    .find(|&i| above_lower(range.start_bound(), &keys[i], C::compare))
+                    .unwrap_or(keys.len());
+                (id, i)
+            }
+        };
+        let back = match range.end_bound() {
+            Bound::Unbounded => (self.last_leaf, self.key_len(self.last_leaf)),
+            Bound::Included(key) | Bound::Excluded(key) => {
+                let id = self.find_leaf(key);
+                let keys = &self.leaf(id).slots.keys;
+                let j = (0..keys.len())
+                    .find(|&j| !below_upper(range.end_bound(), &keys[j], C::compare))
+                    .unwrap_or(keys.len());
+                (id, j)
+            }
+        };
+        let mut iter = LeafRange { tree: self, front, back };
+        // 下边界大于上边界时两端不在同一叶子上也可能交错，此时范围为空
+        let crossed = match (iter.clone().next(), iter.clone().next_back()) {
+            (Some((first, _)), Some((last, _))) => C::compare(first, last) == Ordering::Greater,
+            _ => true,
+        };
+        if crossed {
+            iter.front = iter.back;
+        }
+        iter
+    }
+
+    /// 中序迭代器，沿叶子链表按键的顺序返回键值对，可以从两端迭代
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'a');
+    /// tree.insert(3, 'c');
+    /// let res: Vec<_> = tree.iter().collect();
+    /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    /// ```
+    pub fn iter(&self) -> LeafRange<'_, K, V, C> {
+        self.range(..)
+    }
+
+    /// 检查B+树的所有性质：非根节点的关键字个数在[t - 1, 2t - 1]之间、内部节点的孩子个数比关键字多1、
+    /// 叶子都在同一层、每个节点中的键严格递增且位于双亲的分隔键之间、叶子链表按顺序串起所有叶子，
+    /// 以及键值对个数正确。不满足时返回第一个被发现的问题
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// for key in 0..100 {
+    ///     tree.insert(key, key);
+    /// }
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let mut leaves = Vec::new();
+        self.validate_node(self.root, 0, None, None, &mut leaves)?;
+        let expected = leaves[0].1;
+        if let Some(&(_, actual)) = leaves.iter().find(|&&(_, depth)| depth != expected) {
+            return Err(InvariantViolation::LeafDepth { expected, actual });
+        }
+        // 从first_leaf出发沿着next遍历叶子链表，应当与树中从左到右的叶子一致
+        let mut prev = None;
+        let mut next = Some(self.first_leaf);
+        for &(id, _) in leaves.iter() {
+            if next != Some(id) || self.leaf(id).prev != prev {
+                return Err(InvariantViolation::LeafLink);
+            }
+            prev = Some(id);
+            next = self.leaf(id).next;
+        }
+        if next.is_some() || prev != Some(self.last_leaf) {
+            return Err(InvariantViolation::LeafLink);
+        }
+        let count: usize = leaves.iter().map(|&(id, _)| self.key_len(id)).sum();
+        if count != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                cached: self.len,
+                actual: count,
+            });
+        }
+        Ok(())
+    }
+
+    // 检查以id为根、深度为depth的子树，子树中的键都应当位于[lower, upper)之内，
+    // 按从左到右的顺序把叶子的下标和深度记录到leaves中
+    fn validate_node<'a>(
+        &'a self,
+        id: usize,
+        depth: usize,
+        lower: Option<&'a K>,
+        upper: Option<&'a K>,
+        leaves: &mut Vec<(usize, usize)>,
+    ) -> Result<(), InvariantViolation> {
+        let keys = match &self.nodes[id] {
+            BPlusNode::Leaf(leaf) => &leaf.slots.keys,
+            BPlusNode::Internal(node) => &node.keys,
+        };
+        if keys.len() > max_keys(self.degree) {
+            return Err(InvariantViolation::Overfull {
+                keys: keys.len(),
+                max: max_keys(self.degree),
+            });
+        }
+        if depth > 0 && keys.len() < self.degree - 1 {
+            return Err(InvariantViolation::Underfull {
+                keys: keys.len(),
+                min: self.degree - 1,
+            });
+        }
+        let in_range = |key: &K| {
+            lower.is_none_or(|lower| C::compare(lower, key) != Ordering::Greater)
+                && upper.is_none_or(|upper| C::compare(key, upper) == Ordering::Less)
+        };
+        if !keys.windows(2).all(|w| C::compare(&w[0], &w[1]) == Ordering::Less) || !keys.iter().all(in_range) {
+            return Err(InvariantViolation::KeyOrder);
+        }
+        match &self.nodes[id] {
+            BPlusNode::Leaf(_) => leaves.push((id, depth)),
+            BPlusNode::Internal(node) => {
+                if node.children.len() != keys.len() + 1 {
+                    return Err(InvariantViolation::ChildCount {
+                        keys: keys.len(),
+                        children: node.children.len(),
+                    });
+                }
+                for (i, &child) in node.children.iter().enumerate() {
+                    let lower = if i == 0 { lower } else { Some(&node.keys[i - 1]) };
+                    let upper = node.keys.get(i).or(upper);
+                    self.validate_node(child, depth + 1, lower, upper, leaves)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 开启checked特性时，每次修改之后检查B+树的性质，不满足时panic
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("BPlusTree broken: {}", violation);
+        }
+    }
+}
+
+impl<K, V, C> BPlusTree<K, V, C> {
+    fn leaf(&self, id: usize) -> &Leaf<K, V> {
+        match &self.nodes[id] {
+            BPlusNode::Leaf(leaf) => leaf,
+            BPlusNode::Internal(_) => panic!("BPlusTree broken"),
+        }
+    }
+
+    fn leaf_mut(&mut self, id: usize) -> &mut Leaf<K, V> {
+        match &mut self.nodes[id] {
+            BPlusNode::Leaf(leaf) => leaf,
+            BPlusNode::Internal(_) => panic!("BPlusTree broken"),
+        }
+    }
+
+    fn internal(&self, id: usize) -> &Internal<K> {
+        match &self.nodes[id] {
+            BPlusNode::Internal(node) => node,
+            BPlusNode::Leaf(_) => panic!("BPlusTree broken"),
+        }
+    }
+
+    fn internal_mut(&mut self, id: usize) -> &mut Internal<K> {
+        match &mut self.nodes[id] {
+            BPlusNode::Internal(node) => node,
+            BPlusNode::Leaf(_) => panic!("BPlusTree broken"),
+        }
+    }
+
+    fn is_leaf(&self, id: usize) -> bool {
+        matches!(self.nodes[id], BPlusNode::Leaf(_))
+    }
+
+    fn key_len(&self, id: usize) -> usize {
+        match &self.nodes[id] {
+            BPlusNode::Leaf(leaf) => leaf.slots.keys.len(),
+            BPlusNode::Internal(node) => node.keys.len(),
+        }
+    }
+
+    // 把B+树画成多行，每个节点占一行，内部节点用key写出分隔键，叶子节点用entry写出键值对
+    fn draw(
+        &self,
+        f: &mut Formatter<'_>,
+        key: &dyn Fn(&K, &mut Formatter<'_>) -> fmt::Result,
+        entry: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        let label = |id: &usize, f: &mut Formatter<'_>| {
+            write!(f, "[")?;
+            match &self.nodes[*id] {
+                BPlusNode::Leaf(leaf) => {
+                    for (i, (k, v)) in leaf.slots.keys.iter().zip(leaf.slots.vals.iter()).enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        entry(k, v, f)?;
+                    }
+                }
+                BPlusNode::Internal(node) => {
+                    for (i, k) in node.keys.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        key(k, f)?;
+                    }
+                }
+            }
+            write!(f, "]")
+        };
+        draw_tree(f, self.root, &label, &|id: &usize| match &self.nodes[*id] {
+            BPlusNode::Leaf(_) => Vec::new(),
+            BPlusNode::Internal(node) => node.children.iter().map(|&child| Some(child)).collect(),
+        })
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> BPlusTree<K, V, C> {
+    fn alloc(&mut self, node: BPlusNode<K, V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // 取出节点的内容并回收下标
+    fn release(&mut self, id: usize) -> BPlusNode<K, V> {
+        self.free.push(id);
+        let empty = Leaf {
+            slots: Slots::new(Vec::new(), Vec::new(), Vec::new()),
+            prev: None,
+            next: None,
+        };
+        mem::replace(&mut self.nodes[id], BPlusNode::Leaf(empty))
+    }
+
+    /// 插入键值对，如果键已经存在，则替换旧值并返回旧值
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// assert_eq!(tree.insert(1, 'a'), None);
+    /// assert_eq!(tree.insert(1, 'b'), Some('a'));
+    /// assert_eq!(tree.get(&1), Some(&'b'));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.key_len(self.root) == max_keys(self.degree) {
+            let old_root = self.root;
+            let mut children = Vec::with_capacity(2 * self.degree);
+            children.push(old_root);
+            let root = Internal::new(Vec::with_capacity(max_keys(self.degree)), Vec::new(), children);
+            self.root = self.alloc(BPlusNode::Internal(root));
+            self.split_child(self.root, 0);
+        }
+        // 与BTree相同，下降之前先分裂满的孩子，保证插入叶子时叶子非满
+        let mut id = self.root;
+        while !self.is_leaf(id) {
+            let mut i = child_index::<K, K, C>(&self.internal(id).keys, &key);
+            if self.key_len(self.internal(id).children[i]) == max_keys(self.degree) {
+                self.split_child(id, i);
+                if C::compare(&self.internal(id).keys[i], &key) != Ordering::Greater {
+                    i += 1;
+                }
+            }
+            id = self.internal(id).children[i];
+        }
+        let leaf = self.leaf_mut(id);
+        let i = leaf.slots.keys.iter().take_while(|&k| C::compare(k, &key) == Ordering::Less).count();
+        let old = if i < leaf.slots.keys.len() && C::compare(&leaf.slots.keys[i], &key) == Ordering::Equal {
+            Some(mem::replace(&mut leaf.slots.vals[i], value))
+        } else {
+            leaf.slots.insert(i, key, value);
+            self.len += 1;
+            None
+        };
+        // 键已经存在时下降过程中也可能分裂了节点
+        self.check();
+        old
+    }
+
+    // 分裂parent的第i个孩子，输入必须满足：parent是非满的内部节点，第i个孩子是满节点。
+    // 叶子分裂后右半部分的第一个键复制一份作为分隔键，新叶子插入到叶子链表中；
+    // 内部节点的分裂与BTree相同，中间的分隔键上升到parent
+    fn split_child(&mut self, parent: usize, i: usize) {
+        let mid = split_index(self.degree);
+        let child = self.internal(parent).children[i];
+        let (sep, right) = match &mut self.nodes[child] {
+            BPlusNode::Leaf(leaf) => {
+                let slots = leaf.slots.split_off(mid);
+                let sep = slots.keys[0].clone();
+                let right = Leaf {
+                    slots,
+                    prev: Some(child),
+                    next: leaf.next,
+                };
+                (sep, BPlusNode::Leaf(right))
+            }
+            BPlusNode::Internal(node) => {
+                let (sep, (), right) = node.split(mid);
+                (sep, BPlusNode::Internal(right))
+            }
+        };
+        let right_next = match &right {
+            BPlusNode::Leaf(leaf) => Some(leaf.next),
+            BPlusNode::Internal(_) => None,
+        };
+        let right = self.alloc(right);
+        if let Some(next) = right_next {
+            self.leaf_mut(child).next = Some(right);
+            match next {
+                Some(next) => self.leaf_mut(next).prev = Some(right),
+                None => self.last_leaf = right,
+            }
+        }
+        let node = self.internal_mut(parent);
+        node.insert(i, sep, ());
+        node.children.insert(i + 1, right);
+    }
+
+    /// 删除键值对，返回被删除的值，找不到时返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BPlusTree;
+    /// let mut tree = BPlusTree::new(2);
+    /// tree.insert(1, 'a');
+    /// assert_eq!(tree.remove(&1), Some('a'));
+    /// assert_eq!(tree.remove(&1), None);
+    /// ```
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        // 与BTree的delete相同，下降之前保证孩子至少有t个关键字
+        let mut id = self.root;
+        while !self.is_leaf(id) {
+            let i = child_index::<K, Q, C>(&self.internal(id).keys, key);
+            let i = self.fill_child(id, i);
+            let child = self.internal(id).children[i];
+            if id == self.root && self.key_len(id) == 0 {
+                self.release(id);
+                self.root = child;
+            }
+            id = child;
+        }
+        let leaf = self.leaf_mut(id);
+        let value = match leaf.slots.keys.iter().position(|k| C::compare(k.borrow(), key) == Ordering::Equal) {
+            Some(i) => {
+                let (_, value) = leaf.slots.remove(i);
+                self.len -= 1;
+                Some(value)
+            }
+            None => None,
+        };
+        // 没有找到键时下降过程中也可能调整了树的结构
+        self.check();
+        value
+    }
+
+    // 保证parent的第i个孩子至少有t个关键字：先尝试从左右兄弟借一个关键字，否则与兄弟合并，
+    // 返回原来第i个孩子中的键合并后所在孩子的下标
+    fn fill_child(&mut self, parent: usize, i: usize) -> usize {
+        let t = self.degree;
+        let children = &self.internal(parent).children;
+        let count = children.len();
+        if self.key_len(children[i]) >= t {
+            i
+        } else if i > 0 && self.key_len(children[i - 1]) >= t {
+            self.borrow_from_left(parent, i);
+            i
+        } else if i + 1 < count && self.key_len(children[i + 1]) >= t {
+            self.borrow_from_right(parent, i);
+            i
+        } else if i + 1 < count {
+            self.merge_children(parent, i);
+            i
+        } else {
+            self.merge_children(parent, i - 1);
+            i - 1
+        }
+    }
+
+    // 取出双亲parent以及它的第i个和第i + 1个孩子的可变借用
+    fn siblings_mut(&mut self, parent: usize, i: usize) -> (&mut Internal<K>, &mut BPlusNode<K, V>, &mut BPlusNode<K, V>) {
+        let (left, right) = {
+            let children = &self.internal(parent).children;
+            (children[i], children[i + 1])
+        };
+        match self.nodes.get_disjoint_mut([parent, left, right]) {
+            Ok([BPlusNode::Internal(parent), left, right]) => (parent, left, right),
+            _ => panic!("BPlusTree broken"),
+        }
+    }
+
+    // 把左兄弟的最后一个关键字移到第i个孩子的最前面。
+    // 叶子之间直接移动键值对，分隔键更新为第i个孩子的第一个键；内部节点与BTree相同，经过分隔键旋转
+    fn borrow_from_left(&mut self, parent: usize, i: usize) {
+        match self.siblings_mut(parent, i - 1) {
+            (parent, BPlusNode::Leaf(left), BPlusNode::Leaf(child)) => {
+                let (key, value, _) = left.slots.pop_back();
+                parent.keys[i - 1] = key.clone();
+                child.slots.push_front(key, value, None);
+            }
+            (parent, BPlusNode::Internal(left), BPlusNode::Internal(child)) => {
+                let sep = (&mut parent.keys[i - 1], &mut parent.vals[i - 1]);
+                Slots::rotate_right(sep, left, child);
+            }
+            _ => panic!("BPlusTree broken"),
+        }
+    }
+
+    // 把右兄弟的第一个关键字移到第i个孩子的最后面，叶子之间的分隔键更新为右兄弟新的第一个键
+    fn borrow_from_right(&mut self, parent: usize, i: usize) {
+        match self.siblings_mut(parent, i) {
+            (parent, BPlusNode::Leaf(child), BPlusNode::Leaf(right)) => {
+                let (key, value, _) = right.slots.pop_front();
+                parent.keys[i] = right.slots.keys[0].clone();
+                child.slots.push_back(key, value, None);
+            }
+            (parent, BPlusNode::Internal(child), BPlusNode::Internal(right)) => {
+                let sep = (&mut parent.keys[i], &mut parent.vals[i]);
+                Slots::rotate_left(sep, child, right);
+            }
+            _ => panic!("BPlusTree broken"),
+        }
+    }
+
+    // 把第i + 1个孩子合并进第i个孩子，两个孩子都只有t - 1个关键字。
+    // 叶子合并时直接丢弃分隔键并把右叶子从链表中摘除，内部节点合并时分隔键下降到合并后的节点中
+    fn merge_children(&mut self, parent: usize, i: usize) {
+        let node = self.internal_mut(parent);
+        let sep = node.remove(i);
+        let right = node.children.remove(i + 1);
+        let left = node.children[i];
+        match self.release(right) {
+            BPlusNode::Leaf(right) => {
+                let left_leaf = self.leaf_mut(left);
+                left_leaf.slots.append(right.slots);
+                left_leaf.next = right.next;
+                match right.next {
+                    Some(next) => self.leaf_mut(next).prev = Some(left),
+                    None => self.last_leaf = left,
+                }
+            }
+            BPlusNode::Internal(right) => self.internal_mut(left).merge(sep, right),
+        }
+    }
+}
+
+/// B+树的范围迭代器，只沿叶子链表移动。front是下一个从前端输出的位置，
+/// back是从后端尚未输出的位置的上界（不包含），两端在同一叶子上相遇时结束
+pub struct LeafRange<'a, K, V, C = Natural> {
+    tree: &'a BPlusTree<K, V, C>,
+    front: (usize, usize),
+    back: (usize, usize),
+}
+
+impl<'a, K, V, C> Clone for LeafRange<'a, K, V, C> {
+    fn clone(&self) -> Self {
+        LeafRange {
+            tree: self.tree,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, K, V, C> Iterator for LeafRange<'a, K, V, C> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (id, i) = self.front;
+            if id == self.back.0 && i >= self.back.1 {
+                return None;
+            }
+            let leaf = self.tree.leaf(id);
+            if i < leaf.slots.keys.len() {
+                self.front.1 += 1;
+                return Some((&leaf.slots.keys[i], &leaf.slots.vals[i]));
+            }
+            self.front = (leaf.next?, 0);
+        }
+    }
+}
+
+impl<'a, K, V, C> DoubleEndedIterator for LeafRange<'a, K, V, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let (id, j) = self.back;
+            if id == self.front.0 && j <= self.front.1 {
+                return None;
+            }
+            let leaf = self.tree.leaf(id);
+            if j > 0 {
+                self.back.1 -= 1;
+                return Some((&leaf.slots.keys[j - 1], &leaf.slots.vals[j - 1]));
+            }
+            let prev = leaf.prev?;
+            self.back = (prev, self.tree.key_len(prev));
+        }
+    }
+}
+
+/// `{}`按键的顺序打印所有键值对，`{:#}`把树画成多行，内部节点只有分隔键，叶子节点包含键值对
+/// # Example
+/// ```
+/// use an_ok_btree::BPlusTree;
+/// let mut tree = BPlusTree::new(2);
+/// for key in 1..=4 {
+///     tree.insert(key, key * 10);
+/// }
+/// assert_eq!(tree.to_string(), "{1: 10, 2: 20, 3: 30, 4: 40}");
+/// assert_eq!(format!("{:#}", tree), "[2]\n├── [1: 10]\n└── [2: 20, 3: 30, 4: 40]");
+/// ```
+impl<K: Display, V: Display, C: Compare<K>> Display for BPlusTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.draw(f, &|key, f| write!(f, "{}", key), &|key, value, f| write!(f, "{}: {}", key, value));
+        }
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", key, value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// `{:?}`按键的顺序打印所有键值对，`{:#?}`把树画成多行
+/// # Example
+/// ```
+/// use an_ok_btree::BPlusTree;
+/// let mut tree = BPlusTree::new(2);
+/// for key in 1..=4 {
+///     tree.insert(key, 'a');
+/// }
+/// assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'a', 3: 'a', 4: 'a'}");
+/// assert_eq!(format!("{:#?}", tree), "[2]\n├── [1: 'a']\n└── [2: 'a', 3: 'a', 4: 'a']");
+/// ```
+impl<K: Debug, V: Debug, C: Compare<K>> Debug for BPlusTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.draw(f, &|key, f| write!(f, "{:?}", key), &|key, value, f| write!(f, "{:?}: {:?}", key, value))
+        } else {
+            f.debug_map().entries(self.iter()).finish()
+        }
+    }
+}
@@ -1,14 +1,33 @@
-use std::collections::VecDeque;
-use crate::node::Node;
-use std::fmt::Debug;
-use crate::iterator::TraverseIter;
-
-pub struct BTree<T> {
-    root: Node<T>,
-    degree: usize,
+use crate::iterator::{Iter, KeyIter, KeyRefIter, RangeIter, TraverseIter};
+use crate::BTreeMap;
+use ordered_map::{Compare, Difference, Intersection, InvariantViolation, Natural, SymmetricDifference, Union};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds, Sub};
+
+// 集合的中序迭代器，只输出关键字
+type SetIter<'a, T> = KeyIter<Iter<'a, T, ()>>;
+
+/// 插入已经存在的关键字时的处理方式，在构建B树时选定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// 集合语义，保留树中原有的关键字，拒绝插入
+    Reject,
+    /// 集合语义，用新插入的关键字替换树中相等的关键字
+    Replace,
+    /// 多重集合语义，每次插入都保存一份关键字
+    Allow,
 }
 
-impl<T: PartialOrd + Clone + Debug> BTree<T> {
+/// 基于`BTreeMap<T, ()>`的B树集合，值为单元类型。
+/// 关键字的顺序由比较器C决定，默认使用关键字自身的`Ord`
+pub struct BTree<T, C = Natural> {
+    map: BTreeMap<T, (), C>,
+    policy: DuplicatePolicy,
+}
+
+impl<T: Ord> BTree<T> {
     /// 构建一棵空的B树,设置B树的度t = 2,
     /// B树的度t必须满足t ≥ 2,
     /// 除了根节点以外的每个节点必须至少有t - 1个关键字，
@@ -20,13 +39,88 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// let mut tree: BTree<i32> = BTree::new(2);
     /// ```
     pub fn new(degree: usize) -> Self {
-        let new_node = Node::new(degree, None, None);
+        Self::with_policy(degree, DuplicatePolicy::Allow)
+    }
+
+    /// 构建一棵空的B树，并指定插入重复关键字时的处理方式，`new`等价于使用`DuplicatePolicy::Allow`
+    /// # Example
+    /// ```
+    /// use an_ok_btree::{BTree, DuplicatePolicy};
+    /// let mut set = BTree::with_policy(2, DuplicatePolicy::Reject);
+    /// assert!(set.insert(1));
+    /// assert!(!set.insert(1));
+    /// assert_eq!(set.len(), 1);
+    /// let mut multiset = BTree::with_policy(2, DuplicatePolicy::Allow);
+    /// assert!(multiset.insert(1));
+    /// assert!(multiset.insert(1));
+    /// assert_eq!(multiset.count(&1), 2);
+    /// ```
+    pub fn with_policy(degree: usize, policy: DuplicatePolicy) -> Self {
+        Self::with_comparator(degree, policy)
+    }
+
+    /// 由有序的关键字在O(n)时间内批量构建B树，节点尽量装满，避免逐个插入时反复分裂。
+    /// 与`new`相同使用`DuplicatePolicy::Allow`，重复的关键字都会保留；输入无序时先排序，时间复杂度变为O(n log n)
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let tree = BTree::from_sorted_iter(2, 0..100);
+    /// assert_eq!(tree.len(), 100);
+    /// assert!(tree.contain(&42));
+    /// let tree = BTree::from_sorted_iter(2, vec![3, 1, 3, 2]);
+    /// let res: Vec<&i32> = tree.iter().collect();
+    /// assert_eq!(res, vec![&1, &2, &3, &3]);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(degree: usize, keys: I) -> Self {
+        Self::from_sorted_iter_with_comparator(degree, keys)
+    }
+}
+
+impl<T, C: Compare<T>> BTree<T, C> {
+    /// 构建一棵使用比较器C决定关键字顺序的空B树，并指定重复关键字的处理方式
+    /// # Example
+    /// ```
+    /// use an_ok_btree::{BTree, DuplicatePolicy};
+    /// use ordered_map::Reverse;
+    /// let mut tree: BTree<i32, Reverse> = BTree::with_comparator(2, DuplicatePolicy::Reject);
+    /// for key in 1..=5 {
+    ///     tree.insert(key);
+    /// }
+    /// assert!(!tree.insert(3));
+    /// assert_eq!(tree.find_min(), Some(&5));
+    /// ```
+    pub fn with_comparator(degree: usize, policy: DuplicatePolicy) -> Self {
+        BTree {
+            map: BTreeMap::with_comparator(degree),
+            policy,
+        }
+    }
+
+    /// 与`from_sorted_iter`相同，输入按比较器C的顺序排列
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// use ordered_map::Reverse;
+    /// let tree: BTree<i32, Reverse> = BTree::from_sorted_iter_with_comparator(2, vec![1, 3, 2]);
+    /// let res: Vec<&i32> = tree.iter().collect();
+    /// assert_eq!(res, vec![&3, &2, &1]);
+    /// ```
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = T>>(degree: usize, keys: I) -> Self {
+        let mut pairs: Vec<(T, ())> = keys.into_iter().map(|key| (key, ())).collect();
+        if pairs.windows(2).any(|w| C::compare(&w[0].0, &w[1].0) == Ordering::Greater) {
+            pairs.sort_by(|a, b| C::compare(&a.0, &b.0));
+        }
         BTree {
-            root: new_node,
-            degree,
+            map: BTreeMap::from_sorted_vec(degree, pairs),
+            policy: DuplicatePolicy::Allow,
         }
     }
 
+    /// 返回构建时选定的重复关键字处理方式
+    pub fn policy(&self) -> DuplicatePolicy {
+        self.policy
+    }
+
     /// 判断当前B树是否为空
     /// # Example
     /// ```
@@ -35,30 +129,62 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.root.children_len() == 0 && self.root.key_len() == 0
+        self.map.is_empty()
     }
 
-    /// 向B树中插入关键字,支持重复插入
+    /// 返回B树中关键字的个数，重复插入的关键字分别计数，时间复杂度O(1)
     /// # Example
     /// ```
     /// use an_ok_btree::BTree;
     /// let mut tree = BTree::new(2);
     /// tree.insert(1);
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(tree.len(), 3);
+    /// tree.delete(3);
+    /// assert_eq!(tree.len(), 3);
+    /// tree.delete(1);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 向B树中插入关键字，关键字已经存在时按构建时选定的`DuplicatePolicy`处理，
+    /// 返回关键字的个数是否增加：`Allow`总是返回true，`Reject`和`Replace`在关键字已存在时返回false
+    /// # Example
+    /// ```
+    /// use an_ok_btree::{BTree, DuplicatePolicy};
+    /// let mut tree = BTree::new(2);
+    /// tree.insert(1);
     /// assert!(tree.contain(&1));
     /// tree.insert(2);
     /// assert!(tree.contain(&2));
+    /// let mut set = BTree::with_policy(2, DuplicatePolicy::Replace);
+    /// assert!(set.insert(1));
+    /// assert!(!set.insert(1));
+    /// assert_eq!(set.len(), 1);
     /// ```
-    pub fn insert(&mut self, key: T) {
-        if self.root.is_full_keys() {
-            let mut new_root = Node::new(self.degree, None, None);
-            std::mem::swap(&mut self.root, &mut new_root);
-            self.root.insert_child(0, new_root);
-            self.root.split_child(0);
-        }
-        self.root.insert_non_full(key);
+    pub fn insert(&mut self, key: T) -> bool {
+        let key = match self.policy {
+            DuplicatePolicy::Allow => key,
+            DuplicatePolicy::Reject => {
+                if self.map.contains_key(&key) {
+                    return false;
+                }
+                key
+            }
+            DuplicatePolicy::Replace => match self.map.replace_key(key) {
+                Ok(_) => return false,
+                Err(key) => key,
+            },
+        };
+        self.map.insert_multi(key, ());
+        self.check();
+        true
     }
 
-    /// 从B树中删除关键字，就算没找到关键字也会调整树的结构
+    /// 从B树中删除关键字的一份拷贝，就算没找到关键字也会调整树的结构
     /// # Example
     /// ```
     /// use an_ok_btree::BTree;
@@ -70,7 +196,74 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn delete(&mut self, key: T) {
-        self.root.delete(key);
+        self.map.remove(&key);
+        self.check();
+    }
+
+    /// 删除关键字的所有拷贝，返回删除的个数。
+    /// 每份拷贝单独从根下降删除一次，时间复杂度O(k log n)，k为删除的个数
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// for key in [1, 2, 1, 3, 1].iter() {
+    ///     tree.insert(*key);
+    /// }
+    /// assert_eq!(tree.remove_all(&1), 3);
+    /// assert_eq!(tree.remove_all(&1), 0);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn remove_all<Q: ?Sized>(&mut self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let mut removed = 0;
+        while self.map.remove(key).is_some() {
+            removed += 1;
+        }
+        self.check();
+        removed
+    }
+
+    /// 返回关键字在树中的拷贝个数，集合语义下只会是0或1
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// for key in [1, 2, 1, 3, 1].iter() {
+    ///     tree.insert(*key);
+    /// }
+    /// assert_eq!(tree.count(&1), 3);
+    /// assert_eq!(tree.count(&2), 1);
+    /// assert_eq!(tree.count(&4), 0);
+    /// ```
+    pub fn count<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.get_all(key).count()
+    }
+
+    /// 迭代树中与key相等的所有关键字的借用，多重集合语义下可以访问每一份拷贝
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// tree.insert((1, 'a'));
+    /// tree.insert((2, 'b'));
+    /// tree.insert((1, 'a'));
+    /// let res: Vec<&(i32, char)> = tree.get_all(&(1, 'a')).collect();
+    /// assert_eq!(res, vec![&(1, 'a'), &(1, 'a')]);
+    /// ```
+    pub fn get_all<'a, Q: ?Sized>(&'a self, key: &'a Q) -> KeyRefIter<RangeIter<'a, T, ()>>
+    where
+        T: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let range = (Bound::Included(key), Bound::Included(key));
+        KeyRefIter(RangeIter::new(self.map.root(), range, C::compare))
     }
 
     /// 查找是否存在关键字
@@ -82,8 +275,12 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// assert_eq!(tree.contain(&1), true);
     /// assert_eq!(tree.contain(&2), false);
     /// ```
-    pub fn contain(&self, key: &T) -> bool {
-        self.root.search(key).is_some()
+    pub fn contain<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.map.contains_key(key)
     }
 
     /// 返回B树中的最大关键字
@@ -95,13 +292,10 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// tree.insert(1);
     /// tree.insert(2);
     /// tree.insert(3);
-    /// assert_eq!(tree.find_max(), Some(3));
+    /// assert_eq!(tree.find_max(), Some(&3));
     /// ```
-    pub fn find_max(&self) -> Option<T> {
-        if self.is_empty() {
-            return None;
-        }
-        Some(self.root.max_key())
+    pub fn find_max(&self) -> Option<&T> {
+        self.map.max_pair().map(|(key, _)| key)
     }
 
     /// 返回B树中的最小关键字
@@ -113,13 +307,10 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// tree.insert(1);
     /// tree.insert(2);
     /// tree.insert(3);
-    /// assert_eq!(tree.find_min(), Some(1));
+    /// assert_eq!(tree.find_min(), Some(&1));
     /// ```
-    pub fn find_min(&self) -> Option<T> {
-        if self.is_empty() {
-            return None;
-        }
-        Some(self.root.min_key())
+    pub fn find_min(&self) -> Option<&T> {
+        self.map.min_pair().map(|(key, _)| key)
     }
 
     /// 返回B树中的给定关键字的后继，如果给定节点不存在或后继不存在则返回None
@@ -130,33 +321,12 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// tree.insert(1);
     /// tree.insert(2);
     /// tree.insert(3);
-    /// assert_eq!(tree.successor(1), Some(2));
-    /// assert_eq!(tree.successor(3), None);
-    /// assert_eq!(tree.successor(0), None);
-    /// ```
-    pub fn successor(&self, key: T) -> Option<T> {
-        if let Some((node, index)) = self.root.search(&key) {
-            return if !node.is_leaf() {
-                Some(node.get_child(index + 1).min_key())
-            } else if index < node.key_len() - 1 {
-                Some(node.get_key(index + 1).clone())
-            } else {
-                let mut current = &self.root;
-                let mut succ = None;
-                while !current.is_leaf() {
-                    let mut i = 0;
-                    while i < current.key_len() && key > *current.get_key(i) {
-                        i += 1;
-                    }
-                    if i < current.key_len() && key < *current.get_key(i) {
-                        succ = Some(current.get_key(i).clone());
-                    }
-                    current = current.get_child(i);
-                }
-                succ
-            }
-        }
-        None
+    /// assert_eq!(tree.successor(&1), Some(&2));
+    /// assert_eq!(tree.successor(&3), None);
+    /// assert_eq!(tree.successor(&0), None);
+    /// ```
+    pub fn successor(&self, key: &T) -> Option<&T> {
+        self.map.successor(key).map(|(key, _)| key)
     }
 
     /// 返回B树中的给定关键字的前驱，如果给定节点不存在或后继不存在则返回None
@@ -167,34 +337,73 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// tree.insert(1);
     /// tree.insert(2);
     /// tree.insert(3);
-    /// assert_eq!(tree.predecessor(3), Some(2));
-    /// assert_eq!(tree.predecessor(1), None);
-    /// assert_eq!(tree.predecessor(4), None);
-    /// ```
-    pub fn predecessor(&self, key: T) -> Option<T> {
-        if let Some((node, index)) = self.root.search(&key) {
-            return if !node.is_leaf() {
-                Some(node.get_child(index).max_key())
-            } else if index > 0 {
-                Some(node.get_key(index - 1).clone())
-            } else {
-                let mut current = &self.root;
-                let mut pred = None;
-                while !current.is_leaf() {
-                    let mut i = 0;
-                    while i < current.key_len() && key > *current.get_key(i) {
-                        pred = Some(current.get_key(i).clone());
-                        i += 1;
-                    }
-                    current = current.get_child(i);
-                }
-                pred
-            }
+    /// assert_eq!(tree.predecessor(&3), Some(&2));
+    /// assert_eq!(tree.predecessor(&1), None);
+    /// assert_eq!(tree.predecessor(&4), None);
+    /// ```
+    pub fn predecessor(&self, key: &T) -> Option<&T> {
+        self.map.predecessor(key).map(|(key, _)| key)
+    }
+
+    /// 中序遍历迭代器，返回关键字的借用，不要求关键字实现Clone，可以从两端迭代
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// tree.insert(String::from("b"));
+    /// tree.insert(String::from("a"));
+    /// let res: Vec<&String> = tree.iter().collect();
+    /// assert_eq!(res, vec!["a", "b"]);
+    /// ```
+    pub fn iter(&self) -> KeyRefIter<Iter<'_, T, ()>> {
+        KeyRefIter(self.map.iter())
+    }
+
+    /// 检查B树的所有性质，同`BTreeMap::validate`。只有重复关键字的处理方式为`Allow`时才允许相等的关键字
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// for key in [3, 1, 3, 2].iter() {
+    ///     tree.insert(*key);
+    /// }
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        self.map.validate_keys(self.policy == DuplicatePolicy::Allow)
+    }
+
+    // 开启checked特性时，插入和删除之后按重复关键字的处理方式检查关键字是否唯一，B树的结构已经由BTreeMap检查
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("BTree broken: {}", violation);
         }
-        None
     }
+}
 
-    /// 中序遍历迭代器
+impl<T: Debug, C> BTree<T, C> {
+    /// 生成DOT格式的图，每个节点画成一个record，孩子的指针画在关键字之间，
+    /// 可以保存为文件后用Graphviz绘制：`dot -Tpng tree.dot -o tree.png`
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// for key in 1..=4 {
+    ///     tree.insert(key);
+    /// }
+    /// let dot = tree.to_dot();
+    /// assert!(dot.starts_with("digraph BTree {"));
+    /// assert!(dot.contains("0 [label=\"<c0>|2|<c1>\"];\n    0:c0 -> 1;\n    1 [label=\"1\"];\n    0:c1 -> 2;\n    2 [label=\"3|4\"];\n"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.map.root().to_dot("BTree")
+    }
+}
+
+// 以下迭代器和集合运算按值返回关键字，需要关键字实现Clone
+impl<T: Clone, C: Compare<T>> BTree<T, C> {
+    /// 中序遍历迭代器，可以从两端迭代
     /// # Example
     /// ```
     /// use an_ok_btree::BTree;
@@ -205,15 +414,106 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// tree.insert(4);
     /// let res: Vec<i32> = tree.inorder_iter().collect();
     /// assert_eq!(res, vec![1,2,3,4]);
+    /// let res: Vec<i32> = tree.inorder_iter().rev().collect();
+    /// assert_eq!(res, vec![4,3,2,1]);
+    /// assert_eq!(tree.inorder_iter().len(), 4);
+    /// ```
+    pub fn inorder_iter(&self) -> SetIter<'_, T> {
+        KeyIter(self.map.iter())
+    }
+
+    /// 范围迭代器，按顺序返回范围内的关键字，可以从两端迭代
+    /// # Example
     /// ```
-    pub fn inorder_iter(&self) -> TraverseIter<T>{
-        let mut buf = Vec::new();
-        self.root.in_order(&mut buf);
-        let mut queue = VecDeque::new();
-        for x in buf {
-            queue.push_back(x);
+    /// use an_ok_btree::BTree;
+    /// let mut tree = BTree::new(2);
+    /// for key in 1..=10 {
+    ///     tree.insert(key);
+    /// }
+    /// let res: Vec<i32> = tree.range(3..=5).collect();
+    /// assert_eq!(res, vec![3, 4, 5]);
+    /// let res: Vec<i32> = tree.range(..3).rev().collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// assert_eq!(tree.range(11..).next(), None);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> KeyIter<RangeIter<'_, T, ()>> {
+        KeyIter(self.map.range(range))
+    }
+
+    /// 并集迭代器，按键的顺序返回两棵树中的关键字，两棵树都有的键只返回当前树的关键字
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut a = BTree::new(2);
+    /// let mut b = BTree::new(2);
+    /// for key in 1..=4 {
+    ///     a.insert(key);
+    ///     b.insert(key + 2);
+    /// }
+    /// let res: Vec<i32> = a.union(&b).collect();
+    /// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<SetIter<'a, T>> {
+        Union::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a, b))
+    }
+
+    /// 交集迭代器，按键的顺序返回两棵树都有的键对应的当前树的关键字
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut a = BTree::new(2);
+    /// let mut b = BTree::new(2);
+    /// for key in 1..=4 {
+    ///     a.insert(key);
+    ///     b.insert(key + 2);
+    /// }
+    /// let res: Vec<i32> = a.intersection(&b).collect();
+    /// assert_eq!(res, vec![3, 4]);
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<SetIter<'a, T>> {
+        Intersection::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a, b))
+    }
+
+    /// 差集迭代器，按键的顺序返回只在当前树中的关键字
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut a = BTree::new(2);
+    /// let mut b = BTree::new(2);
+    /// for key in 1..=4 {
+    ///     a.insert(key);
+    ///     b.insert(key + 2);
+    /// }
+    /// let res: Vec<i32> = a.difference(&b).collect();
+    /// assert_eq!(res, vec![1, 2]);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<SetIter<'a, T>> {
+        Difference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a, b))
+    }
+
+    /// 对称差迭代器，按键的顺序返回只在其中一棵树中的关键字
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTree;
+    /// let mut a = BTree::new(2);
+    /// let mut b = BTree::new(2);
+    /// for key in 1..=4 {
+    ///     a.insert(key);
+    ///     b.insert(key + 2);
+    /// }
+    /// let res: Vec<i32> = a.symmetric_difference(&b).collect();
+    /// assert_eq!(res, vec![1, 2, 5, 6]);
+    /// ```
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<SetIter<'a, T>> {
+        SymmetricDifference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a, b))
+    }
+
+    // 由有序的关键字构建与当前树的度和重复关键字处理方式都相同的新树
+    fn build_from<I: Iterator<Item = T>>(&self, keys: I) -> Self {
+        BTree {
+            map: BTreeMap::from_sorted_vec(self.map.degree(), keys.map(|key| (key, ())).collect()),
+            policy: self.policy,
         }
-        TraverseIter::new(queue)
     }
 
     /// 层序遍历迭代器
@@ -228,20 +528,133 @@ impl<T: PartialOrd + Clone + Debug> BTree<T> {
     /// let res: Vec<i32> = tree.levelorder_iter().collect();
     /// assert_eq!(res, vec![2,1,3,4]);
     /// ```
-    pub fn levelorder_iter(&self) -> TraverseIter<T>{
-        let mut buf = Vec::new();
-        self.root.level_order(&mut buf);
+    pub fn levelorder_iter(&self) -> KeyIter<TraverseIter<'_, T, ()>> {
+        KeyIter(self.map.levelorder_iter())
+    }
+}
+
+/// 返回两棵树的并集构成的新树
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut a = BTree::new(2);
+/// let mut b = BTree::new(2);
+/// for key in 1..=4 {
+///     a.insert(key);
+///     b.insert(key + 2);
+/// }
+/// let res: Vec<i32> = (&a | &b).inorder_iter().collect();
+/// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+/// ```
+impl<T: Clone, C: Compare<T>> BitOr<&BTree<T, C>> for &BTree<T, C> {
+    type Output = BTree<T, C>;
+
+    fn bitor(self, rhs: &BTree<T, C>) -> BTree<T, C> {
+        self.build_from(self.union(rhs))
+    }
+}
+
+/// 返回两棵树的交集构成的新树
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut a = BTree::new(2);
+/// let mut b = BTree::new(2);
+/// for key in 1..=4 {
+///     a.insert(key);
+///     b.insert(key + 2);
+/// }
+/// let res: Vec<i32> = (&a & &b).inorder_iter().collect();
+/// assert_eq!(res, vec![3, 4]);
+/// ```
+impl<T: Clone, C: Compare<T>> BitAnd<&BTree<T, C>> for &BTree<T, C> {
+    type Output = BTree<T, C>;
+
+    fn bitand(self, rhs: &BTree<T, C>) -> BTree<T, C> {
+        self.build_from(self.intersection(rhs))
+    }
+}
 
-        let mut queue = VecDeque::new();
-        for x in buf {
-            queue.push_back(x);
+/// 返回两棵树的差集构成的新树
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut a = BTree::new(2);
+/// let mut b = BTree::new(2);
+/// for key in 1..=4 {
+///     a.insert(key);
+///     b.insert(key + 2);
+/// }
+/// let res: Vec<i32> = (&a - &b).inorder_iter().collect();
+/// assert_eq!(res, vec![1, 2]);
+/// ```
+impl<T: Clone, C: Compare<T>> Sub<&BTree<T, C>> for &BTree<T, C> {
+    type Output = BTree<T, C>;
+
+    fn sub(self, rhs: &BTree<T, C>) -> BTree<T, C> {
+        self.build_from(self.difference(rhs))
+    }
+}
+
+/// 返回两棵树的对称差构成的新树
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut a = BTree::new(2);
+/// let mut b = BTree::new(2);
+/// for key in 1..=4 {
+///     a.insert(key);
+///     b.insert(key + 2);
+/// }
+/// let res: Vec<i32> = (&a ^ &b).inorder_iter().collect();
+/// assert_eq!(res, vec![1, 2, 5, 6]);
+/// ```
+impl<T: Clone, C: Compare<T>> BitXor<&BTree<T, C>> for &BTree<T, C> {
+    type Output = BTree<T, C>;
+
+    fn bitxor(self, rhs: &BTree<T, C>) -> BTree<T, C> {
+        self.build_from(self.symmetric_difference(rhs))
+    }
+}
+
+/// 将B树打印成字符串，`{}`打印成一行嵌套的形式，`{:#}`把树画成多行，每个节点占一行
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut tree = BTree::new(2);
+/// for key in 1..=4 {
+///     tree.insert(key);
+/// }
+/// assert_eq!(tree.to_string(), "BNode([2], [BNode([1], []), BNode([3, 4], [])])");
+/// assert_eq!(format!("{:#}", tree), "[2]\n├── [1]\n└── [3, 4]");
+/// ```
+impl<T: Debug, C> Display for BTree<T, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.map.root().draw(f, &|key, _, f| write!(f, "{:?}", key))
+        } else {
+            write!(f, "{:?}", self.map.root())
         }
-        TraverseIter::new(queue)
     }
 }
 
-impl<T: Debug> ToString for BTree<T> {
-    fn to_string(&self) -> String {
-        format!("{:?}", self.root)
+/// `{:?}`按顺序打印所有关键字，`{:#?}`与`{:#}`相同，把树画成多行
+/// # Example
+/// ```
+/// use an_ok_btree::BTree;
+/// let mut tree = BTree::new(2);
+/// for key in 1..=4 {
+///     tree.insert(key);
+/// }
+/// assert_eq!(format!("{:?}", tree), "{1, 2, 3, 4}");
+/// assert_eq!(format!("{:#?}", tree), "[2]\n├── [1]\n└── [3, 4]");
+/// ```
+impl<T: Debug, C: Compare<T>> Debug for BTree<T, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self)
+        } else {
+            f.debug_set().entries(self.iter()).finish()
+        }
     }
 }
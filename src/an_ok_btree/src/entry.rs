@@ -0,0 +1,163 @@
+use crate::BTreeMap;
+use ordered_map::{Compare, Natural};
+
+/// B树映射中某个键的入口，由`BTreeMap::entry`返回
+pub enum Entry<'a, K, V, C = Natural> {
+    /// 键不存在
+    Vacant(VacantEntry<'a, K, V, C>),
+    /// 键已存在
+    Occupied(OccupiedEntry<'a, K, V, C>),
+}
+
+/// 不存在的键的入口
+pub struct VacantEntry<'a, K, V, C = Natural> {
+    map: &'a mut BTreeMap<K, V, C>,
+    key: K,
+}
+
+/// 已存在的键的入口。节点在插入和删除时会分裂与合并，因此只记录键，访问值时重新查找
+pub struct OccupiedEntry<'a, K, V, C = Natural> {
+    map: &'a mut BTreeMap<K, V, C>,
+    key: K,
+}
+
+impl<'a, K, V, C: Compare<K>> Entry<'a, K, V, C> {
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// 键不存在时插入default，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// *map.entry(1).or_insert(10) += 1;
+    /// *map.entry(1).or_insert(10) += 1;
+    /// assert_eq!(map.get(&1), Some(&12));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入f的返回值，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.entry(1).or_insert_with(Vec::new).push('a');
+    /// map.entry(1).or_insert_with(Vec::new).push('b');
+    /// assert_eq!(map.get(&1), Some(&vec!['a', 'b']));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(f()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// 键不存在时插入默认值，返回值的可变借用
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// 键存在时用f原地修改值，返回修改后的入口
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// map.entry(1).and_modify(|v| *v += 1).or_insert(0);
+    /// assert_eq!(map.get(&1), Some(&1));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V, C: Compare<K>> VacantEntry<'a, K, V, C> {
+    pub(crate) fn new(map: &'a mut BTreeMap<K, V, C>, key: K) -> Self {
+        VacantEntry { map, key }
+    }
+
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 取回入口对应的键
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 插入值，返回值的可变借用
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.insert_multi(self.key, value)
+    }
+}
+
+impl<'a, K, V, C: Compare<K>> OccupiedEntry<'a, K, V, C> {
+    pub(crate) fn new(map: &'a mut BTreeMap<K, V, C>, key: K) -> Self {
+        OccupiedEntry { map, key }
+    }
+
+    /// 返回入口对应的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 返回值的不可变借用
+    pub fn get(&self) -> &V {
+        self.map.get(&self.key).expect("BTree broken")
+    }
+
+    /// 返回值的可变借用
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.get_mut(&self.key).expect("BTree broken")
+    }
+
+    /// 将入口转换为值的可变借用，生命周期与映射的借用相同
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.get_mut(&self.key).expect("BTree broken")
+    }
+
+    /// 替换为新值，返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 从映射中删除该键值对并返回
+    /// # Example
+    /// ```
+    /// use an_ok_btree::{BTreeMap, Entry};
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// if let Entry::Occupied(entry) = map.entry(1) {
+    ///     assert_eq!(entry.remove_entry(), (1, 'a'));
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_entry(&self.key).expect("BTree broken")
+    }
+
+    /// 从映射中删除该键值对，返回值
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
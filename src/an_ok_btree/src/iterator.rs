@@ -1,20 +1,343 @@
+use crate::node::Node;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::collections::VecDeque;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
 
-//遍历迭代器
-pub struct TraverseIter<T> {
-    data: VecDeque<T>,
+// 根到叶子路径上的节点以及节点中的下标
+type Stack<'a, K, V> = Vec<(&'a Node<K, V>, usize)>;
+
+// 将节点及其最左侧路径上的节点依次入栈，下标是节点中下一个待输出的键
+fn push_left<'a, K, V>(stack: &mut Stack<'a, K, V>, mut node: &'a Node<K, V>) {
+    loop {
+        stack.push((node, 0));
+        if node.is_leaf() {
+            break;
+        }
+        node = node.get_child(0);
+    }
+}
+
+// 将节点及其最右侧路径上的节点依次入栈，下标是节点中尚未从后端输出的键的个数
+fn push_right<'a, K, V>(stack: &mut Stack<'a, K, V>, mut node: &'a Node<K, V>) {
+    loop {
+        stack.push((node, node.key_len()));
+        if node.is_leaf() {
+            break;
+        }
+        node = node.get_child(node.key_len());
+    }
+}
+
+// 键值对在树中的位置：所在节点和节点中的下标，重复的键和零大小的键也能区分
+type Position<'a, K, V> = (&'a Node<K, V>, usize);
+
+fn same_position<K, V>(a: Option<Position<'_, K, V>>, b: Position<'_, K, V>) -> bool {
+    a.is_some_and(|a| ptr::eq(a.0, b.0) && a.1 == b.1)
+}
+
+// 从正向栈中取出下一个最小的键值对的位置
+fn next_position<'a, K, V>(stack: &mut Stack<'a, K, V>) -> Option<Position<'a, K, V>> {
+    loop {
+        let (node, index) = stack.last_mut()?;
+        let (node, i) = (*node, *index);
+        if i < node.key_len() {
+            // 第i个孩子已经访问完，输出第i个键，然后访问第i+1个孩子
+            *index += 1;
+            if !node.is_leaf() {
+                push_left(stack, node.get_child(i + 1));
+            }
+            return Some((node, i));
+        }
+        stack.pop();
+    }
+}
+
+// 从反向栈中取出下一个最大的键值对的位置
+fn next_back_position<'a, K, V>(stack: &mut Stack<'a, K, V>) -> Option<Position<'a, K, V>> {
+    loop {
+        let (node, index) = stack.last_mut()?;
+        let (node, i) = (*node, *index);
+        if i > 0 {
+            // 第i个孩子已经访问完，输出第i-1个键，然后访问第i-1个孩子
+            *index -= 1;
+            if !node.is_leaf() {
+                push_right(stack, node.get_child(i - 1));
+            }
+            return Some((node, i - 1));
+        }
+        stack.pop();
+    }
+}
+
+fn pair_at<'a, K, V>((node, i): Position<'a, K, V>) -> (&'a K, &'a V) {
+    (node.get_key(i), node.get_value(i))
+}
+
+// 从正向栈中取出下一个最小的键值对
+fn next_pair<'a, K, V>(stack: &mut Stack<'a, K, V>) -> Option<(&'a K, &'a V)> {
+    next_position(stack).map(pair_at)
+}
+
+// 从反向栈中取出下一个最大的键值对
+fn next_back_pair<'a, K, V>(stack: &mut Stack<'a, K, V>) -> Option<(&'a K, &'a V)> {
+    next_back_position(stack).map(pair_at)
+}
+
+// key是否不小于下边界
+pub(crate) fn above_lower<K: ?Sized>(from: Bound<&K>, key: &K, cmp: fn(&K, &K) -> Ordering) -> bool {
+    match from {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Less,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Greater,
+        Bound::Unbounded => true,
+    }
+}
+
+// key是否不大于上边界
+pub(crate) fn below_upper<K: ?Sized>(to: Bound<&K>, key: &K, cmp: fn(&K, &K) -> Ordering) -> bool {
+    match to {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Greater,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Less,
+        Bound::Unbounded => true,
+    }
+}
+
+// 中序迭代器，可以从两端迭代。两个栈只保存根到叶子路径上的节点
+pub struct Iter<'a, K, V> {
+    // 正向迭代的栈，下标是节点中下一个待输出的键
+    front: Stack<'a, K, V>,
+    // 反向迭代的栈，下标是节点中尚未从后端输出的键的个数
+    back: Stack<'a, K, V>,
+    remaining: usize, // 剩余未输出的关键字个数，为0时两端相遇
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    pub fn new(root: &'a Node<K, V>, len: usize) -> Self {
+        let mut iter = Iter {
+            front: Vec::new(),
+            back: Vec::new(),
+            remaining: len,
+        };
+        push_left(&mut iter.front, root);
+        push_right(&mut iter.back, root);
+        iter
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        next_pair(&mut self.front)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        next_back_pair(&mut self.back)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+// 范围迭代器，可以从两端迭代。构建时找到范围内第一个和最后一个键值对的位置，
+// 两端按位置判断是否到达范围的另一端或者对方已经输出的键值对
+pub struct RangeIter<'a, K, V> {
+    front: Stack<'a, K, V>,
+    back: Stack<'a, K, V>,
+    first: Option<Position<'a, K, V>>,      // 范围内的第一个键值对
+    last: Option<Position<'a, K, V>>,       // 范围内的最后一个键值对
+    front_last: Option<Position<'a, K, V>>, // 正向迭代上一次输出的键值对
+    back_last: Option<Position<'a, K, V>>,  // 反向迭代上一次输出的键值对
+    finished: bool,
 }
 
-impl<T> TraverseIter<T> {
-    pub fn new(queue: VecDeque<T>) -> Self {
-        TraverseIter { data: queue }
+impl<'a, K, V> RangeIter<'a, K, V> {
+    // 范围的边界可以是键的借用形式Q，cmp按Q比较
+    pub fn new<Q: ?Sized, R: RangeBounds<Q>>(root: &'a Node<K, V>, range: R, cmp: fn(&Q, &Q) -> Ordering) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut iter = RangeIter {
+            front: Vec::new(),
+            back: Vec::new(),
+            first: None,
+            last: None,
+            front_last: None,
+            back_last: None,
+            finished: false,
+        };
+        // 每层从第一个不小于下边界的键开始，并进入它左边的孩子
+        let mut node = root;
+        loop {
+            let i = (0..node.key_len())
+                .find(|&i| above_lower(range.start_bound(), node.get_key(i).borrow(), cmp))
+                .unwrap_or_else(|| node.key_len());
+            iter.front.push((node, i));
+            if node.is_leaf() {
+                break;
+            }
+            node = node.get_child(i);
+        }
+        // 每层只保留不大于上边界的键，并进入最后一个这样的键右边的孩子
+        let mut node = root;
+        loop {
+            let i = (0..node.key_len())
+                .find(|&i| !below_upper(range.end_bound(), node.get_key(i).borrow(), cmp))
+                .unwrap_or_else(|| node.key_len());
+            iter.back.push((node, i));
+            if node.is_leaf() {
+                break;
+            }
+            node = node.get_child(i);
+        }
+        iter.first = next_position(&mut iter.front.clone());
+        iter.last = next_back_position(&mut iter.back.clone());
+        iter.finished = match (iter.first, iter.last) {
+            (Some(first), Some(last)) => cmp(pair_at(first).0.borrow(), pair_at(last).0.borrow()) == Ordering::Greater,
+            _ => true,
+        };
+        iter
     }
 }
 
-impl<T> Iterator for TraverseIter<T> {
+impl<'a, K, V> Iterator for RangeIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let pos = next_position(&mut self.front)?;
+        if same_position(self.back_last, pos) {
+            self.finished = true;
+            return None;
+        }
+        self.finished = same_position(self.last, pos);
+        self.front_last = Some(pos);
+        Some(pair_at(pos))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangeIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let pos = next_back_position(&mut self.back)?;
+        if same_position(self.front_last, pos) {
+            self.finished = true;
+            return None;
+        }
+        self.finished = same_position(self.first, pos);
+        self.back_last = Some(pos);
+        Some(pair_at(pos))
+    }
+}
+
+//层序遍历迭代器，只保存待访问的节点以及节点中下一个待访问键的下标，按需产生键值对
+pub struct TraverseIter<'a, K, V> {
+    nodes: VecDeque<(&'a Node<K, V>, usize)>,
+    remaining: usize, // 剩余未输出的关键字个数
+}
+
+impl<'a, K, V> TraverseIter<'a, K, V> {
+    pub fn new(root: &'a Node<K, V>, len: usize) -> Self {
+        let mut nodes = VecDeque::new();
+        nodes.push_back((root, 0));
+        TraverseIter {
+            nodes,
+            remaining: len,
+        }
+    }
+
+    fn next_pair(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            let (node, index) = self.nodes.front_mut()?;
+            let (node, i) = (*node, *index);
+            if i < node.key_len() {
+                *index += 1;
+                return Some((node.get_key(i), node.get_value(i)));
+            }
+            self.nodes.pop_front();
+            for c in 0..node.children_len() {
+                self.nodes.push_back((node.get_child(c), 0));
+            }
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for TraverseIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.next_pair()?;
+        self.remaining -= 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for TraverseIter<'a, K, V> {}
+
+// 集合的迭代器适配器，丢弃单元值，输出键的克隆
+pub struct KeyIter<I>(pub I);
+
+impl<'a, T: Clone + 'a, I: Iterator<Item = (&'a T, &'a ())>> Iterator for KeyIter<I> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.data.pop_front()
+        self.0.next().map(|(key, _)| key.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T: Clone + 'a, I: DoubleEndedIterator<Item = (&'a T, &'a ())>> DoubleEndedIterator for KeyIter<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, _)| key.clone())
+    }
+}
+
+impl<'a, T: Clone + 'a, I: ExactSizeIterator<Item = (&'a T, &'a ())>> ExactSizeIterator for KeyIter<I> {}
+
+// 集合的迭代器适配器，丢弃单元值，输出键的借用
+pub struct KeyRefIter<I>(pub I);
+
+impl<'a, T: 'a, I: Iterator<Item = (&'a T, &'a ())>> Iterator for KeyRefIter<I> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T: 'a, I: DoubleEndedIterator<Item = (&'a T, &'a ())>> DoubleEndedIterator for KeyRefIter<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, T: 'a, I: ExactSizeIterator<Item = (&'a T, &'a ())>> ExactSizeIterator for KeyRefIter<I> {}
@@ -1,5 +1,11 @@
+mod bplus;
 mod btree;
-mod node;
+mod entry;
 mod iterator;
+mod map;
+mod node;
 
-pub use btree::BTree;
+pub use bplus::{BPlusTree, LeafRange};
+pub use btree::{BTree, DuplicatePolicy};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use map::BTreeMap;
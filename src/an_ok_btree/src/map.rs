@@ -0,0 +1,534 @@
+use crate::entry::{Entry, OccupiedEntry, VacantEntry};
+use crate::iterator::{Iter, RangeIter, TraverseIter};
+use crate::node::{subtree_capacity, Node};
+use ordered_map::{sort_unique_pairs, Compare, InvariantViolation, Natural};
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+/// 基于B树的有序映射，节点布局与`BTree`相同，每个关键字带有一个值。
+/// 键的顺序由比较器C决定，默认使用键自身的`Ord`
+pub struct BTreeMap<K, V, C = Natural> {
+    root: Node<K, V>,
+    degree: usize,
+    len: usize,
+    marker: PhantomData<C>,
+}
+
+impl<K, V, C> BTreeMap<K, V, C> {
+    pub(crate) fn root(&self) -> &Node<K, V> {
+        &self.root
+    }
+}
+
+impl<K: Ord, V> BTreeMap<K, V> {
+    /// 构建一棵空的B树映射，B树的度t必须满足t ≥ 2,
+    /// 除了根节点以外的每个节点必须至少有t - 1个关键字，
+    /// 每个节点最多包含2t-1个关键字，每个内部节点最多有2t个孩子
+    /// # Examples
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map: BTreeMap<i32, char> = BTreeMap::new(2);
+    /// ```
+    pub fn new(degree: usize) -> Self {
+        Self::with_comparator(degree)
+    }
+
+    /// 由按键有序的键值对在O(n)时间内批量构建B树映射，节点尽量装满，避免逐个插入时反复分裂。
+    /// 输入无序时先按键排序，时间复杂度变为O(n log n)，重复的键保留最后一个值
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let map = BTreeMap::from_sorted_iter(2, (0..100).map(|key| (key, key * 10)));
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.get(&42), Some(&420));
+    /// let map = BTreeMap::from_sorted_iter(2, vec![(3, 'c'), (1, 'a'), (3, 'd')]);
+    /// let res: Vec<(&i32, &char)> = map.iter().collect();
+    /// assert_eq!(res, vec![(&1, &'a'), (&3, &'d')]);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(degree: usize, pairs: I) -> Self {
+        Self::from_sorted_iter_with_comparator(degree, pairs)
+    }
+}
+
+impl<K, V, C: Compare<K>> BTreeMap<K, V, C> {
+    /// 构建一棵使用比较器C决定键顺序的空B树映射
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// use ordered_map::Reverse;
+    /// let mut map: BTreeMap<i32, char, Reverse> = BTreeMap::with_comparator(2);
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    /// assert_eq!(map.min_pair(), Some((&2, &'b')));
+    /// ```
+    pub fn with_comparator(degree: usize) -> Self {
+        BTreeMap {
+            root: Node::new(degree, None, None),
+            degree,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// 与`from_sorted_iter`相同，输入按比较器C的顺序排列
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// use ordered_map::Reverse;
+    /// let map: BTreeMap<i32, i32, Reverse> = BTreeMap::from_sorted_iter_with_comparator(2, (0..10).map(|key| (key, key)));
+    /// let res: Vec<i32> = map.iter().map(|(k, _)| *k).take(3).collect();
+    /// assert_eq!(res, vec![9, 8, 7]);
+    /// ```
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = (K, V)>>(degree: usize, pairs: I) -> Self {
+        Self::from_sorted_vec(degree, sort_unique_pairs(pairs, C::compare))
+    }
+
+    // 由已经按键排好序的键值对构建，允许重复的键
+    pub(crate) fn from_sorted_vec(degree: usize, pairs: Vec<(K, V)>) -> Self {
+        let len = pairs.len();
+        let mut height = 0;
+        while subtree_capacity(degree, height) < len {
+            height += 1;
+        }
+        let map = BTreeMap {
+            root: Node::build(degree, &mut pairs.into_iter(), len, height, true),
+            degree,
+            len,
+            marker: PhantomData,
+        };
+        map.check();
+        map
+    }
+
+    /// 判断映射是否为空
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// assert!(map.is_empty());
+    /// map.insert(1, 'a');
+    /// assert!(!map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 返回键值对的个数，时间复杂度O(1)
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'c');
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 返回B树的度
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// 插入键值对，如果键已经存在，则替换旧值并返回旧值
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// assert_eq!(map.insert(1, 'a'), None);
+    /// assert_eq!(map.insert(1, 'b'), Some('a'));
+    /// assert_eq!(map.get(&1), Some(&'b'));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(mem::replace(old, value));
+        }
+        self.insert_multi(key, value);
+        None
+    }
+
+    // 不检查键是否已经存在，直接插入键值对，返回插入的值的可变借用
+    pub(crate) fn insert_multi(&mut self, key: K, value: V) -> &mut V {
+        if self.root.is_full_keys() {
+            let mut new_root = Node::new(self.degree, None, None);
+            mem::swap(&mut self.root, &mut new_root);
+            self.root.insert_child(0, new_root);
+            self.root.split_child(0);
+        }
+        self.len += 1;
+        let mut path = Vec::new();
+        let index = self.root.insert_non_full::<C>(key, value, &mut path);
+        self.check();
+        self.root.value_at_mut(&path, index)
+    }
+
+    // 键已经存在时用key替换树中相等的键，返回Ok(旧键)；不存在时原样返回Err(key)
+    pub(crate) fn replace_key(&mut self, key: K) -> Result<K, K> {
+        match self.root.search_mut::<K, C>(&key) {
+            Some((node, index)) => Ok(node.replace_key(index, key)),
+            None => Err(key),
+        }
+    }
+
+    /// 获取键对应的入口，用于“不存在则插入，存在则修改”
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// for c in "abcab".chars() {
+    ///     *map.entry(c).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(map.get(&'a'), Some(&2));
+    /// assert_eq!(map.get(&'c'), Some(&1));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, C> {
+        if self.contains_key(&key) {
+            Entry::Occupied(OccupiedEntry::new(self, key))
+        } else {
+            Entry::Vacant(VacantEntry::new(self, key))
+        }
+    }
+
+    /// 删除键值对，返回被删除的值，找不到时返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.remove(&1), Some('a'));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// 删除键值对，返回被删除的键值对，找不到时返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.remove_entry(&1), Some((1, 'a')));
+    /// ```
+    pub fn remove_entry<Q: ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        // 没有找到关键字时也可能调整了树的结构
+        let pair = self.root.delete::<Q, C>(key);
+        if pair.is_some() {
+            self.len -= 1;
+        }
+        self.check();
+        pair
+    }
+
+    /// 根据键查找对应的值，找不到返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.get(&1), Some(&'a'));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.get_pair(key).map(|(_, value)| value)
+    }
+
+    /// 根据键查找对应值的可变借用
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// if let Some(value) = map.get_mut(&1) {
+    ///     *value = 'b';
+    /// }
+    /// assert_eq!(map.get(&1), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root
+            .search_mut::<Q, C>(key)
+            .map(|(node, index)| node.get_value_mut(index))
+    }
+
+    /// 根据键获取相应键值对
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.get_pair(&1), Some((&1, &'a')));
+    /// ```
+    pub fn get_pair<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root
+            .search::<Q, C>(key)
+            .map(|(node, index)| (node.get_key(index), node.get_value(index)))
+    }
+
+    /// 查找是否存在键
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// assert!(map.contains_key(&1));
+    /// assert!(!map.contains_key(&2));
+    /// ```
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.root.search::<Q, C>(key).is_some()
+    }
+
+    /// 返回最小键值对
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// assert_eq!(map.min_pair(), None);
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.min_pair(), Some((&1, &'a')));
+    /// ```
+    pub fn min_pair(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.root.min_pair())
+    }
+
+    /// 返回最大键值对
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// assert_eq!(map.max_pair(), None);
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.max_pair(), Some((&2, &'b')));
+    /// ```
+    pub fn max_pair(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.root.max_pair())
+    }
+
+    /// 返回给定键的后继键值对，即第一个严格大于给定键的键值对，如果给定键不存在或后继不存在则返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    /// assert_eq!(map.successor(&1), Some((&2, &'b')));
+    /// assert_eq!(map.successor(&2), None);
+    /// assert_eq!(map.successor(&0), None);
+    /// ```
+    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        self.root.search::<K, C>(key)?;
+        self.range((Bound::Excluded(key), Bound::Unbounded)).next()
+    }
+
+    /// 返回给定键的前驱键值对，即最后一个严格小于给定键的键值对，如果给定键不存在或前驱不存在则返回None
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    /// assert_eq!(map.predecessor(&2), Some((&1, &'a')));
+    /// assert_eq!(map.predecessor(&1), None);
+    /// assert_eq!(map.predecessor(&3), None);
+    /// ```
+    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        self.root.search::<K, C>(key)?;
+        self.range((Bound::Unbounded, Bound::Excluded(key))).next_back()
+    }
+
+    /// 中序迭代器，按键的顺序返回键值对，可以从两端迭代
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'a');
+    /// map.insert(3, 'c');
+    /// let res: Vec<_> = map.iter().collect();
+    /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    /// assert_eq!(map.iter().rev().next(), Some((&3, &'c')));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.root, self.len)
+    }
+
+    /// 范围迭代器，按键的顺序返回范围内的键值对，可以从两端迭代
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// for key in 1..=10 {
+    ///     map.insert(key, key * 10);
+    /// }
+    /// let res: Vec<_> = map.range(3..=5).map(|(_, v)| *v).collect();
+    /// assert_eq!(res, vec![30, 40, 50]);
+    /// let res: Vec<_> = map.range(..3).rev().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> RangeIter<'_, K, V> {
+        RangeIter::new(&self.root, range, C::compare)
+    }
+
+    /// 层序遍历迭代器
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// for key in 1..=4 {
+    ///     map.insert(key, ());
+    /// }
+    /// let res: Vec<i32> = map.levelorder_iter().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1, 3, 4]);
+    /// ```
+    pub fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(&self.root, self.len)
+    }
+
+    /// 检查B树的所有性质：非根节点的关键字个数在[t - 1, 2t - 1]之间、内部节点的孩子个数比关键字多1、
+    /// 叶子都在同一层、键按比较器C严格递增，以及键值对个数正确。不满足时返回第一个被发现的问题
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// assert_eq!(map.validate(), Ok(()));
+    /// for key in 0..100 {
+    ///     map.insert(key, key);
+    /// }
+    /// assert_eq!(map.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        self.validate_keys(false)
+    }
+
+    // 检查B树的性质，allow_equal为true时允许相等的关键字
+    pub(crate) fn validate_keys(&self, allow_equal: bool) -> Result<(), InvariantViolation> {
+        let count = self.root.validate::<C>(0, allow_equal, &mut None, &mut None)?;
+        if count != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                cached: self.len,
+                actual: count,
+            });
+        }
+        Ok(())
+    }
+
+    // 开启checked特性时，每次修改之后检查B树的性质，不满足时panic。
+    // BTree集合可能保存相等的关键字，这里只要求关键字不递减，是否唯一由validate检查
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate_keys(true) {
+            panic!("BTree broken: {}", violation);
+        }
+    }
+}
+
+impl<K: Debug, V, C> BTreeMap<K, V, C> {
+    /// 生成DOT格式的图，每个节点画成一个只包含关键字的record，可以保存为文件后用Graphviz绘制：`dot -Tpng tree.dot -o tree.png`
+    /// # Example
+    /// ```
+    /// use an_ok_btree::BTreeMap;
+    /// let mut map = BTreeMap::new(2);
+    /// for key in 1..=4 {
+    ///     map.insert(key, key * 10);
+    /// }
+    /// let dot = map.to_dot();
+    /// assert!(dot.starts_with("digraph BTreeMap {"));
+    /// assert!(dot.contains("0 [label=\"<c0>|2|<c1>\"];\n    0:c0 -> 1;\n    1 [label=\"1\"];\n    0:c1 -> 2;\n    2 [label=\"3|4\"];\n"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.root.to_dot("BTreeMap")
+    }
+}
+
+impl<'a, K, V, C: Compare<K>> IntoIterator for &'a BTreeMap<K, V, C> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// `{}`按键的顺序打印所有键值对，`{:#}`把树画成多行，每个节点占一行
+/// # Example
+/// ```
+/// use an_ok_btree::BTreeMap;
+/// let mut map = BTreeMap::new(2);
+/// for key in 1..=4 {
+///     map.insert(key, key * 10);
+/// }
+/// assert_eq!(map.to_string(), "{1: 10, 2: 20, 3: 30, 4: 40}");
+/// assert_eq!(format!("{:#}", map), "[2: 20]\n├── [1: 10]\n└── [3: 30, 4: 40]");
+/// ```
+impl<K: Display, V: Display, C: Compare<K>> Display for BTreeMap<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.root.draw(f, &|key, value, f| write!(f, "{}: {}", key, value));
+        }
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", key, value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// `{:?}`按键的顺序打印所有键值对，`{:#?}`把树画成多行
+/// # Example
+/// ```
+/// use an_ok_btree::BTreeMap;
+/// let mut map = BTreeMap::new(2);
+/// for key in 1..=4 {
+///     map.insert(key, 'a');
+/// }
+/// assert_eq!(format!("{:?}", map), "{1: 'a', 2: 'a', 3: 'a', 4: 'a'}");
+/// assert_eq!(format!("{:#?}", map), "[2: 'a']\n├── [1: 'a']\n└── [3: 'a', 4: 'a']");
+/// ```
+impl<K: Debug, V: Debug, C: Compare<K>> Debug for BTreeMap<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.root.draw(f, &|key, value, f| write!(f, "{:?}: {:?}", key, value))
+        } else {
+            f.debug_map().entries(self.iter()).finish()
+        }
+    }
+}
@@ -1,179 +1,446 @@
-use std::collections::VecDeque;
+use ordered_map::{dot_escape_record, draw_tree, Compare, InvariantViolation};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::convert::TryFrom;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
 
-pub struct Node<T> {
-    keys: Vec<T>,
-    children: Vec<Node<T>>,
+// 度为degree的节点最多包含的关键字个数
+pub fn max_keys(degree: usize) -> usize {
+    2 * degree - 1
+}
+
+// 满节点分裂时中间关键字的下标，分裂后左边保留split_index(degree)个关键字
+pub fn split_index(degree: usize) -> usize {
+    max_keys(degree) / 2
+}
+
+// 高度为height(叶子为0)的子树最多包含的关键字个数，即(2t)^(height+1) - 1
+pub fn subtree_capacity(degree: usize, height: usize) -> usize {
+    (2 * degree).saturating_pow(height as u32 + 1) - 1
+}
+
+// 节点中的关键字和值分别存放，vals[i]是keys[i]对应的值，内部节点的孩子比关键字多一个，叶子节点没有孩子。
+// BTreeMap和BPlusTree的节点都用它存放内容，共用下面的分裂、借用和合并过程：
+// BTreeMap的孩子是节点本身，BPlusTree的孩子是节点的下标，内部节点的值为单元类型
+pub struct Slots<K, V, Ch> {
+    pub keys: Vec<K>,
+    pub vals: Vec<V>,
+    pub children: Vec<Ch>,
+}
+
+impl<K, V, Ch> Slots<K, V, Ch> {
+    pub fn new(keys: Vec<K>, vals: Vec<V>, children: Vec<Ch>) -> Self {
+        Slots { keys, vals, children }
+    }
+
+    pub fn insert(&mut self, index: usize, key: K, value: V) {
+        self.keys.insert(index, key);
+        self.vals.insert(index, value);
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        (self.keys.remove(index), self.vals.remove(index))
+    }
+
+    // 用新的键值对替换下标为index的键值对，返回旧的键值对
+    pub fn replace(&mut self, index: usize, key: K, value: V) -> (K, V) {
+        (
+            mem::replace(&mut self.keys[index], key),
+            mem::replace(&mut self.vals[index], value),
+        )
+    }
+
+    // 把下标at及之后的键值对，以及下标at及之后的孩子(内部节点)移到返回的新节点中
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let children = if self.children.is_empty() {
+            Vec::new()
+        } else {
+            self.children.split_off(at)
+        };
+        Slots::new(self.keys.split_off(at), self.vals.split_off(at), children)
+    }
+
+    // 满节点从下标mid处分裂：mid之后的键值对和孩子移到返回的右半部分，
+    // 下标mid的键值对从节点中取出，由调用者作为分隔键放入双亲
+    pub fn split(&mut self, mid: usize) -> (K, V, Self) {
+        let right = self.split_off(mid + 1);
+        let key = self.keys.pop().expect("BTree broken");
+        let value = self.vals.pop().expect("BTree broken");
+        (key, value, right)
+    }
+
+    // 把right的键值对和孩子依次接到当前节点的后面
+    pub fn append(&mut self, mut right: Self) {
+        self.keys.append(&mut right.keys);
+        self.vals.append(&mut right.vals);
+        self.children.append(&mut right.children);
+    }
+
+    // 合并兄弟节点：双亲的分隔键sep下降到当前节点的末尾，再接上右兄弟right
+    pub fn merge(&mut self, sep: (K, V), right: Self) {
+        self.keys.push(sep.0);
+        self.vals.push(sep.1);
+        self.append(right);
+    }
+
+    // 取出最后一个键值对，以及内部节点的最后一个孩子
+    pub fn pop_back(&mut self) -> (K, V, Option<Ch>) {
+        let key = self.keys.pop().expect("BTree broken");
+        let value = self.vals.pop().expect("BTree broken");
+        (key, value, self.children.pop())
+    }
+
+    // 取出第一个键值对，以及内部节点的第一个孩子
+    pub fn pop_front(&mut self) -> (K, V, Option<Ch>) {
+        let (key, value) = self.remove(0);
+        let child = if self.children.is_empty() {
+            None
+        } else {
+            Some(self.children.remove(0))
+        };
+        (key, value, child)
+    }
+
+    pub fn push_front(&mut self, key: K, value: V, child: Option<Ch>) {
+        self.insert(0, key, value);
+        if let Some(child) = child {
+            self.children.insert(0, child);
+        }
+    }
+
+    pub fn push_back(&mut self, key: K, value: V, child: Option<Ch>) {
+        self.keys.push(key);
+        self.vals.push(value);
+        self.children.extend(child);
+    }
+
+    // 从左兄弟借用：left的最后一个键值对上升替换双亲中的分隔键sep，
+    // 原来的分隔键下降到right的最前面，left的最后一个孩子随之移到right
+    pub fn rotate_right(sep: (&mut K, &mut V), left: &mut Self, right: &mut Self) {
+        let (key, value, child) = left.pop_back();
+        let key = mem::replace(sep.0, key);
+        let value = mem::replace(sep.1, value);
+        right.push_front(key, value, child);
+    }
+
+    // 从右兄弟借用：right的第一个键值对上升替换双亲中的分隔键sep，
+    // 原来的分隔键下降到left的末尾，right的第一个孩子随之移到left
+    pub fn rotate_left(sep: (&mut K, &mut V), left: &mut Self, right: &mut Self) {
+        let (key, value, child) = right.pop_front();
+        let key = mem::replace(sep.0, key);
+        let value = mem::replace(sep.1, value);
+        left.push_back(key, value, child);
+    }
+}
+
+pub struct Node<K, V> {
+    slots: Slots<K, V, Node<K, V>>,
     degree: usize,
 }
 
-impl<T: Debug> Debug for Node<T> {
+impl<K: Debug, V> Debug for Node<K, V> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("BNode")
-            .field(&self.keys)
-            .field(&self.children)
+            .field(&self.slots.keys)
+            .field(&self.slots.children)
             .finish()
     }
 }
 
-impl<T: PartialOrd + Clone + Debug> Node<T> {
-    pub fn new(degree: usize, _key: Option<Vec<T>>, _child: Option<Vec<Node<T>>>) -> Self {
-        let new_key = match _key {
-            None => Vec::with_capacity(2 * degree - 1),
-            Some(key) => key,
+impl<K: Debug, V> Node<K, V> {
+    // 把以该节点为根的B树写成名为name的DOT图，每个节点画成一个record，孩子的指针画在关键字之间
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut out = format!("digraph {} {{\n    node [shape=record];\n", name);
+        if !self.slots.keys.is_empty() {
+            self.write_dot(&mut out, &mut 0);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    // 按前序把以该节点为根的子树写成DOT格式，id为下一个可用的节点编号
+    fn write_dot(&self, out: &mut String, id: &mut usize) {
+        let me = *id;
+        *id += 1;
+        let mut fields: Vec<String> = Vec::new();
+        for (i, key) in self.slots.keys.iter().enumerate() {
+            if !self.is_leaf() {
+                fields.push(format!("<c{}>", i));
+            }
+            fields.push(dot_escape_record(&format!("{:?}", key)));
+        }
+        if !self.is_leaf() {
+            fields.push(format!("<c{}>", self.slots.keys.len()));
+        }
+        out.push_str(&format!("    {} [label=\"{}\"];\n", me, fields.join("|")));
+        for (i, child) in self.slots.children.iter().enumerate() {
+            out.push_str(&format!("    {}:c{} -> {};\n", me, i, *id));
+            child.write_dot(out, id);
+        }
+    }
+}
+
+impl<K, V> Node<K, V> {
+    // 把以该节点为根的B树画成多行，每个节点占一行，entry写出节点中的每个键值对
+    pub fn draw(&self, f: &mut Formatter<'_>, entry: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        let label = |node: &&Self, f: &mut Formatter<'_>| {
+            write!(f, "[")?;
+            for (i, (key, value)) in node.slots.keys.iter().zip(node.slots.vals.iter()).enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                entry(key, value, f)?;
+            }
+            write!(f, "]")
+        };
+        draw_tree(f, self, &label, &|node: &&Self| node.slots.children.iter().map(Some).collect())
+    }
+}
+
+impl<K, V> Node<K, V> {
+    pub fn new(degree: usize, _entry: Option<(Vec<K>, Vec<V>)>, _child: Option<Vec<Node<K, V>>>) -> Self {
+        let (new_key, new_val) = match _entry {
+            None => (Vec::with_capacity(max_keys(degree)), Vec::with_capacity(max_keys(degree))),
+            Some(entry) => entry,
         };
         let new_child = match _child {
             None => Vec::with_capacity(2 * degree),
             Some(child) => child,
         };
         Node {
-            keys: new_key,
-            children: new_child,
+            slots: Slots::new(new_key, new_val, new_child),
             degree,
         }
     }
 
+    // 由按键有序的键值对构建高度为height、包含len个关键字的子树，时间复杂度O(len)。
+    // 每个节点取满足容量的最少孩子个数，使节点尽量装满，非根节点至少t个孩子，根节点至少2个孩子，
+    // 关键字平均分给各个孩子，保证每个节点的关键字个数在[t - 1, 2t - 1]之间
+    pub fn build<I: Iterator<Item = (K, V)>>(degree: usize, pairs: &mut I, len: usize, height: usize, is_root: bool) -> Self {
+        let mut node = Node::new(degree, None, None);
+        if height == 0 {
+            for (key, value) in pairs.take(len) {
+                node.slots.keys.push(key);
+                node.slots.vals.push(value);
+            }
+            return node;
+        }
+        let unit = subtree_capacity(degree, height - 1) + 1;
+        let min_children = if is_root { 2 } else { degree };
+        let count = std::cmp::max(min_children, (len + unit) / unit);
+        let child_keys = len + 1 - count;
+        let (base, extra) = (child_keys / count, child_keys % count);
+        for j in 0..count {
+            let child_len = base + usize::from(j < extra);
+            node.slots.children.push(Node::build(degree, pairs, child_len, height - 1, false));
+            if j + 1 < count {
+                let (key, value) = pairs.next().expect("BTree broken");
+                node.slots.keys.push(key);
+                node.slots.vals.push(value);
+            }
+        }
+        node
+    }
+
     pub fn is_leaf(&self) -> bool {
-        self.children.is_empty()
+        self.slots.children.is_empty()
     }
 
     pub fn is_full_keys(&self) -> bool {
-        let max_keys = 2 * self.degree - 1;
-        self.key_len() == max_keys
+        self.key_len() == max_keys(self.degree)
+    }
+
+    pub fn insert_child(&mut self, index: usize, elem: Node<K, V>) {
+        self.slots.children.insert(index, elem);
+    }
+
+    pub fn get_child(&self, index: usize) -> &Node<K, V> {
+        &self.slots.children[index]
+    }
+
+    pub fn insert_key(&mut self, index: usize, key: K, value: V) {
+        self.slots.insert(index, key, value);
     }
 
-    pub fn insert_child(&mut self, index: usize, elem: Node<T>) {
-        self.children.insert(index, elem);
+    pub fn get_key(&self, index: usize) -> &K {
+        &self.slots.keys[index]
     }
 
-    pub fn get_child(&self, index: usize) -> &Node<T> {
-        &self.children[index]
+    // 用相等的新关键字替换下标为index的关键字，返回旧关键字
+    pub fn replace_key(&mut self, index: usize, key: K) -> K {
+        mem::replace(&mut self.slots.keys[index], key)
     }
 
-    pub fn insert_key(&mut self, index: usize, elem: T) {
-        self.keys.insert(index, elem);
+    pub fn get_value(&self, index: usize) -> &V {
+        &self.slots.vals[index]
     }
 
-    pub fn get_key(&self, index: usize) -> &T {
-        &self.keys[index]
+    pub fn get_value_mut(&mut self, index: usize) -> &mut V {
+        &mut self.slots.vals[index]
     }
 
     pub fn key_len(&self) -> usize {
-        self.keys.len()
+        self.slots.keys.len()
     }
 
     pub fn children_len(&self) -> usize {
-        self.children.len()
+        self.slots.children.len()
     }
 
     // 分裂B树中child_index指向的孩子节点，输入必须满足：
     // 1.当前节点self是非满的内部节点，且不能是叶子节点
     // 2.下标为child_index的孩子是满节点
     pub fn split_child(&mut self, child_index: usize) {
-        let mid_key_index = (2 * self.degree - 1) / 2;
-        let child = &mut self.children[child_index];
-        let right_keys = child.keys.split_off(mid_key_index + 1);
-        let middle_key = child.keys.pop().unwrap();
-
-        let right_child = if !child.is_leaf() {
-            Some(child.children.split_off(mid_key_index + 1))
-        } else {
-            None
+        let child = &mut self.slots.children[child_index];
+        let (middle_key, middle_val, right) = child.slots.split(split_index(self.degree));
+        let new_child_node = Node {
+            slots: right,
+            degree: self.degree,
         };
-        let new_child_node = Node::new(self.degree, Some(right_keys), right_child);
-        self.insert_key(child_index, middle_key);
+        self.insert_key(child_index, middle_key, middle_val);
         self.insert_child(child_index + 1, new_child_node);
     }
 
-    // 辅助的递归过程，将关键字key递归插入当前节点，调用时满足：
+    // 辅助的递归过程，将键值对递归插入当前节点，path记录下降时经过的孩子下标，
+    // 返回键值对在叶子节点中的下标，调用时满足：
     // 1.当前节点self必须是非满的
     // 2.递归调用时如果发现孩子节点是满节点，则调用split_child()进行拆分
-    pub fn insert_non_full(&mut self, key: T) {
+    pub fn insert_non_full<C: Compare<K>>(&mut self, key: K, value: V, path: &mut Vec<usize>) -> usize {
         let mut i = isize::try_from(self.key_len()).ok().unwrap() - 1;
-        while i >= 0 && self.keys[i as usize] >= key {
+        while i >= 0 && C::compare(&self.slots.keys[i as usize], &key) != Ordering::Less {
             i -= 1;
         }
         let mut u_index = usize::try_from(i + 1).ok().unwrap();
         if self.is_leaf() {
-            self.insert_key(u_index, key);
+            self.insert_key(u_index, key, value);
+            u_index
         } else {
-            if self.children[u_index].is_full_keys() {
+            if self.slots.children[u_index].is_full_keys() {
                 self.split_child(u_index);
-                if self.keys[u_index] < key {
+                if C::compare(&self.slots.keys[u_index], &key) == Ordering::Less {
                     u_index += 1;
                 }
             }
-            self.children[u_index].insert_non_full(key);
+            path.push(u_index);
+            self.slots.children[u_index].insert_non_full::<C>(key, value, path)
+        }
+    }
+
+    // 沿着孩子下标的路径找到叶子节点，返回其中第index个值的可变借用
+    pub fn value_at_mut(&mut self, path: &[usize], index: usize) -> &mut V {
+        let mut cur = self;
+        for &i in path {
+            cur = &mut cur.slots.children[i];
         }
+        &mut cur.slots.vals[index]
     }
 
-    pub fn max_key(&self) -> T {
+    pub fn max_pair(&self) -> (&K, &V) {
         let mut cur = self;
         while !cur.is_leaf() {
-            cur = &cur.children[cur.key_len()];
+            cur = &cur.slots.children[cur.key_len()];
         }
-        cur.keys[cur.key_len() - 1].clone()
+        let last = cur.key_len() - 1;
+        (&cur.slots.keys[last], &cur.slots.vals[last])
     }
 
-    pub fn min_key(&self) -> T {
+    pub fn min_pair(&self) -> (&K, &V) {
         let mut cur = self;
         while !cur.is_leaf() {
-            cur = &cur.children[0];
+            cur = &cur.slots.children[0];
+        }
+        (&cur.slots.keys[0], &cur.slots.vals[0])
+    }
+
+    // 删除并返回子树中最大的键值对，调用时self至少有t个关键字或者是根节点，
+    // 下降之前保证孩子至少有t个关键字，与delete的case 3相同
+    pub fn remove_max(&mut self) -> (K, V) {
+        if self.is_leaf() {
+            let (key, value, _) = self.slots.pop_back();
+            return (key, value);
+        }
+        let i = self.key_len();
+        if self.slots.children[i].key_len() == self.degree - 1 {
+            if self.slots.children[i - 1].key_len() >= self.degree {
+                self.roright(i);
+            } else {
+                self.unionchild(i - 1);
+                return self.remove_max();
+            }
+        }
+        self.slots.children[i].remove_max()
+    }
+
+    // 删除并返回子树中最小的键值对，调用条件同remove_max
+    pub fn remove_min(&mut self) -> (K, V) {
+        if self.is_leaf() {
+            return self.slots.remove(0);
+        }
+        if self.slots.children[0].key_len() == self.degree - 1 {
+            if self.slots.children[1].key_len() >= self.degree {
+                self.roleft(0);
+            } else {
+                self.unionchild(0);
+                return self.remove_min();
+            }
         }
-        cur.keys[0].clone()
+        self.slots.children[0].remove_min()
     }
 
-    pub fn delete(&mut self, key: T) {
+    // 删除关键字，返回被删除的键值对，没找到时返回None
+    pub fn delete<Q: ?Sized, C: Compare<Q>>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
         let t = self.degree; // 除了根节点外每个节点必须至少有t-1个关键字key
         let mut i = 0;
-        while i < self.key_len() && self.keys[i] < key {
+        while i < self.key_len() && C::compare(self.slots.keys[i].borrow(), key) == Ordering::Less {
             i += 1;
         }
+        let found = i < self.key_len() && C::compare(self.slots.keys[i].borrow(), key) == Ordering::Equal;
         if self.is_leaf() {
             //case 1
-            if i < self.key_len() && key == self.keys[i] {
-                self.keys.remove(i);
+            if found {
+                return Some(self.slots.remove(i));
             }
-            return;
+            return None;
         }
-        if i < self.key_len() && key == self.keys[i] {
+        if found {
             //case 2
-            if self.children[i].key_len() >= t {
+            if self.slots.children[i].key_len() >= t {
                 //case a
-                let max_key = self.children[i].max_key();
-                self.keys[i] = max_key.clone();
-                return self.children[i].delete(max_key);
-            } else if self.children[i + 1].key_len() >= t {
+                let (max_key, max_val) = self.slots.children[i].remove_max();
+                return Some(self.slots.replace(i, max_key, max_val));
+            } else if self.slots.children[i + 1].key_len() >= t {
                 //case b
-                let min_key = self.children[i + 1].min_key();
-                self.keys[i] = min_key.clone();
-                return self.children[i + 1].delete(min_key);
+                let (min_key, min_val) = self.slots.children[i + 1].remove_min();
+                return Some(self.slots.replace(i, min_key, min_val));
             } else {
                 // case c
                 self.unionchild(i);
-                return self.delete(key);
+                return self.delete::<Q, C>(key);
             }
-        } else if self.children[i].key_len() == t - 1 {
+        } else if self.slots.children[i].key_len() == t - 1 {
             //case 3
-            if i >= 1 && self.children[i - 1].key_len() >= t {
+            if i >= 1 && self.slots.children[i - 1].key_len() >= t {
                 // a_left
                 self.roright(i);
-                return self.children[i].delete(key);
-            } else if i + 1 < self.children_len() && self.children[i + 1].key_len() >= t {
+                return self.slots.children[i].delete::<Q, C>(key);
+            } else if i + 1 < self.children_len() && self.slots.children[i + 1].key_len() >= t {
                 // a_right
                 self.roleft(i);
-                return self.children[i].delete(key);
+                return self.slots.children[i].delete::<Q, C>(key);
             } else {
                 // b
                 if i >= self.key_len() {
                     i -= 1;
                 }
                 self.unionchild(i);
-                return self.delete(key);
+                return self.delete::<Q, C>(key);
             }
         }
-        self.children[i].delete(key)
+        self.slots.children[i].delete::<Q, C>(key)
     }
 
     // 调用条件：self.children[i]只有t-1个关键字，但是他的右兄弟self.children[i + 1]存在且至少有t个关键字
@@ -181,18 +448,10 @@ impl<T: PartialOrd + Clone + Debug> Node<T> {
     // 此时self.children[i + 1]至少有t个关键字，则将self的一个关键字降至self.children[i]中，
     // 再将self.children[i + 1]的一个关键字升至self，最后将self.children[i + 1]相应的孩子移到self.children[i]
     pub fn roleft(&mut self, i: usize) {
-        self.children[i].keys.push(self.keys[i].clone());
-        if !self.children[i + 1].children.is_empty() {
-            let new_child = std::mem::replace(
-                &mut self.children[i + 1].children[0],
-                Node::new(self.degree, None, None),
-            );
-            self.children[i].children.push(new_child);
-            self.children[i + 1].children.remove(0);
-        }
-
-        self.keys[i] = self.children[i + 1].keys[0].clone();
-        self.children[i + 1].keys.remove(0);
+        let slots = &mut self.slots;
+        let (left, right) = slots.children.split_at_mut(i + 1);
+        let sep = (&mut slots.keys[i], &mut slots.vals[i]);
+        Slots::rotate_left(sep, &mut left[i].slots, &mut right[0].slots);
     }
 
     // 调用条件：self.children[i]只有t-1个关键字，但是他的左兄弟self.children[i - 1]存在且至少有t个关键字
@@ -200,13 +459,10 @@ impl<T: PartialOrd + Clone + Debug> Node<T> {
     // 此时self.children[i - 1]至少有t个关键字，则将self的某个关键字降至self.children[i]中，
     // 再将self.children[i - 1]的某个关键字升至self，最后将self.children[i - 1]相应的孩子移到self.children[i]
     pub fn roright(&mut self, i: usize) {
-        self.children[i].keys.insert(0, self.keys[i - 1].clone());
-        if !self.children[i - 1].children.is_empty() {
-            let new_child = self.children[i - 1].children.pop().unwrap();
-            self.children[i].children.insert(0, new_child);
-        }
-
-        self.keys[i - 1] = self.children[i - 1].keys.pop().unwrap();
+        let slots = &mut self.slots;
+        let (left, right) = slots.children.split_at_mut(i);
+        let sep = (&mut slots.keys[i - 1], &mut slots.vals[i - 1]);
+        Slots::rotate_right(sep, &mut left[i - 1].slots, &mut right[0].slots);
     }
 
     // 设当前节点的index指向的key关键字记为k, 当前节点前于k的子节点记为y，当前节点后于k的子节点记为z。
@@ -214,64 +470,98 @@ impl<T: PartialOrd + Clone + Debug> Node<T> {
     // 输入必须保证：y和z必须存在且y和z的关键字个数都等于t - 1
     // 调用完成后y的关键字个数为2t-1
     pub fn unionchild(&mut self, i: usize) {
-        let mut right_child = std::mem::replace(
-            &mut self.children[i + 1],
-            Node::new(self.degree, None, None),
-        );
-        //let mut right_child = &mut self.children[index + 1];
-        let left_child = &mut self.children[i];
-
-        left_child.keys.push(self.keys[i].clone());
-        left_child.keys.append(&mut right_child.keys);
-
-        left_child.children.append(&mut right_child.children);
-        self.keys.remove(i);
-        self.children.remove(i + 1);
+        let right_child = self.slots.children.remove(i + 1);
+        let sep = self.slots.remove(i);
+        self.slots.children[i].slots.merge(sep, right_child.slots);
         if self.key_len() == 0 {
-            *self = std::mem::replace(&mut self.children[i], Node::new(self.degree, None, None));
+            *self = self.slots.children.pop().unwrap();
+        }
+    }
+
+    // 检查以当前节点为根、深度为depth的子树是否满足B树的性质：关键字个数在[t - 1, 2t - 1]之间(根节点没有下限)、
+    // 内部节点的孩子个数比关键字多1、叶子都在同一层、中序遍历的关键字不递减。
+    // leaf_depth为已经访问过的叶子的深度，prev为中序遍历中上一个关键字，
+    // allow_equal为false时相等的关键字也视为顺序错误。满足时返回子树中关键字的个数
+    pub fn validate<'a, C: Compare<K>>(
+        &'a self,
+        depth: usize,
+        allow_equal: bool,
+        leaf_depth: &mut Option<usize>,
+        prev: &mut Option<&'a K>,
+    ) -> Result<usize, InvariantViolation> {
+        let keys = self.key_len();
+        if keys > max_keys(self.degree) {
+            return Err(InvariantViolation::Overfull {
+                keys,
+                max: max_keys(self.degree),
+            });
+        }
+        if depth > 0 && keys < self.degree - 1 {
+            return Err(InvariantViolation::Underfull {
+                keys,
+                min: self.degree - 1,
+            });
+        }
+        if self.is_leaf() {
+            match *leaf_depth {
+                Some(expected) if expected != depth => {
+                    return Err(InvariantViolation::LeafDepth { expected, actual: depth });
+                }
+                _ => *leaf_depth = Some(depth),
+            }
+        } else if self.children_len() != keys + 1 {
+            return Err(InvariantViolation::ChildCount {
+                keys,
+                children: self.children_len(),
+            });
+        }
+        let mut count = keys;
+        for i in 0..=keys {
+            if let Some(child) = self.slots.children.get(i) {
+                count += child.validate::<C>(depth + 1, allow_equal, leaf_depth, prev)?;
+            }
+            if let Some(key) = self.slots.keys.get(i) {
+                let order = prev.map(|prev| C::compare(prev, key));
+                if order == Some(Ordering::Greater) || (order == Some(Ordering::Equal) && !allow_equal) {
+                    return Err(InvariantViolation::KeyOrder);
+                }
+                *prev = Some(key);
+            }
         }
+        Ok(count)
     }
 
-    pub fn search(&self, key: &T) -> Option<(&Node<T>, usize)> {
+    pub fn search<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<(&Node<K, V>, usize)>
+    where
+        K: Borrow<Q>,
+    {
         let mut i = 0;
-        while i < self.key_len() && *key > self.keys[i] {
+        while i < self.key_len() && C::compare(key, self.slots.keys[i].borrow()) == Ordering::Greater {
             i += 1;
         }
-        if i < self.key_len() && *key == self.keys[i] {
+        if i < self.key_len() && C::compare(key, self.slots.keys[i].borrow()) == Ordering::Equal {
             Some((self, i))
         } else if self.is_leaf() {
             None
         } else {
-            self.children[i].search(key)
+            self.slots.children[i].search::<Q, C>(key)
         }
     }
 
-    pub fn in_order(&self, buf: &mut Vec<T>) {
-        if self.is_leaf() {
-            for x in &self.keys {
-                buf.push(x.clone());
-            }
-            return;
-        }
-        for i in 0..self.key_len() {
-            self.children[i].in_order(buf);
-            buf.push(self.keys[i].clone());
+    pub fn search_mut<Q: ?Sized, C: Compare<Q>>(&mut self, key: &Q) -> Option<(&mut Node<K, V>, usize)>
+    where
+        K: Borrow<Q>,
+    {
+        let mut i = 0;
+        while i < self.key_len() && C::compare(key, self.slots.keys[i].borrow()) == Ordering::Greater {
+            i += 1;
         }
-        self.children[self.key_len()].in_order(buf);
-    }
-
-    pub fn level_order(&self, buf: &mut Vec<T>) {
-        let mut queue = VecDeque::new();
-        queue.push_back(self);
-        while !queue.is_empty() {
-            if let Some(node) = queue.pop_front() {
-                for k in &node.keys {
-                    buf.push(k.clone());
-                }
-                for c in &node.children {
-                    queue.push_back(c);
-                }
-            }
+        if i < self.key_len() && C::compare(key, self.slots.keys[i].borrow()) == Ordering::Equal {
+            Some((self, i))
+        } else if self.is_leaf() {
+            None
+        } else {
+            self.slots.children[i].search_mut::<Q, C>(key)
         }
     }
 }
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use an_ok_btree::BTree;
+    use an_ok_btree::{BPlusTree, BTree, BTreeMap, DuplicatePolicy, Entry};
+    use ordered_map::{Compare, InvariantViolation, Reverse};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, Bound};
     /*
                      6
                    /   \
@@ -255,8 +258,8 @@ mod tests {
         assert!(!tree.contain(&13));
         assert!(!tree.contain(&14));
 
-        assert_eq!(tree.find_max(), Some(12));
-        assert_eq!(tree.find_min(), Some(1));
+        assert_eq!(tree.find_max(), Some(&12));
+        assert_eq!(tree.find_min(), Some(&1));
     }
 
     #[test]
@@ -274,34 +277,49 @@ mod tests {
         tree.insert(12);
         tree.insert(10);
         tree.insert(11);
-        assert_eq!(tree.successor(0), None);
-        assert_eq!(tree.successor(1), Some(2));
-        assert_eq!(tree.successor(2), Some(3));
-        assert_eq!(tree.successor(3), Some(4));
-        assert_eq!(tree.successor(4), Some(5));
-        assert_eq!(tree.successor(5), Some(6));
-        assert_eq!(tree.successor(6), Some(7));
-        assert_eq!(tree.successor(7), Some(8));
-        assert_eq!(tree.successor(8), Some(9));
-        assert_eq!(tree.successor(9), Some(10));
-        assert_eq!(tree.successor(10), Some(11));
-        assert_eq!(tree.successor(11), Some(12));
-        assert_eq!(tree.successor(12), None);
-
-        assert_eq!(tree.predecessor(0), None);
-        assert_eq!(tree.predecessor(1), None);
-        assert_eq!(tree.predecessor(2), Some(1));
-        assert_eq!(tree.predecessor(3), Some(2));
-        assert_eq!(tree.predecessor(4), Some(3));
-        assert_eq!(tree.predecessor(5), Some(4));
-        assert_eq!(tree.predecessor(6), Some(5));
-        assert_eq!(tree.predecessor(7), Some(6));
-        assert_eq!(tree.predecessor(8), Some(7));
-        assert_eq!(tree.predecessor(9), Some(8));
-        assert_eq!(tree.predecessor(10), Some(9));
-        assert_eq!(tree.predecessor(11), Some(10));
-        assert_eq!(tree.predecessor(12), Some(11));
-        assert_eq!(tree.predecessor(13), None);
+        assert_eq!(tree.successor(&0), None);
+        assert_eq!(tree.successor(&1), Some(&2));
+        assert_eq!(tree.successor(&2), Some(&3));
+        assert_eq!(tree.successor(&3), Some(&4));
+        assert_eq!(tree.successor(&4), Some(&5));
+        assert_eq!(tree.successor(&5), Some(&6));
+        assert_eq!(tree.successor(&6), Some(&7));
+        assert_eq!(tree.successor(&7), Some(&8));
+        assert_eq!(tree.successor(&8), Some(&9));
+        assert_eq!(tree.successor(&9), Some(&10));
+        assert_eq!(tree.successor(&10), Some(&11));
+        assert_eq!(tree.successor(&11), Some(&12));
+        assert_eq!(tree.successor(&12), None);
+
+        assert_eq!(tree.predecessor(&0), None);
+        assert_eq!(tree.predecessor(&1), None);
+        assert_eq!(tree.predecessor(&2), Some(&1));
+        assert_eq!(tree.predecessor(&3), Some(&2));
+        assert_eq!(tree.predecessor(&4), Some(&3));
+        assert_eq!(tree.predecessor(&5), Some(&4));
+        assert_eq!(tree.predecessor(&6), Some(&5));
+        assert_eq!(tree.predecessor(&7), Some(&6));
+        assert_eq!(tree.predecessor(&8), Some(&7));
+        assert_eq!(tree.predecessor(&9), Some(&8));
+        assert_eq!(tree.predecessor(&10), Some(&9));
+        assert_eq!(tree.predecessor(&11), Some(&10));
+        assert_eq!(tree.predecessor(&12), Some(&11));
+        assert_eq!(tree.predecessor(&13), None);
+    }
+
+    #[test]
+    fn successor_predecessor_multiset() {
+        // 相等的关键字可能分布在内部节点和叶子中，后继和前驱必须跳过所有相等的关键字
+        let mut tree = BTree::with_policy(2, DuplicatePolicy::Allow);
+        for key in [1, 1, 1, 2, 2, 3, 1, 1, 1, 1] {
+            tree.insert(key);
+        }
+        assert_eq!(tree.successor(&1), Some(&2));
+        assert_eq!(tree.successor(&2), Some(&3));
+        assert_eq!(tree.successor(&3), None);
+        assert_eq!(tree.predecessor(&3), Some(&2));
+        assert_eq!(tree.predecessor(&2), Some(&1));
+        assert_eq!(tree.predecessor(&1), None);
     }
 
     #[test]
@@ -328,4 +346,598 @@ mod tests {
         let res: Vec<i32> = tree.levelorder_iter().collect();
         assert_eq!(res, vec![6,2,8,10,1,3,4,5,7,9,11,12]);
     }
+
+    #[test]
+    fn lazy_traverse() {
+        for degree in 2..5 {
+            let mut tree = BTree::new(degree);
+            // 37与1000互质，键以打乱的顺序插入
+            for i in 0..1000 {
+                tree.insert(i * 37 % 1000);
+            }
+            let res: Vec<i32> = tree.inorder_iter().take(3).collect();
+            assert_eq!(res, vec![0, 1, 2]);
+            let res: Vec<i32> = tree.inorder_iter().collect();
+            assert_eq!(res, (0..1000).collect::<Vec<i32>>());
+            let mut res: Vec<i32> = tree.levelorder_iter().collect();
+            res.sort();
+            assert_eq!(res, (0..1000).collect::<Vec<i32>>());
+        }
+    }
+
+    #[test]
+    fn double_ended() {
+        for degree in 2..5 {
+            let mut tree = BTree::new(degree);
+            for i in 0..1000 {
+                tree.insert(i * 37 % 1000);
+            }
+            // 重复插入的关键字分别计数
+            tree.insert(1);
+            assert_eq!(tree.len(), 1001);
+            tree.delete(1);
+            for key in (0..1000).step_by(3) {
+                tree.delete(key);
+            }
+            tree.delete(1000);
+            let expected: Vec<i32> = (0..1000).filter(|key| key % 3 != 0).collect();
+            assert_eq!(tree.len(), expected.len());
+            let res: Vec<i32> = tree.inorder_iter().rev().collect();
+            assert_eq!(res, expected.iter().rev().cloned().collect::<Vec<i32>>());
+
+            // 两端交替迭代，在中间相遇后两端都结束
+            let mut iter = tree.inorder_iter();
+            let n = expected.len();
+            for i in 0..n / 2 {
+                assert_eq!(iter.len(), n - 2 * i);
+                assert_eq!(iter.next(), Some(expected[i]));
+                assert_eq!(iter.next_back(), Some(expected[n - 1 - i]));
+            }
+            assert_eq!(iter.len(), 0);
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+            assert_eq!(tree.levelorder_iter().len(), n);
+        }
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = BTree::new(3);
+        let mut expected = BTreeSet::new();
+        // 37与1000互质，键以打乱的顺序插入，只保留偶数键
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            if key % 2 == 0 {
+                tree.insert(key);
+                expected.insert(key);
+            }
+        }
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Included(101), Bound::Excluded(199)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Excluded(500), Bound::Excluded(502)),
+            (Bound::Included(-5), Bound::Excluded(0)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            let want: Vec<i32> = expected.range((lower, upper)).cloned().collect();
+            let res: Vec<i32> = tree.range((lower, upper)).collect();
+            assert_eq!(res, want);
+            let res: Vec<i32> = tree.range((lower, upper)).rev().collect();
+            assert_eq!(res, want.iter().rev().cloned().collect::<Vec<i32>>());
+            // 两端交替迭代，相遇后两端都结束
+            let mut iter = tree.range((lower, upper));
+            let (mut front, mut back) = (0, want.len());
+            while front < back {
+                assert_eq!(iter.next(), Some(want[front]));
+                front += 1;
+                if front < back {
+                    back -= 1;
+                    assert_eq!(iter.next_back(), Some(want[back]));
+                }
+            }
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+        let res: Vec<i32> = tree.range(3..=9).collect();
+        assert_eq!(res, vec![4, 6, 8]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let sets: Vec<BTreeSet<i32>> = vec![
+            BTreeSet::new(),
+            (0..100).collect(),
+            (0..100).filter(|key| key % 3 == 0).collect(),
+            (50..150).filter(|key| key % 2 == 0).collect(),
+            (200..210).collect(),
+        ];
+        let build = |set: &BTreeSet<i32>| {
+            let mut tree = BTree::new(3);
+            for key in set.iter() {
+                tree.insert(*key);
+            }
+            tree
+        };
+        let tree_keys = |tree: BTree<i32>| -> Vec<i32> { tree.inorder_iter().collect() };
+        for x in sets.iter() {
+            for y in sets.iter() {
+                let (a, b) = (build(x), build(y));
+                let union: Vec<i32> = x.union(y).cloned().collect();
+                let intersection: Vec<i32> = x.intersection(y).cloned().collect();
+                let difference: Vec<i32> = x.difference(y).cloned().collect();
+                let symmetric_difference: Vec<i32> = x.symmetric_difference(y).cloned().collect();
+                assert_eq!(a.union(&b).collect::<Vec<i32>>(), union);
+                assert_eq!(a.intersection(&b).collect::<Vec<i32>>(), intersection);
+                assert_eq!(a.difference(&b).collect::<Vec<i32>>(), difference);
+                assert_eq!(a.symmetric_difference(&b).collect::<Vec<i32>>(), symmetric_difference);
+                assert_eq!(tree_keys(&a | &b), union);
+                assert_eq!(tree_keys(&a & &b), intersection);
+                assert_eq!(tree_keys(&a - &b), difference);
+                assert_eq!(tree_keys(&a ^ &b), symmetric_difference);
+            }
+        }
+    }
+
+    #[test]
+    fn range_duplicates() {
+        let mut tree = BTree::new(2);
+        for key in [3, 1, 2, 2, 3, 2, 1, 2].iter() {
+            tree.insert(*key);
+        }
+        let res: Vec<i32> = tree.range(2..=2).collect();
+        assert_eq!(res, vec![2, 2, 2, 2]);
+        let res: Vec<i32> = tree.range(2..).rev().collect();
+        assert_eq!(res, vec![3, 3, 2, 2, 2, 2]);
+        let mut iter = tree.range(1..3);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.by_ref().count(), 4);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn map() {
+        let mut map = BTreeMap::new(3);
+        let mut expected = std::collections::BTreeMap::new();
+        for i in 0..500 {
+            let key = i * 37 % 200;
+            assert_eq!(map.insert(key, i), expected.insert(key, i));
+            assert_eq!(map.len(), expected.len());
+        }
+        for key in 0..200 {
+            assert_eq!(map.get(&key), expected.get(&key));
+            *map.get_mut(&key).unwrap() += 1;
+        }
+        assert_eq!(map.get(&200), None);
+        assert_eq!(map.get_mut(&-1), None);
+        for (key, value) in expected.iter_mut() {
+            *value += 1;
+            assert_eq!(map.get_pair(key), Some((key, &*value)));
+        }
+        let res: Vec<(&i32, &i32)> = map.range(50..60).collect();
+        assert_eq!(res, expected.range(50..60).collect::<Vec<_>>());
+        let res: Vec<(&i32, &i32)> = map.iter().rev().collect();
+        assert_eq!(res, expected.iter().rev().collect::<Vec<_>>());
+
+        for key in (0..250).step_by(3) {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+            assert_eq!(map.len(), expected.len());
+        }
+        assert!(map.iter().eq(expected.iter()));
+        for key in expected.keys().cloned().collect::<Vec<i32>>() {
+            assert_eq!(map.remove_entry(&key), expected.remove_entry(&key));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.min_pair(), None);
+    }
+
+    #[test]
+    fn map_entry() {
+        let mut map = BTreeMap::new(2);
+        for word in "the quick fox jumps over the lazy dog the end".split(' ') {
+            *map.entry(word).or_insert(0) += 1;
+        }
+        assert_eq!(map.get(&"the"), Some(&3));
+        assert_eq!(map.get(&"fox"), Some(&1));
+        assert_eq!(map.len(), 8);
+
+        map.entry("fox").and_modify(|count| *count += 10).or_default();
+        map.entry("cat").and_modify(|count| *count += 10).or_default();
+        assert_eq!(map.get(&"fox"), Some(&11));
+        assert_eq!(map.get(&"cat"), Some(&0));
+
+        match map.entry("dog") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), &"dog");
+                assert_eq!(entry.insert(5), 1);
+                assert_eq!(*entry.get(), 5);
+                assert_eq!(entry.remove(), 5);
+            }
+            Entry::Vacant(_) => panic!("dog should be present"),
+        }
+        match map.entry("bird") {
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), &"bird");
+                *entry.insert(7) += 1;
+            }
+            Entry::Occupied(_) => panic!("bird should be absent"),
+        }
+        assert_eq!(map.get(&"dog"), None);
+        assert_eq!(map.get(&"bird"), Some(&8));
+        assert_eq!(map.len(), 9);
+        let keys: Vec<&str> = map.iter().map(|(key, _)| *key).collect();
+        let mut sorted = keys.clone();
+        sorted.sort_unstable();
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn borrowed_queries() {
+        // 不实现Clone和Debug的关键字
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(String);
+        let mut tree = BTree::new(2);
+        for word in ["pear", "apple", "fig", "kiwi", "banana"].iter() {
+            tree.insert(Key(word.to_string()));
+        }
+        fn name(key: Option<&Key>) -> Option<&str> {
+            key.map(|key| key.0.as_str())
+        }
+        assert_eq!(name(tree.find_min()), Some("apple"));
+        assert_eq!(name(tree.find_max()), Some("pear"));
+        let fig = Key(String::from("fig"));
+        assert!(tree.contain(&fig));
+        assert_eq!(name(tree.successor(&fig)), Some("kiwi"));
+        assert_eq!(name(tree.predecessor(&fig)), Some("banana"));
+        let res: Vec<&str> = tree.iter().map(|key| key.0.as_str()).collect();
+        assert_eq!(res, vec!["apple", "banana", "fig", "kiwi", "pear"]);
+        assert_eq!(tree.iter().rev().len(), 5);
+        tree.delete(fig);
+        assert_eq!(tree.len(), 4);
+    }
+
+    #[test]
+    fn duplicate_policy() {
+        // 关键字只按第一个分量比较，第二个分量用来区分同一关键字的不同拷贝
+        #[derive(Debug)]
+        struct Key(i32, char);
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Key {}
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Key {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        let insert_all = |tree: &mut BTree<Key>| -> Vec<bool> {
+            (0..30)
+                .map(|i| tree.insert(Key(i % 10, (b'a' + (i / 10) as u8) as char)))
+                .collect()
+        };
+        let tags = |tree: &BTree<Key>| -> String { tree.iter().map(|key| key.1).collect() };
+
+        let mut reject = BTree::with_policy(2, DuplicatePolicy::Reject);
+        assert_eq!(reject.policy(), DuplicatePolicy::Reject);
+        let inserted = insert_all(&mut reject);
+        assert_eq!(inserted.iter().filter(|&&x| x).count(), 10);
+        assert_eq!(reject.len(), 10);
+        assert_eq!(tags(&reject), "aaaaaaaaaa");
+        assert_eq!(reject.count(&Key(3, ' ')), 1);
+
+        let mut replace = BTree::with_policy(3, DuplicatePolicy::Replace);
+        let inserted = insert_all(&mut replace);
+        assert_eq!(inserted.iter().filter(|&&x| x).count(), 10);
+        assert_eq!(replace.len(), 10);
+        assert_eq!(tags(&replace), "cccccccccc");
+
+        let mut multi = BTree::with_policy(2, DuplicatePolicy::Allow);
+        assert!(insert_all(&mut multi).into_iter().all(|x| x));
+        assert_eq!(multi.len(), 30);
+        for i in 0..10 {
+            assert_eq!(multi.count(&Key(i, ' ')), 3);
+            let mut copies: Vec<char> = multi.get_all(&Key(i, ' ')).map(|key| key.1).collect();
+            copies.sort_unstable();
+            assert_eq!(copies, vec!['a', 'b', 'c']);
+        }
+        assert_eq!(multi.count(&Key(10, ' ')), 0);
+        multi.delete(Key(4, ' '));
+        assert_eq!(multi.count(&Key(4, ' ')), 2);
+        assert_eq!(multi.remove_all(&Key(4, ' ')), 2);
+        assert_eq!(multi.remove_all(&Key(4, ' ')), 0);
+        assert_eq!(multi.len(), 27);
+        for i in 0..10 {
+            if i != 4 {
+                assert_eq!(multi.remove_all(&Key(i, ' ')), 3);
+            }
+        }
+        assert!(multi.is_empty());
+    }
+
+    #[test]
+    fn bplus_tree() {
+        for degree in 2..5 {
+            let mut tree = BPlusTree::new(degree);
+            let mut expected = std::collections::BTreeMap::new();
+            // 线性同余生成的伪随机操作序列，插入和删除交替进行
+            let mut seed: u64 = 42;
+            for step in 0..3000 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let key = (seed >> 33) % 300;
+                if step % 3 == 2 {
+                    assert_eq!(tree.remove(&key), expected.remove(&key));
+                } else {
+                    assert_eq!(tree.insert(key, step), expected.insert(key, step));
+                }
+                assert_eq!(tree.len(), expected.len());
+            }
+            for key in 0..300 {
+                assert_eq!(tree.get(&key), expected.get(&key));
+                assert_eq!(tree.contains_key(&key), expected.contains_key(&key));
+            }
+            assert!(tree.iter().eq(expected.iter()));
+            assert!(tree.iter().rev().eq(expected.iter().rev()));
+            let bounds = [
+                (Bound::Included(100), Bound::Included(200)),
+                (Bound::Excluded(100), Bound::Excluded(200)),
+                (Bound::Unbounded, Bound::Included(7)),
+                (Bound::Excluded(290), Bound::Unbounded),
+                (Bound::Included(150), Bound::Included(150)),
+                (Bound::Included(200), Bound::Excluded(100)),
+                (Bound::Excluded(150), Bound::Excluded(150)),
+                (Bound::Unbounded, Bound::Unbounded),
+            ];
+            for (lower, upper) in bounds.iter().cloned() {
+                let want: Vec<(&u64, &i32)> = if lower == Bound::Excluded(150) || lower == Bound::Included(200) {
+                    Vec::new()
+                } else {
+                    expected.range((lower, upper)).collect()
+                };
+                assert_eq!(tree.range((lower, upper)).collect::<Vec<_>>(), want);
+                let mut iter = tree.range((lower, upper));
+                let (mut front, mut back) = (0, want.len());
+                while front < back {
+                    assert_eq!(iter.next(), Some(want[front]));
+                    front += 1;
+                    if front < back {
+                        back -= 1;
+                        assert_eq!(iter.next_back(), Some(want[back]));
+                    }
+                }
+                assert_eq!(iter.next(), None);
+                assert_eq!(iter.next_back(), None);
+            }
+            for key in 0..300 {
+                *tree.get_mut(&key).unwrap_or(&mut 0) += 1;
+                assert_eq!(tree.remove(&key), expected.remove(&key).map(|value| value + 1));
+            }
+            assert!(tree.is_empty());
+            assert_eq!(tree.iter().next(), None);
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        let tree = BTree::from_sorted_iter(2, 0..7);
+        assert_eq!(tree.to_string(), "BNode([3], [BNode([0, 1, 2], []), BNode([4, 5, 6], [])])");
+        let tree = BTree::from_sorted_iter(2, 0..16);
+        assert_eq!(
+            tree.to_string(),
+            "BNode([8], [BNode([2, 5], [BNode([0, 1], []), BNode([3, 4], []), BNode([6, 7], [])]), \
+             BNode([12], [BNode([9, 10, 11], []), BNode([13, 14, 15], [])])])"
+        );
+        for degree in 2..5 {
+            for n in 0..200 {
+                let mut tree = BTree::from_sorted_iter(degree, 0..n);
+                assert_eq!(tree.len(), n as usize);
+                assert!(tree.inorder_iter().eq(0..n));
+                assert!(tree.inorder_iter().rev().eq((0..n).rev()));
+                // 批量构建的树可以继续插入删除
+                let mut model: Vec<i32> = (0..n).collect();
+                tree.insert(n / 2);
+                model.insert(model.binary_search(&(n / 2)).unwrap_or_else(|i| i), n / 2);
+                for key in (0..n).step_by(3) {
+                    tree.delete(key);
+                    model.remove(model.binary_search(&key).unwrap());
+                    assert!(tree.inorder_iter().eq(model.iter().cloned()));
+                }
+                assert_eq!(tree.len(), model.len());
+            }
+        }
+        let mut map = BTreeMap::from_sorted_iter(3, vec![(5, 'e'), (1, 'a'), (3, 'c'), (1, 'z')]);
+        let res: Vec<(&i32, &char)> = map.iter().collect();
+        assert_eq!(res, vec![(&1, &'z'), (&3, &'c'), (&5, &'e')]);
+        assert_eq!(map.insert(3, 'd'), Some('c'));
+        assert_eq!(map.remove(&1), Some('z'));
+        assert_eq!(map.len(), 2);
+    }
+
+    // 忽略大小写比较字符串，同时支持按String和&str比较
+    struct CaseInsensitive;
+
+    impl Compare<str> for CaseInsensitive {
+        fn compare(a: &str, b: &str) -> Ordering {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        }
+    }
+
+    impl Compare<String> for CaseInsensitive {
+        fn compare(a: &String, b: &String) -> Ordering {
+            <Self as Compare<str>>::compare(a, b)
+        }
+    }
+
+    #[test]
+    fn comparator() {
+        let mut tree: BTree<i32, Reverse> = BTree::with_comparator(2, DuplicatePolicy::Reject);
+        for key in 0..50 {
+            tree.insert(key);
+        }
+        assert!(!tree.insert(7));
+        assert_eq!(tree.find_min(), Some(&49));
+        assert_eq!(tree.find_max(), Some(&0));
+        assert_eq!(tree.successor(&10), Some(&9));
+        assert_eq!(tree.predecessor(&10), Some(&11));
+        let res: Vec<i32> = tree.range((Bound::Included(12), Bound::Included(10))).collect();
+        assert_eq!(res, vec![12, 11, 10]);
+        tree.delete(49);
+        tree.remove_all(&48);
+        assert_eq!(tree.find_min(), Some(&47));
+
+        let evens: BTree<i32, Reverse> = BTree::from_sorted_iter_with_comparator(2, (0..50).step_by(2));
+        let res: Vec<i32> = (&tree & &evens).inorder_iter().take(3).collect();
+        assert_eq!(res, vec![46, 44, 42]);
+
+        let mut map: BTreeMap<String, i32, CaseInsensitive> = BTreeMap::with_comparator(2);
+        for word in ["Apple", "banana", "APPLE", "Cherry"].iter() {
+            *map.entry(word.to_string()).or_insert(0) += 1;
+        }
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("apple"), Some(&2));
+        assert_eq!(map.get_pair("CHERRY"), Some((&String::from("Cherry"), &1)));
+        assert_eq!(map.remove("BANANA"), Some(1));
+        assert!(!map.contains_key("banana"));
+
+        let mut bplus: BPlusTree<i32, i32, Reverse> = BPlusTree::with_comparator(2);
+        for key in 0..50 {
+            bplus.insert(key, key * 10);
+        }
+        assert_eq!(bplus.validate(), Ok(()));
+        let res: Vec<i32> = bplus.range((Bound::Included(12), Bound::Included(10))).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![12, 11, 10]);
+        assert_eq!(bplus.remove(&20), Some(200));
+        assert_eq!(bplus.get(&20), None);
+        assert_eq!(bplus.iter().next(), Some((&49, &490)));
+    }
+
+    #[test]
+    fn borrowed_lookup() {
+        let mut map = BTreeMap::new(2);
+        for word in ["b", "a", "c"].iter() {
+            map.insert(word.to_string(), word.len());
+        }
+        assert_eq!(map.get("a"), Some(&1));
+        assert!(map.contains_key("c"));
+        *map.get_mut("b").unwrap() += 1;
+        assert_eq!(map.get_pair("b"), Some((&String::from("b"), &2)));
+        assert_eq!(map.remove_entry("a"), Some((String::from("a"), 1)));
+
+        let mut tree = BTree::new(2);
+        tree.insert(String::from("x"));
+        tree.insert(String::from("x"));
+        assert!(tree.contain("x"));
+        assert_eq!(tree.count("x"), 2);
+        assert_eq!(tree.get_all("x").count(), 2);
+        assert_eq!(tree.remove_all("x"), 2);
+
+        let mut bplus = BPlusTree::new(2);
+        for word in ["b", "a", "c", "d"].iter() {
+            bplus.insert(word.to_string(), word.len());
+        }
+        assert_eq!(bplus.get("a"), Some(&1));
+        assert!(bplus.contains_key("c"));
+        *bplus.get_mut("b").unwrap() += 1;
+        assert_eq!(bplus.get("b"), Some(&2));
+        assert_eq!(bplus.remove("a"), Some(1));
+        assert_eq!(bplus.remove("a"), None);
+        assert_eq!(bplus.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate() {
+        let mut set = BTree::with_policy(2, DuplicatePolicy::Allow);
+        let mut map = BTreeMap::new(3);
+        let mut bplus = BPlusTree::new(3);
+        assert_eq!(set.validate(), Ok(()));
+        assert_eq!(map.validate(), Ok(()));
+        assert_eq!(bplus.validate(), Ok(()));
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            set.insert(key % 300);
+            map.insert(key, key);
+            bplus.insert(key, key);
+            assert_eq!(set.validate(), Ok(()));
+            assert_eq!(map.validate(), Ok(()));
+            assert_eq!(bplus.validate(), Ok(()));
+        }
+        for key in (0..1000).step_by(3) {
+            set.delete(key % 300);
+            map.remove(&key);
+            bplus.remove(&key);
+            assert_eq!(set.validate(), Ok(()));
+            assert_eq!(map.validate(), Ok(()));
+            assert_eq!(bplus.validate(), Ok(()));
+        }
+        for key in 0..100 {
+            *map.entry(key).or_insert(0) += 1;
+        }
+        assert_eq!(map.validate(), Ok(()));
+        let map = BTreeMap::from_sorted_iter(2, (0..100).map(|key| (key, key)));
+        assert_eq!(map.validate(), Ok(()));
+
+        let violation = InvariantViolation::Underfull { keys: 0, min: 1 };
+        assert_eq!(violation.to_string(), "节点只有0个关键字，至少需要1个");
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut tree = BTree::new(2);
+        assert_eq!(tree.to_dot(), "digraph BTree {\n    node [shape=record];\n}\n");
+        for key in 1..=6 {
+            tree.insert(key);
+        }
+        let expected = [
+            "digraph BTree {",
+            "    node [shape=record];",
+            r#"    0 [label="<c0>|2|<c1>|4|<c2>"];"#,
+            "    0:c0 -> 1;",
+            r#"    1 [label="1"];"#,
+            "    0:c1 -> 2;",
+            r#"    2 [label="3"];"#,
+            "    0:c2 -> 3;",
+            r#"    3 [label="5|6"];"#,
+            "}",
+        ];
+        assert_eq!(tree.to_dot(), expected.join("\n") + "\n");
+
+        // record中有特殊含义的字符需要转义
+        let mut map = BTreeMap::new(2);
+        map.insert("<a|b>", 1);
+        assert_eq!(map.to_dot(), "digraph BTreeMap {\n    node [shape=record];\n    0 [label=\"\\\"\\<a\\|b\\>\\\"\"];\n}\n");
+    }
+
+    #[test]
+    fn pretty_print() {
+        let mut tree = BTree::new(2);
+        for key in 1..=6 {
+            tree.insert(key);
+        }
+        assert_eq!(tree.to_string(), "BNode([2, 4], [BNode([1], []), BNode([3], []), BNode([5, 6], [])])");
+        assert_eq!(format!("{:#}", tree), "[2, 4]\n├── [1]\n├── [3]\n└── [5, 6]");
+        assert_eq!(format!("{:?}", tree), "{1, 2, 3, 4, 5, 6}");
+
+        let mut map = BTreeMap::new(2);
+        let mut bplus = BPlusTree::new(2);
+        for key in 1..=6 {
+            map.insert(key, key * 10);
+            bplus.insert(key, key * 10);
+        }
+        assert_eq!(map.to_string(), "{1: 10, 2: 20, 3: 30, 4: 40, 5: 50, 6: 60}");
+        assert_eq!(format!("{:#}", map), "[2: 20, 4: 40]\n├── [1: 10]\n├── [3: 30]\n└── [5: 50, 6: 60]");
+        assert_eq!(bplus.to_string(), map.to_string());
+        assert_eq!(format!("{:?}", bplus), format!("{:?}", map));
+        assert_eq!(format!("{:#}", bplus), "[2, 3, 4]\n├── [1: 10]\n├── [2: 20]\n├── [3: 30]\n└── [4: 40, 5: 50, 6: 60]");
+    }
 }
@@ -0,0 +1,165 @@
+use crate::{List, Node};
+use std::mem;
+use std::ptr::NonNull;
+
+impl<T> List<T> {
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+}
+
+// 可以修改链表的游标。游标指向某个元素，或者指向尾部和头部之间的“幽灵”位置，此时current为None，
+// 幽灵位置的下一个是头部，上一个是尾部，下标等于链表长度
+pub struct CursorMut<'a, T> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a mut List<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).next;
+                self.index += 1;
+            },
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { (*current.as_ptr()).next },
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(current) => unsafe { (*current.as_ptr()).prev },
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    // 下一个节点，游标在幽灵位置时是头部
+    fn next_node(&self) -> Option<NonNull<Node<T>>> {
+        match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { (*current.as_ptr()).next },
+        }
+    }
+
+    // 上一个节点，游标在幽灵位置时是尾部
+    fn prev_node(&self) -> Option<NonNull<Node<T>>> {
+        match self.current {
+            None => self.list.tail,
+            Some(current) => unsafe { (*current.as_ptr()).prev },
+        }
+    }
+
+    // 在当前位置之后插入元素，游标在幽灵位置时插入到头部
+    pub fn insert_after(&mut self, elem: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node::new(elem))));
+        let next = self.next_node();
+        self.list.splice_nodes(self.current, next, node, node, 1);
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // 在当前位置之前插入元素，游标在幽灵位置时插入到尾部
+    pub fn insert_before(&mut self, elem: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node::new(elem))));
+        let prev = self.prev_node();
+        self.list.splice_nodes(prev, self.current, node, node, 1);
+        self.index += 1;
+    }
+
+    // 删除当前元素，游标移动到下一个位置
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        self.current = self.next_node();
+        self.list.unlink_node(node);
+        let node = unsafe { Box::from_raw(node.as_ptr()) };
+        Some(node.elem)
+    }
+
+    // 把当前位置之后的元素拆分成新链表，游标在幽灵位置时拆出整个链表
+    pub fn split_after(&mut self) -> List<T> {
+        let at = if self.current.is_some() { self.index + 1 } else { 0 };
+        if self.current.is_none() {
+            self.index = 0;
+        }
+        self.list.split_off_after_node(self.current, at)
+    }
+
+    // 把当前位置之前的元素拆分成新链表，游标在幽灵位置时拆出整个链表
+    pub fn split_before(&mut self) -> List<T> {
+        let at = self.index;
+        self.index = 0;
+        self.list.split_off_before_node(self.current, at)
+    }
+
+    // 把list中的元素整体插入到当前位置之后，游标在幽灵位置时插入到头部
+    pub fn splice_after(&mut self, mut list: List<T>) {
+        if let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) {
+            let splice_len = mem::replace(&mut list.len, 0);
+            let next = self.next_node();
+            self.list.splice_nodes(self.current, next, head, tail, splice_len);
+            if self.current.is_none() {
+                self.index = self.list.len;
+            }
+        }
+    }
+
+    // 把list中的元素整体插入到当前位置之前，游标在幽灵位置时插入到尾部
+    pub fn splice_before(&mut self, mut list: List<T>) {
+        if let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) {
+            let splice_len = mem::replace(&mut list.len, 0);
+            let prev = self.prev_node();
+            self.list.splice_nodes(prev, self.current, head, tail, splice_len);
+            self.index += splice_len;
+        }
+    }
+}
+
+unsafe impl<'a, T: Send> Send for CursorMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for CursorMut<'a, T> {}
@@ -1,4 +1,5 @@
 use crate::{List, Node};
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 
@@ -7,6 +8,7 @@ impl<T> List<T> {
         Iter {
             head: self.head,
             tail: self.tail,
+            len: self.len,
             marker: Default::default(),
         }
     }
@@ -15,9 +17,22 @@ impl<T> List<T> {
         IterMut {
             head: self.head,
             tail: self.tail,
+            len: self.len,
             marker: Default::default(),
         }
     }
+
+    // 惰性地删除并返回满足pred的元素，迭代器没有走完时剩下的元素保留在链表中
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            next: self.head,
+            list: self,
+            pred,
+        }
+    }
 }
 
 pub struct IntoIter<T> {
@@ -30,6 +45,10 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.list.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
 }
 
 impl<T> DoubleEndedIterator for IntoIter<T> {
@@ -38,37 +57,67 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
 pub struct Iter<'a, T> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
+    // 剩余未输出的元素个数，为0时两端相遇
+    len: usize,
     marker: PhantomData<&'a Node<T>>,
 }
 
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         self.head.map(|node| unsafe {
             let node = &*node.as_ptr();
+            self.len -= 1;
             self.head = node.next;
             &node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         self.tail.map(|node| unsafe {
             let node = &*node.as_ptr();
+            self.len -= 1;
             self.tail = node.prev;
             &node.elem
         })
     }
 }
 
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
 pub struct IterMut<'a, T> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
+    // 剩余未输出的元素个数，为0时两端相遇
+    len: usize,
     marker: PhantomData<&'a mut Node<T>>,
 }
 
@@ -76,24 +125,73 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         self.head.map(|node| unsafe {
             let node = &mut *node.as_ptr();
+            self.len -= 1;
             self.head = node.next;
             &mut node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         self.tail.map(|node| unsafe {
             let node = &mut *node.as_ptr();
+            self.len -= 1;
             self.tail = node.prev;
             &mut node.elem
         })
     }
 }
 
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+pub struct ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    list: &'a mut List<T>,
+    next: Option<NonNull<Node<T>>>,
+    pred: F,
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next {
+            unsafe {
+                self.next = (*node.as_ptr()).next;
+                if (self.pred)(&mut (*node.as_ptr()).elem) {
+                    self.list.unlink_node(node);
+                    return Some(Box::from_raw(node.as_ptr()).elem);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.list.len))
+    }
+}
+
 impl<T> IntoIterator for List<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
@@ -120,3 +218,10 @@ impl<'a, T> IntoIterator for &'a mut List<T> {
         self.iter_mut()
     }
 }
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+// 两端相遇后不再输出，每个元素至多给出一个&mut，和std的LinkedList一样按T的Send/Sync推导
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
@@ -1,5 +1,14 @@
+mod cursor;
 mod iterator;
+mod sort;
 
+pub use cursor::CursorMut;
+pub use iterator::{ExtractIf, IntoIter, Iter, IterMut};
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::mem;
 use std::ptr::NonNull;
@@ -7,6 +16,7 @@ use std::ptr::NonNull;
 pub struct List<T> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
+    len: usize,
     marker: PhantomData<Box<Node<T>>>,
 }
 
@@ -46,6 +56,7 @@ impl<T> List<T> {
                 }
             }
             self.head = node;
+            self.len += 1;
         }
     }
 
@@ -62,6 +73,7 @@ impl<T> List<T> {
                     (*head.as_ptr()).prev = None;
                 }
             }
+            self.len -= 1;
             node
         })
     }
@@ -82,6 +94,7 @@ impl<T> List<T> {
                 }
             }
             self.tail = node;
+            self.len += 1;
         }
     }
 
@@ -98,9 +111,120 @@ impl<T> List<T> {
                     (*tail.as_ptr()).next = None;
                 }
             }
+            self.len -= 1;
             node
         })
     }
+
+    // 从链表中摘除node，不释放节点
+    #[inline]
+    fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        unsafe {
+            let node = &mut *node.as_ptr();
+            match node.prev {
+                None => self.head = node.next,
+                Some(prev) => (*prev.as_ptr()).next = node.next,
+            }
+            match node.next {
+                None => self.tail = node.prev,
+                Some(next) => (*next.as_ptr()).prev = node.prev,
+            }
+            self.len -= 1;
+        }
+    }
+
+    // 把从start到end的一串节点接到prev和next之间，prev为None表示接到头部，next为None表示接到尾部
+    #[inline]
+    fn splice_nodes(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        start: NonNull<Node<T>>,
+        end: NonNull<Node<T>>,
+        splice_len: usize,
+    ) {
+        unsafe {
+            match prev {
+                None => self.head = Some(start),
+                Some(prev) => (*prev.as_ptr()).next = Some(start),
+            }
+            match next {
+                None => self.tail = Some(end),
+                Some(next) => (*next.as_ptr()).prev = Some(end),
+            }
+            (*start.as_ptr()).prev = prev;
+            (*end.as_ptr()).next = next;
+        }
+        self.len += splice_len;
+    }
+
+    // 在node之后断开链表，返回后半部分，at是前半部分的长度。node为None时整个链表都属于后半部分
+    #[inline]
+    fn split_off_after_node(&mut self, node: Option<NonNull<Node<T>>>, at: usize) -> Self {
+        match node {
+            None => mem::take(self),
+            Some(node) => unsafe {
+                let second_head = (*node.as_ptr()).next.take();
+                let second_tail = second_head.and(self.tail);
+                if let Some(head) = second_head {
+                    (*head.as_ptr()).prev = None;
+                }
+                let second = List {
+                    head: second_head,
+                    tail: second_tail,
+                    len: self.len - at,
+                    marker: Default::default(),
+                };
+                self.tail = Some(node);
+                self.len = at;
+                second
+            },
+        }
+    }
+
+    // 在node之前断开链表，返回前半部分，at是前半部分的长度。node为None时整个链表都属于前半部分
+    #[inline]
+    fn split_off_before_node(&mut self, node: Option<NonNull<Node<T>>>, at: usize) -> Self {
+        match node {
+            None => mem::take(self),
+            Some(node) => unsafe {
+                let first_tail = (*node.as_ptr()).prev.take();
+                let first_head = first_tail.and(self.head);
+                if let Some(tail) = first_tail {
+                    (*tail.as_ptr()).next = None;
+                }
+                let first = List {
+                    head: first_head,
+                    tail: first_tail,
+                    len: at,
+                    marker: Default::default(),
+                };
+                self.head = Some(node);
+                self.len -= at;
+                first
+            },
+        }
+    }
+
+    // 第at个节点，从离at较近的一端开始查找，调用者保证at < len
+    #[inline]
+    fn node_at(&self, at: usize) -> NonNull<Node<T>> {
+        unsafe {
+            if at < self.len / 2 {
+                let mut node = self.head.expect("index out of bounds");
+                for _ in 0..at {
+                    node = (*node.as_ptr()).next.expect("index out of bounds");
+                }
+                node
+            } else {
+                let mut node = self.tail.expect("index out of bounds");
+                for _ in at + 1..self.len {
+                    node = (*node.as_ptr()).prev.expect("index out of bounds");
+                }
+                node
+            }
+        }
+    }
 }
 
 impl<T> List<T> {
@@ -108,6 +232,7 @@ impl<T> List<T> {
         List {
             head: None,
             tail: None,
+            len: 0,
             marker: Default::default(),
         }
     }
@@ -151,13 +276,15 @@ impl<T> List<T> {
                 mem::swap(self, other)
             }
             Some(mut tail) => {
+                // other为空时不能修改self.tail
                 if let Some(mut other_head) = other.head.take() {
                     unsafe {
                         tail.as_mut().next = Some(other_head);
                         other_head.as_mut().prev = Some(tail);
                     }
+                    self.tail = other.tail.take();
+                    self.len += mem::replace(&mut other.len, 0);
                 }
-                self.tail = other.tail.take();
             }
         }
     }
@@ -166,9 +293,67 @@ impl<T> List<T> {
         self.head.is_none()
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
     pub fn clear(&mut self) {
         *self = Self::new();
     }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|elem| elem == x)
+    }
+
+    // 在at处把链表一分为二，返回[at, len)部分
+    pub fn split_off(&mut self, at: usize) -> List<T> {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return mem::take(self);
+        }
+        if at == self.len {
+            return Self::new();
+        }
+        let node = self.node_at(at - 1);
+        self.split_off_after_node(Some(node), at)
+    }
+
+    pub fn insert(&mut self, at: usize, elem: T) {
+        assert!(at <= self.len, "Cannot insert at a nonexistent index");
+        if at == self.len {
+            return self.push_back(elem);
+        }
+        let next = self.node_at(at);
+        let prev = unsafe { (*next.as_ptr()).prev };
+        let node = NonNull::from(Box::leak(Box::new(Node::new(elem))));
+        self.splice_nodes(prev, Some(next), node, node, 1);
+    }
+
+    pub fn remove(&mut self, at: usize) -> T {
+        assert!(at < self.len, "Cannot remove at an index outside of the list bounds");
+        let node = self.node_at(at);
+        self.unlink_node(node);
+        let node = unsafe { Box::from_raw(node.as_ptr()) };
+        node.elem
+    }
+
+    // 只保留满足f的元素，保持原有顺序
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(elem) = cursor.current() {
+            if f(elem) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
 }
 
 impl<T> Default for List<T> {
@@ -194,3 +379,132 @@ impl<T> Drop for List<T> {
         }
     }
 }
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for List<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for List<T> {
+    fn from(arr: [T; N]) -> Self {
+        IntoIterator::into_iter(arr).collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        match iter.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in iter {
+                    write!(f, " <-> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+// 链表独占所有节点，和Box<T>一样按T的约束实现Send和Sync
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+/// ```compile_fail
+/// use an_ok_nonnull_deque::IterMut;
+///
+/// fn iter_mut_covariant<'i, 'a, T>(x: IterMut<'i, &'static T>) -> IterMut<'i, &'a T> {
+///     x
+/// }
+/// ```
+#[allow(dead_code)]
+fn iter_mut_invariant() {}
+
+/// ```compile_fail
+/// use an_ok_nonnull_deque::CursorMut;
+///
+/// fn cursor_mut_covariant<'c, 'a, T>(x: CursorMut<'c, &'static T>) -> CursorMut<'c, &'a T> {
+///     x
+/// }
+/// ```
+#[allow(dead_code)]
+fn cursor_mut_invariant() {}
+
+/// ```compile_fail
+/// use an_ok_nonnull_deque::List;
+/// use std::rc::Rc;
+///
+/// fn is_send<T: Send>() {}
+/// is_send::<List<Rc<i32>>>();
+/// ```
+#[allow(dead_code)]
+fn list_not_send() {}
+
+/// ```compile_fail
+/// use an_ok_nonnull_deque::List;
+/// use std::cell::Cell;
+///
+/// fn is_sync<T: Sync>() {}
+/// is_sync::<List<Cell<i32>>>();
+/// ```
+#[allow(dead_code)]
+fn list_not_sync() {}
@@ -0,0 +1,154 @@
+use crate::{List, Node};
+use std::cmp::Ordering;
+use std::mem;
+use std::ptr::{self, NonNull};
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+// 从slot指向的节点开始跳过n个节点，返回第n个节点之后的链接
+unsafe fn skip<T>(mut slot: *mut Link<T>, n: usize) -> *mut Link<T> {
+    for _ in 0..n {
+        let node = (*slot).expect("list broken");
+        slot = ptr::addr_of_mut!((*node.as_ptr()).next);
+    }
+    slot
+}
+
+// 原地合并相邻的两段有序链：从slot开始的a_len个节点和紧随其后的b_len个节点，返回合并后的段之后的链接。
+// 右段中较小的节点逐个摘下插到左段中，指针都在compare返回之后才修改，compare panic时所有节点仍在同一条next链上。
+// 相等时左段的节点在前，保证稳定
+unsafe fn merge<T, F>(mut slot: *mut Link<T>, mut a_len: usize, mut b_len: usize, compare: &mut F) -> *mut Link<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let b_slot = skip(slot, a_len);
+    while a_len > 0 && b_len > 0 {
+        let a = (*slot).expect("list broken");
+        let b = (*b_slot).expect("list broken");
+        if compare(&(*b.as_ptr()).elem, &(*a.as_ptr()).elem) == Ordering::Less {
+            *b_slot = (*b.as_ptr()).next;
+            (*b.as_ptr()).next = Some(a);
+            *slot = Some(b);
+            slot = ptr::addr_of_mut!((*b.as_ptr()).next);
+            b_len -= 1;
+        } else {
+            slot = ptr::addr_of_mut!((*a.as_ptr()).next);
+            a_len -= 1;
+        }
+    }
+    skip(slot, a_len + b_len)
+}
+
+// 自底向上的归并排序，每一轮把相邻的两段长为width的有序段合并，width逐轮翻倍。
+// 只维护next指针，排序过程中整条链始终完整
+unsafe fn merge_sort<T, F>(head: *mut Link<T>, len: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut width = 1;
+    while width < len {
+        let mut slot = head;
+        let mut rest = len;
+        while rest > width {
+            let b_len = width.min(rest - width);
+            slot = merge(slot, width, b_len, compare);
+            rest -= width + b_len;
+        }
+        width *= 2;
+    }
+}
+
+// 排序和合并只维护next指针，guard释放时重新设置prev指针和尾部。
+// compare panic时也会在展开过程中执行，链表仍然可用，只是元素的顺序没有排完
+struct RelinkGuard<'a, T>(&'a mut List<T>);
+
+impl<'a, T> Drop for RelinkGuard<'a, T> {
+    fn drop(&mut self) {
+        self.0.relink();
+    }
+}
+
+impl<T> List<T> {
+    // 从头部开始沿next重新设置prev指针和尾部
+    fn relink(&mut self) {
+        let mut prev = None;
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                (*node.as_ptr()).prev = prev;
+                cur = (*node.as_ptr()).next;
+            }
+            prev = Some(node);
+        }
+        self.tail = prev;
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(|a, b| a.cmp(b))
+    }
+
+    // 稳定的归并排序，只调整节点的指针，不分配内存。compare panic时链表中的元素不会丢失
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = self.len;
+        let guard = RelinkGuard(self);
+        unsafe { merge_sort(ptr::addr_of_mut!(guard.0.head), len, &mut compare) };
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    // 把有序的other合并进有序的self，相等的元素self中的在前
+    pub fn merge_sorted(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        let (a_len, b_len) = (self.len, other.len);
+        self.append(other);
+        let guard = RelinkGuard(self);
+        unsafe { merge(ptr::addr_of_mut!(guard.0.head), a_len, b_len, &mut |a: &T, b: &T| a.cmp(b)) };
+    }
+
+    // 删除连续重复的元素
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                while let Some(next) = (*node.as_ptr()).next {
+                    if (*next.as_ptr()).elem != (*node.as_ptr()).elem {
+                        break;
+                    }
+                    self.unlink_node(next);
+                    drop(Box::from_raw(next.as_ptr()));
+                }
+                cur = (*node.as_ptr()).next;
+            }
+        }
+    }
+
+    // 交换每个节点的next和prev，再交换头尾
+    pub fn reverse(&mut self) {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                let node = &mut *node.as_ptr();
+                mem::swap(&mut node.next, &mut node.prev);
+                cur = node.prev;
+            }
+        }
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+}
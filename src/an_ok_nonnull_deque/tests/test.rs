@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use an_ok_nonnull_deque::List;
+    use an_ok_nonnull_deque::{CursorMut, IntoIter, Iter, IterMut, List};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::panic::{self, AssertUnwindSafe};
 
     #[test]
     fn front_basics() {
@@ -86,9 +89,9 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
         assert_eq!(list.peek_back_mut(), Some(&mut 3));
-        list.peek_back_mut().map(|value| {
+        if let Some(value) = list.peek_back_mut() {
             *value = 11;
-        });
+        }
         assert_eq!(list.peek_back_mut(), Some(&mut 11));
         assert_eq!(list.peek_front_mut(), Some(&mut 1));
 
@@ -97,9 +100,9 @@ mod tests {
         list.push_front(2);
         list.push_front(3);
         assert_eq!(list.peek_front_mut(), Some(&mut 3));
-        list.peek_front_mut().map(|value| {
+        if let Some(value) = list.peek_front_mut() {
             *value = 11;
-        });
+        }
         assert_eq!(list.peek_front_mut(), Some(&mut 11));
         assert_eq!(list.peek_back_mut(), Some(&mut 1));
     }
@@ -129,7 +132,9 @@ mod tests {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
         assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
 
+        let mut iter = list.iter();
         assert_eq!(iter.next_back(), Some(&1));
         assert_eq!(iter.next_back(), Some(&2));
         assert_eq!(iter.next_back(), Some(&3));
@@ -143,10 +148,16 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
         let mut iter = list.iter_mut();
-        iter.next().map(|elem| *elem = 4);
-        iter.next().map(|elem| *elem = 5);
+        if let Some(elem) = iter.next() {
+            *elem = 4;
+        }
+        if let Some(elem) = iter.next() {
+            *elem = 5;
+        }
 
-        iter.next().map(|elem| *elem = 6);
+        if let Some(elem) = iter.next() {
+            *elem = 6;
+        }
         for elem in list.iter_mut() {
             *elem += 1;
         }
@@ -164,15 +175,15 @@ mod tests {
         list.push_back("sky".to_string());
         let mut iter = list.iter_mut();
 
-        iter.next_back().map(|node| {
+        if let Some(node) = iter.next_back() {
             node.push_str(" blue");
-        });
-        iter.next_back().map(|node| {
+        }
+        if let Some(node) = iter.next_back() {
             node.push_str(" yello");
-        });
-        iter.next_back().map(|node| {
+        }
+        if let Some(node) = iter.next_back() {
             node.push_str(" red");
-        });
+        }
 
         for name in list.iter_mut().rev() {
             name.push_str(" yes");
@@ -183,6 +194,37 @@ mod tests {
         assert_eq!(list.pop_back(), None);
     }
 
+    #[test]
+    fn iter_meet() {
+        let mut list = List::from([1, 2]);
+        let mut iter = list.iter();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // 两端相遇后不能再得到同一元素的第二个&mut
+        let mut iter = list.iter_mut();
+        let a = iter.next().unwrap();
+        let b = iter.next_back().unwrap();
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+        *a += 10;
+        *b += 20;
+        assert_eq!(collect(&list), vec![11, 22]);
+
+        list.push_back(3);
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.len(), 3);
+        let mid = iter.nth(1).unwrap();
+        assert_eq!(iter.next_back(), Some(&mut 3));
+        assert_eq!(iter.next_back(), None);
+        *mid = 0;
+        assert_eq!(collect(&list), vec![11, 0, 3]);
+    }
+
     #[test]
     fn append() {
         let mut list1 = List::new();
@@ -214,4 +256,471 @@ mod tests {
         list.clear();
         assert!(list.is_empty());
     }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        assert_eq!(list.len(), 3);
+        list.pop_front();
+        assert_eq!(list.len(), 2);
+
+        let mut empty = List::new();
+        list.append(&mut empty);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.peek_back(), Some(&2));
+
+        let mut other = List::new();
+        other.push_back(3);
+        other.push_back(4);
+        list.append(&mut other);
+        assert_eq!(list.len(), 4);
+        assert_eq!(other.len(), 0);
+        assert_eq!(list.peek_back(), Some(&4));
+
+        empty.append(&mut list);
+        assert_eq!(empty.len(), 4);
+        assert_eq!(list.len(), 0);
+
+        assert_eq!(empty.iter().count(), 4);
+        assert_eq!(empty.into_iter().len(), 4);
+
+        list.push_back(5);
+        list.push_back(6);
+        list.clear();
+        assert_eq!(list.len(), 0);
+    }
+
+    fn collect(list: &List<i32>) -> Vec<i32> {
+        let forward: Vec<i32> = list.iter().copied().collect();
+        let mut backward: Vec<i32> = list.iter().rev().copied().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+        forward
+    }
+
+    fn from_vec(v: Vec<i32>) -> List<i32> {
+        let mut list = List::new();
+        for x in v {
+            list.push_back(x);
+        }
+        list
+    }
+
+    #[test]
+    fn cursor_move() {
+        let mut list = from_vec(vec![1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.peek_prev(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.index(), Some(2));
+        *cursor.current().unwrap() = 30;
+        assert_eq!(collect(&list), vec![1, 2, 30]);
+
+        let mut empty: List<i32> = List::new();
+        let mut cursor = empty.cursor_back_mut();
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn cursor_insert_remove() {
+        let mut list = from_vec(vec![1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(10);
+        cursor.insert_after(20);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(collect(&list), vec![1, 10, 2, 20, 3]);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(cursor.index(), Some(6));
+        assert_eq!(collect(&list), vec![0, 1, 10, 2, 20, 3, 4]);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 10));
+        assert_eq!(cursor.index(), Some(1));
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(0));
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(collect(&list), vec![10, 2, 20, 3]);
+
+        let mut cursor = list.cursor_front_mut();
+        while cursor.remove_current().is_some() {}
+        assert!(list.is_empty());
+        assert_eq!(collect(&list), vec![]);
+        list.push_back(5);
+        assert_eq!(collect(&list), vec![5]);
+    }
+
+    #[test]
+    fn cursor_split() {
+        let mut list = from_vec(vec![1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let after = cursor.split_after();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(collect(&list), vec![2]);
+        assert_eq!(collect(&after), vec![3, 4, 5]);
+        assert_eq!(collect(&before), vec![1]);
+
+        let mut list = from_vec(vec![1, 2, 3]);
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(collect(&cursor.split_after()), vec![]);
+        cursor.move_next();
+        let all = cursor.split_before();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(collect(&all), vec![1, 2, 3]);
+        assert!(list.is_empty());
+
+        let mut list = from_vec(vec![1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(collect(&cursor.split_before()), vec![]);
+        cursor.move_prev();
+        let all = cursor.split_after();
+        assert_eq!(collect(&all), vec![1, 2, 3]);
+        assert_eq!(collect(&list), vec![]);
+    }
+
+    #[test]
+    fn cursor_splice() {
+        let mut list = from_vec(vec![1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.splice_before(from_vec(vec![10, 11]));
+        assert_eq!(cursor.index(), Some(3));
+        cursor.splice_after(from_vec(vec![20, 21]));
+        cursor.splice_after(List::new());
+        cursor.splice_before(List::new());
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(collect(&list), vec![1, 10, 11, 2, 20, 21, 3]);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        cursor.splice_after(from_vec(vec![-2, -1]));
+        cursor.splice_before(from_vec(vec![4, 5]));
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(10));
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(collect(&list), vec![-2, -1, 1, 10, 11, 2, 20, 21, 3, 4, 5]);
+
+        let mut empty = List::new();
+        let mut cursor = empty.cursor_front_mut();
+        cursor.splice_after(from_vec(vec![1, 2]));
+        cursor.splice_before(from_vec(vec![3]));
+        assert_eq!(collect(&empty), vec![1, 2, 3]);
+    }
+
+    fn hash_of<T: Hash>(x: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = (1..=3).collect();
+        assert_eq!(collect(&list), vec![1, 2, 3]);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", List::<i32>::new()), "[]");
+        assert_eq!(list.to_string(), "1 <-> 2 <-> 3");
+        assert_eq!(List::<i32>::new().to_string(), "Ø");
+
+        let mut cloned = list.clone();
+        assert_eq!(cloned, list);
+        cloned.extend(vec![4, 5]);
+        cloned.extend(&[6]);
+        assert_eq!(collect(&cloned), vec![1, 2, 3, 4, 5, 6]);
+        assert_ne!(cloned, list);
+        assert_eq!(List::from([1, 2, 3]), list);
+
+        assert!(list < cloned);
+        assert!(List::from([1, 3]) > list);
+        assert_eq!(list.partial_cmp(&list), Some(std::cmp::Ordering::Equal));
+        assert_eq!(List::from([f64::NAN]).partial_cmp(&List::from([1.0])), None);
+
+        assert_eq!(hash_of(&list), hash_of(&List::from([1, 2, 3])));
+        let nested_a: List<List<i32>> = List::from([List::from([1]), List::from([2, 3])]);
+        let nested_b: List<List<i32>> = List::from([List::from([1, 2]), List::from([3])]);
+        assert_ne!(hash_of(&nested_a), hash_of(&nested_b));
+
+        let mut iter = list.iter();
+        iter.next();
+        assert_eq!(iter.clone().copied().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn send_sync_variance() {
+        fn is_send<T: Send>() {}
+        fn is_sync<T: Sync>() {}
+
+        is_send::<List<i32>>();
+        is_sync::<List<i32>>();
+        is_send::<IntoIter<i32>>();
+        is_sync::<IntoIter<i32>>();
+        is_send::<Iter<i32>>();
+        is_sync::<Iter<i32>>();
+        is_send::<IterMut<i32>>();
+        is_sync::<IterMut<i32>>();
+        is_send::<CursorMut<i32>>();
+        is_sync::<CursorMut<i32>>();
+
+        fn list_covariant<'a, T>(x: List<&'static T>) -> List<&'a T> {
+            x
+        }
+        fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T>) -> Iter<'i, &'a T> {
+            x
+        }
+        fn into_iter_covariant<'a, T>(x: IntoIter<&'static T>) -> IntoIter<&'a T> {
+            x
+        }
+
+        static ONE: i32 = 1;
+        let two = 2;
+        let mut list = list_covariant(List::from([&ONE]));
+        list.push_back(&two);
+        assert_eq!(iter_covariant(List::from([&ONE]).iter()).count(), 1);
+        assert_eq!(into_iter_covariant(List::from([&ONE]).into_iter()).count(), 1);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn positional() {
+        let mut list: List<i32> = (0..10).collect();
+        let tail = list.split_off(7);
+        assert_eq!(collect(&tail), vec![7, 8, 9]);
+        let mut mid = list.split_off(2);
+        assert_eq!(collect(&mid), vec![2, 3, 4, 5, 6]);
+        assert_eq!(collect(&list.split_off(2)), vec![]);
+        assert_eq!(collect(&mid.split_off(0)), vec![2, 3, 4, 5, 6]);
+        assert!(mid.is_empty());
+        assert_eq!(collect(&list), vec![0, 1]);
+
+        list.insert(0, -1);
+        list.insert(3, 3);
+        list.insert(2, 10);
+        list.insert(1, 20);
+        assert_eq!(collect(&list), vec![-1, 20, 0, 10, 1, 3]);
+        assert_eq!(list.remove(0), -1);
+        assert_eq!(list.remove(4), 3);
+        assert_eq!(list.remove(1), 0);
+        assert_eq!(collect(&list), vec![20, 10, 1]);
+        assert!(list.contains(&10));
+        assert!(!list.contains(&0));
+
+        let mut list: List<i32> = (0..100).collect();
+        let mut vec: Vec<i32> = (0..100).collect();
+        for i in 0..100 {
+            let at = i * 37 % vec.len();
+            assert_eq!(list.remove(at), vec.remove(at));
+            let at = i * 13 % (vec.len() + 1);
+            list.insert(at, i as i32 + 100);
+            vec.insert(at, i as i32 + 100);
+        }
+        assert_eq!(collect(&list), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_out_of_bounds() {
+        let mut list: List<i32> = (0..3).collect();
+        list.remove(3);
+    }
+
+    #[test]
+    fn retain_extract_if() {
+        let mut list: List<i32> = (0..10).collect();
+        list.retain(|x| x % 3 != 0);
+        assert_eq!(collect(&list), vec![1, 2, 4, 5, 7, 8]);
+        list.retain(|_| false);
+        assert_eq!(collect(&list), vec![]);
+
+        let mut list: List<i32> = (0..10).collect();
+        let evens: Vec<i32> = list.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, vec![0, 2, 4, 6, 8]);
+        assert_eq!(collect(&list), vec![1, 3, 5, 7, 9]);
+
+        let first = list
+            .extract_if(|x| {
+                *x *= 10;
+                *x > 20
+            })
+            .next();
+        assert_eq!(first, Some(30));
+        assert_eq!(collect(&list), vec![10, 5, 7, 9]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut list: List<i32> = List::new();
+        list.sort();
+        assert!(list.is_empty());
+
+        let mut list = List::from([5, 3, 8, 1, 9, 2, 7, 3, 0]);
+        list.sort();
+        assert_eq!(list, List::from([0, 1, 2, 3, 3, 5, 7, 8, 9]));
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(collect(&list), vec![9, 8, 7, 5, 3, 3, 2, 1, 0]);
+
+        // 排序后从尾部开始的游标要落在新的最后一个元素上
+        let mut words: List<&str> = "pear fig apple kiwi banana date".split(' ').collect();
+        words.sort_by_key(|word| word.len());
+        assert_eq!(words, List::from(["fig", "pear", "kiwi", "date", "apple", "banana"]));
+        let mut cursor = words.cursor_back_mut();
+        assert_eq!(cursor.current(), Some(&mut "banana"));
+        cursor.insert_after("cherry");
+        assert_eq!(words.peek_back(), Some(&"cherry"));
+
+        // 长度不是2的幂时最后一段较短
+        let mut x = 7u32;
+        let vec: Vec<i32> = (0..333)
+            .map(|_| {
+                x = x.wrapping_mul(1103515245).wrapping_add(12345) % 1000;
+                x as i32
+            })
+            .collect();
+        let mut list: List<i32> = vec.iter().copied().collect();
+        let mut sorted = vec.clone();
+        list.sort();
+        sorted.sort();
+        assert_eq!(collect(&list), sorted);
+    }
+
+    #[test]
+    fn sort_panic() {
+        // 比较函数在排序中途panic，链表中的元素不丢失，两个方向仍然一致
+        let mut list = List::from([5, 3, 8, 1, 9, 2, 7, 3, 0]);
+        let mut calls = 0;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                assert!(calls < 10, "comparator gave up");
+                a.cmp(b)
+            })
+        }));
+        assert!(res.is_err());
+        let mut elems = collect(&list);
+        elems.sort();
+        assert_eq!(elems, vec![0, 1, 2, 3, 3, 5, 7, 8, 9]);
+        list.push_back(4);
+        list.sort();
+        assert_eq!(collect(&list), vec![0, 1, 2, 3, 3, 4, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut evens: List<(i32, char)> = (0..5).map(|x| (x * 2, 'e')).collect();
+        let mut mixed = List::from([(1, 'm'), (2, 'm'), (7, 'm'), (8, 'm'), (11, 'm')]);
+        evens.merge_sorted(&mut mixed);
+        assert!(mixed.is_empty());
+        assert_eq!(
+            evens,
+            List::from([(0, 'e'), (1, 'm'), (2, 'e'), (2, 'm'), (4, 'e'), (6, 'e'), (7, 'm'), (8, 'e'), (8, 'm'), (11, 'm')])
+        );
+        // 合并后prev链完整，可以从尾部切分
+        let high = evens.split_off(7);
+        assert_eq!(high, List::from([(8, 'e'), (8, 'm'), (11, 'm')]));
+        assert_eq!(evens.peek_back(), Some(&(7, 'm')));
+
+        // 空链表合并进来或合并到空链表里
+        let mut empty = List::new();
+        mixed.merge_sorted(&mut evens);
+        mixed.merge_sorted(&mut empty);
+        assert_eq!(mixed.len(), 7);
+        assert_eq!(mixed.iter().next_back(), Some(&(7, 'm')));
+    }
+
+    #[test]
+    fn dedup_reverse() {
+        let mut list = List::from([1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        list.dedup();
+        assert_eq!(list, List::from([1, 2, 3, 1, 4]));
+        list.reverse();
+        assert_eq!(collect(&list), vec![4, 1, 3, 2, 1]);
+
+        // 反转之后游标两端和按下标操作仍然正确
+        list.cursor_front_mut().insert_before(5);
+        list.insert(list.len(), 0);
+        assert_eq!(list.remove(2), 1);
+        assert_eq!(collect(&list), vec![5, 4, 3, 2, 1, 0]);
+
+        let mut list: List<i32> = List::new();
+        list.reverse();
+        list.dedup();
+        assert!(list.is_empty());
+        let mut list = List::from([7, 7, 7]);
+        list.dedup();
+        list.reverse();
+        assert_eq!(collect(&list), vec![7]);
+    }
+
+    #[test]
+    fn miri_food() {
+        let mut list = List::from([4, 2, 2, 9, 1, 4]);
+        list.sort();
+        list.dedup();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let tail = cursor.split_after();
+        assert_eq!(collect(&tail), vec![4, 9]);
+        list.reverse();
+        for x in list.iter_mut() {
+            *x *= 10;
+        }
+
+        let mut other = tail;
+        other.retain(|x| *x != 9);
+        list.sort_by_key(|x| -x);
+        list.reverse();
+        list.merge_sorted(&mut other);
+        assert_eq!(collect(&list), vec![4, 10, 20]);
+        let removed: Vec<i32> = list.extract_if(|x| *x >= 10).collect();
+        assert_eq!(removed, vec![10, 20]);
+        list.push_front(3);
+        list.push_back(5);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next(), Some(3));
+
+        // Drop it on the ground and let the dtor exercise itself
+    }
 }
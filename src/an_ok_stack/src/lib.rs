@@ -1,6 +1,8 @@
+use std::fmt::{self, Debug, Display, Formatter};
 
 pub struct List<T> {
     head: Link<T>,
+    len: usize,
 }
 
 type Link<T> = Option<Box<Node<T>>>;
@@ -14,6 +16,7 @@ impl<T> List<T> {
     pub fn new() -> Self {
         List {
             head: None,
+            len: 0,
         }
     }
 
@@ -23,11 +26,13 @@ impl<T> List<T> {
             next: self.head.take(),
         });
         self.head = Some(new_node);
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
         self.head.take().map(|node| {
             self.head = node.next;
+            self.len -= 1;
             node.elem
         })
     }
@@ -64,6 +69,16 @@ impl<T> List<T> {
     pub fn is_empty(&self) -> bool{
         self.head.is_none()
     }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Drop for List<T> {
@@ -78,8 +93,11 @@ impl<T> Drop for List<T> {
 //实现IntoIter迭代器
 pub struct IntoIter<T>(List<T>);
 
-impl<T> List<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
         IntoIter(self)
     }
 }
@@ -100,8 +118,7 @@ pub struct Iter<'a, T> {
 impl<T> List<T> {
     pub fn iter(&self) -> Iter<'_, T> {
         Iter {
-            next: self.head.as_ref().map(|node| &**node)
-            //next: self.head.as_deref()
+            next: self.head.as_deref()
         }
     }
 }
@@ -143,6 +160,28 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     }
 }
 
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        match iter.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in iter {
+                    write!(f, " -> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
@@ -179,6 +218,22 @@ mod tests {
         assert_eq!(list.pop(), None);
     }
 
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+        list.pop();
+        assert_eq!(list.len(), 2);
+        list.pop();
+        list.pop();
+        list.pop();
+        assert_eq!(list.len(), 0);
+    }
+
     #[test]
     fn peek() {
         let mut list = List::new();
@@ -201,9 +256,9 @@ mod tests {
         assert_eq!(list.peek(), Some(&String::from("hello")));
         assert_eq!(list.peek_mut(), Some(&mut String::from("hello")));
 
-        list.peek_mut().map(|value| {
+        if let Some(value) = list.peek_mut() {
             value.push_str(" world");
-        });
+        }
 
         assert_eq!(list.peek(), Some(&String::from("hello world")));
         assert_eq!(list.pop(), Some(String::from("hello world")));
@@ -261,20 +316,20 @@ mod tests {
         list.push(2);
         list.push(3);
         let mut iter = list.iter_mut();
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 4
-        });
-        iter.next().map(|elem| {
+        }
+        if let Some(elem) = iter.next() {
             *elem = 5
-        });
+        }
 
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 6
-        });
+        }
 
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 7
-        });
+        }
 
         let mut iter = list.iter_mut();
         assert_eq!(iter.next(), Some(&mut 4));
@@ -282,4 +337,17 @@ mod tests {
         assert_eq!(iter.next(), Some(&mut 6));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.to_string(), "3 -> 2 -> 1");
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+        assert_eq!(format!("{:#?}", list), "[\n    3,\n    2,\n    1,\n]");
+    }
 }
@@ -1,250 +1,485 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ptr;
 
 pub struct List<T> {
-    head: Option<Box<Node<T>>>,
-    tail: *mut Node<T>,
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<Box<Node<T>>>,
 }
 
+// 节点都用裸指针连接，由List统一负责分配和释放，没有任何节点被Box持有
+type Link<T> = *mut Node<T>;
+
 struct Node<T> {
     elem: T,
-    next: Option<Box<Node<T>>>,
-    prev: *mut Node<T>,
+    next: Link<T>,
+    prev: Link<T>,
 }
 
 impl<T> Node<T> {
-    pub fn new(elem: T) -> Self {
-        Node {
+    // 在堆上分配节点，返回的裸指针由链表负责释放
+    fn new(elem: T) -> Link<T> {
+        Box::into_raw(Box::new(Node {
             elem,
-            next: None,
-            prev: std::ptr::null_mut(),
-        }
-    }
-}
-
-impl<T: Default> Default for Node<T> {
-    fn default() -> Self {
-        Node::new(T::default())
+            next: ptr::null_mut(),
+            prev: ptr::null_mut(),
+        }))
     }
 }
 
-impl<T: Default> List<T> {
+impl<T> List<T> {
     pub fn new() -> Self {
         List {
-            head: None,
-            tail: std::ptr::null_mut(),
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+            len: 0,
+            marker: PhantomData,
         }
     }
 
     pub fn push_front(&mut self, elem: T) {
-        let mut new_head = Box::new(Node::new(elem));
-        let raw_head: *mut Node<T> = &mut *new_head;
-        match self.head.take() {
-            None => {
-                self.tail = raw_head;
-            }
-            Some(mut node) => {
-                node.prev = raw_head;
-                new_head.next = Some(node);
+        unsafe {
+            let new_head = Node::new(elem);
+            if self.head.is_null() {
+                self.tail = new_head;
+            } else {
+                (*self.head).prev = new_head;
+                (*new_head).next = self.head;
             }
+            self.head = new_head;
+            self.len += 1;
         }
-        self.head = Some(new_head);
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        self.head.take().map(|old_head| {
-            match old_head.next {
-                None => {
-                    self.tail = std::ptr::null_mut();
-                    self.head = None;
-                }
-                Some(mut new_head) => {
-                    new_head.prev = std::ptr::null_mut();
-                    self.head = Some(new_head);
-                }
+        if self.head.is_null() {
+            return None;
+        }
+        unsafe {
+            let old_head = Box::from_raw(self.head);
+            self.head = old_head.next;
+            if self.head.is_null() {
+                self.tail = ptr::null_mut();
+            } else {
+                (*self.head).prev = ptr::null_mut();
             }
-            old_head.elem
-        })
+            self.len -= 1;
+            Some(old_head.elem)
+        }
     }
 
     pub fn peek_front(&self) -> Option<&T> {
-        self.head.as_ref().map(|node| {
-            &node.elem
-        })
+        unsafe { self.head.as_ref().map(|node| &node.elem) }
     }
 
     pub fn peek_front_mut(&mut self) -> Option<&mut T> {
-        self.head.as_mut().map(|node| {
-            &mut node.elem
-        })
+        unsafe { self.head.as_mut().map(|node| &mut node.elem) }
     }
 
-
     pub fn push_back(&mut self, elem: T) {
-        let mut new_tail = Box::new(Node::new(elem));
-        let raw_tail: *mut Node<T> = &mut *new_tail;
-        new_tail.prev = self.tail;
-        if !self.tail.is_null() {
-            unsafe {
-                (*self.tail).next = Some(new_tail);
+        unsafe {
+            let new_tail = Node::new(elem);
+            if self.tail.is_null() {
+                self.head = new_tail;
+            } else {
+                (*self.tail).next = new_tail;
+                (*new_tail).prev = self.tail;
             }
+            self.tail = new_tail;
+            self.len += 1;
         }
-        else {
-            self.head = Some(new_tail);
-        }
-        self.tail = raw_tail;
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        if !self.tail.is_null() {
-            unsafe {
-                let old_tail = self.tail.replace(Node::default());
-                if !old_tail.prev.is_null() {
-                    (*old_tail.prev).next = None;
-                    self.tail = old_tail.prev;
-                }
-                else {
-                    self.head = None;
-                    self.tail = std::ptr::null_mut();
-                }
-                Some(old_tail.elem)
-            }
+        if self.tail.is_null() {
+            return None;
         }
-        else {
-            None
+        unsafe {
+            let old_tail = Box::from_raw(self.tail);
+            self.tail = old_tail.prev;
+            if self.tail.is_null() {
+                self.head = ptr::null_mut();
+            } else {
+                (*self.tail).next = ptr::null_mut();
+            }
+            self.len -= 1;
+            Some(old_tail.elem)
         }
     }
 
     pub fn peek_back(&self) -> Option<&T> {
-        if !self.tail.is_null() {
-            unsafe {
-                Some(&(*self.tail).elem)
-            }
-        }
-        else {
-            None
-        }
+        unsafe { self.tail.as_ref().map(|node| &node.elem) }
     }
 
     pub fn peek_back_mut(&mut self) -> Option<&mut T> {
-        if !self.tail.is_null() {
+        unsafe { self.tail.as_mut().map(|node| &mut node.elem) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        if self.tail.is_null() {
+            std::mem::swap(self, other);
+            return;
+        }
+        // other为空时不能修改self.tail
+        if !other.head.is_null() {
             unsafe {
-                Some(&mut (*self.tail).elem)
+                (*self.tail).next = other.head;
+                (*other.head).prev = self.tail;
             }
+            self.tail = std::mem::replace(&mut other.tail, ptr::null_mut());
+            other.head = ptr::null_mut();
+            self.len += std::mem::replace(&mut other.len, 0);
         }
-        else {
-            None
-        }
-    }
-
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
     }
 
     pub fn iter(&self) -> Iter<'_, T> {
         Iter {
-            next: self.head.as_deref(),
+            next: self.head,
             prev: self.tail,
+            len: self.len,
+            marker: PhantomData,
         }
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
-            next: self.head.as_deref_mut(),
+            next: self.head,
             prev: self.tail,
+            len: self.len,
+            marker: PhantomData,
         }
     }
 }
 
-impl<T> Drop for List<T> {
+// 从slot指向的节点开始跳过n个节点，返回第n个节点之后的链接
+unsafe fn skip<T>(mut slot: *mut Link<T>, n: usize) -> *mut Link<T> {
+    for _ in 0..n {
+        slot = ptr::addr_of_mut!((**slot).next);
+    }
+    slot
+}
+
+// 原地合并相邻的两段有序链：从slot开始的a_len个节点和紧随其后的b_len个节点，返回合并后的段之后的链接。
+// 右段中较小的节点摘下插到左段中，指针都在compare返回之后才修改，compare panic时所有节点仍在同一条next链上。
+// 相等时左段的节点在前，保证稳定
+unsafe fn merge<T, F>(mut slot: *mut Link<T>, mut a_len: usize, mut b_len: usize, compare: &mut F) -> *mut Link<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let b_slot = skip(slot, a_len);
+    while a_len > 0 && b_len > 0 {
+        let (a, b) = (*slot, *b_slot);
+        if compare(&(*b).elem, &(*a).elem) == Ordering::Less {
+            *b_slot = (*b).next;
+            (*b).next = a;
+            *slot = b;
+            slot = ptr::addr_of_mut!((*b).next);
+            b_len -= 1;
+        } else {
+            slot = ptr::addr_of_mut!((*a).next);
+            a_len -= 1;
+        }
+    }
+    skip(slot, a_len + b_len)
+}
+
+// 自底向上的归并排序，相邻的两段长为width的有序段两两合并，width逐轮翻倍，整条链始终完整
+unsafe fn merge_sort<T, F>(head: *mut Link<T>, len: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut width = 1;
+    while width < len {
+        let mut slot = head;
+        let mut rest = len;
+        while rest > width {
+            let b_len = width.min(rest - width);
+            slot = merge(slot, width, b_len, compare);
+            rest -= width + b_len;
+        }
+        width *= 2;
+    }
+}
+
+// 离开作用域时调用relink，compare panic展开时也会执行，链表保持可用
+struct Relink<'a, T>(&'a mut List<T>);
+
+impl<'a, T> Drop for Relink<'a, T> {
     fn drop(&mut self) {
-        let mut cur_node = self.head.take();
-        while let Some(mut node) = cur_node {
-            cur_node = node.next.take()
+        self.0.relink();
+    }
+}
+
+impl<T> List<T> {
+    // 重新设置prev指针和尾部，用于只调整了next的操作之后
+    fn relink(&mut self) {
+        let mut prev = ptr::null_mut();
+        let mut cur = self.head;
+        while !cur.is_null() {
+            unsafe {
+                (*cur).prev = prev;
+                prev = cur;
+                cur = (*cur).next;
+            }
+        }
+        self.tail = prev;
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(|a, b| a.cmp(b))
+    }
+
+    // 稳定的归并排序，只调整节点的指针，不分配内存
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = self.len;
+        let guard = Relink(self);
+        unsafe { merge_sort(ptr::addr_of_mut!(guard.0.head), len, &mut compare) };
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    // 把有序的other合并进有序的self，相等的元素self中的在前
+    pub fn merge_sorted(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        let (a_len, b_len) = (self.len, other.len);
+        self.append(other);
+        let guard = Relink(self);
+        unsafe { merge(ptr::addr_of_mut!(guard.0.head), a_len, b_len, &mut |a: &T, b: &T| a.cmp(b)) };
+    }
+
+    // 删除连续重复的元素
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let mut cur = self.head;
+        while !cur.is_null() {
+            unsafe {
+                let mut next = (*cur).next;
+                while !next.is_null() && (*next).elem == (*cur).elem {
+                    let removed = Box::from_raw(next);
+                    next = removed.next;
+                    self.len -= 1;
+                }
+                (*cur).next = next;
+                cur = next;
+            }
         }
-        self.tail = std::ptr::null_mut();
+        self.relink();
+    }
+
+    // 交换每个节点的next和prev，再交换头尾
+    pub fn reverse(&mut self) {
+        let mut cur = self.head;
+        while !cur.is_null() {
+            unsafe {
+                let node = &mut *cur;
+                std::mem::swap(&mut node.next, &mut node.prev);
+                cur = node.prev;
+            }
+        }
+        std::mem::swap(&mut self.head, &mut self.tail);
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        self.clear();
     }
 }
 
 pub struct IntoIter<T>(List<T>);
 
-impl<T: Default> Iterator for IntoIter<T> {
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
-impl<T: Default> DoubleEndedIterator for IntoIter<T> {
+impl<T> DoubleEndedIterator for IntoIter<T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.0.pop_back()
     }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
 pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
-    prev: *mut Node<T>,
+    next: Link<T>,
+    prev: Link<T>,
+    // 剩余未输出的元素个数，为0时两端相遇
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next.take().map(|node| {
-            self.next = node.next.as_deref();
-            &node.elem
-        })
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            self.next.as_ref().map(|node| {
+                self.len -= 1;
+                self.next = node.next;
+                &node.elem
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
     }
 }
 
 impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if !self.prev.is_null() {
-            let raw_cur = self.prev;
-            unsafe {
-                self.prev = (*raw_cur).prev;
-                Some(&(*raw_cur).elem)
-            }
+        if self.len == 0 {
+            return None;
         }
-        else {
-            None
+        unsafe {
+            self.prev.as_ref().map(|node| {
+                self.len -= 1;
+                self.prev = node.prev;
+                &node.elem
+            })
         }
     }
 }
 
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
 pub struct IterMut<'a, T> {
-    next: Option<&'a mut Node<T>>,
-    prev: *mut Node<T>,
+    next: Link<T>,
+    prev: Link<T>,
+    // 剩余未输出的元素个数，为0时两端相遇，不能再对同一节点给出第二个&mut
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next.take().map(|node| {
-            self.next = node.next.as_deref_mut();
-            &mut node.elem
-        })
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            self.next.as_mut().map(|node| {
+                self.len -= 1;
+                self.next = node.next;
+                &mut node.elem
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
     }
 }
 
 impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if !self.prev.is_null() {
-            let raw_cur = self.prev;
-            unsafe {
-                self.prev = (*raw_cur).prev;
-                Some(&mut (*raw_cur).elem)
-            }
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            self.prev.as_mut().map(|node| {
+                self.len -= 1;
+                self.prev = node.prev;
+                &mut node.elem
+            })
         }
-        else {
-            None
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        match iter.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in iter {
+                    write!(f, " <-> {}", elem)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
+    use std::cmp::Ordering;
+    use std::panic::{self, AssertUnwindSafe};
 
     #[test]
     fn front_basics() {
@@ -304,6 +539,25 @@ mod tests {
         assert_eq!(list.pop_front(), None);
     }
 
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        list.push_front(1);
+        list.push_back(2);
+        list.push_front(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().count(), 3);
+        list.pop_back();
+        assert_eq!(list.len(), 2);
+        list.pop_front();
+        list.pop_front();
+        list.pop_back();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+    }
+
     #[test]
     fn peek() {
         let mut list = List::new();
@@ -330,9 +584,9 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
         assert_eq!(list.peek_back_mut(), Some(&mut 3));
-        list.peek_back_mut().map(|value| {
+        if let Some(value) = list.peek_back_mut() {
             *value = 11;
-        });
+        }
         assert_eq!(list.peek_back_mut(), Some(&mut 11));
         assert_eq!(list.peek_front_mut(), Some(&mut 1));
 
@@ -341,9 +595,9 @@ mod tests {
         list.push_front(2);
         list.push_front(3);
         assert_eq!(list.peek_front_mut(), Some(&mut 3));
-        list.peek_front_mut().map(|value| {
+        if let Some(value) = list.peek_front_mut() {
             *value = 11;
-        });
+        }
         assert_eq!(list.peek_front_mut(), Some(&mut 11));
         assert_eq!(list.peek_back_mut(), Some(&mut 1));
     }
@@ -369,7 +623,9 @@ mod tests {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
         assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
 
+        let mut iter = list.iter();
         assert_eq!(iter.next_back(), Some(&1));
         assert_eq!(iter.next_back(), Some(&2));
         assert_eq!(iter.next_back(), Some(&3));
@@ -383,16 +639,16 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
         let mut iter = list.iter_mut();
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 4
-        });
-        iter.next().map(|elem| {
+        }
+        if let Some(elem) = iter.next() {
             *elem = 5
-        });
+        }
 
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 6
-        });
+        }
         for elem in list.iter_mut() {
             *elem += 1;
         }
@@ -410,15 +666,15 @@ mod tests {
         list.push_back("sky".to_string());
         let mut iter = list.iter_mut();
 
-        iter.next_back().map(|node| {
+        if let Some(node) = iter.next_back() {
             node.push_str(" blue");
-        });
-        iter.next_back().map(|node| {
+        }
+        if let Some(node) = iter.next_back() {
             node.push_str(" yello");
-        });
-        iter.next_back().map(|node| {
+        }
+        if let Some(node) = iter.next_back() {
             node.push_str(" red");
-        });
+        }
 
         for name in list.iter_mut().rev() {
             name.push_str(" yes");
@@ -429,4 +685,306 @@ mod tests {
         assert_eq!(list.pop_back(), None);
 
     }
+
+    // Display沿next从头输出，再沿prev从尾部读一遍，两个方向必须一致，返回Display的结果
+    fn show(list: &List<i32>) -> String {
+        let mut backward: Vec<String> = list.iter().rev().map(|x| x.to_string()).collect();
+        backward.reverse();
+        assert_eq!(backward.len(), list.len());
+        let shown = list.to_string();
+        if !list.is_empty() {
+            assert_eq!(shown, backward.join(" <-> "));
+        }
+        shown
+    }
+
+    #[test]
+    fn sort() {
+        let mut list = List::new();
+        list.sort();
+        assert_eq!(show(&list), "Ø");
+        list.push_back(2);
+        list.push_back(1);
+        list.sort();
+        assert_eq!(show(&list), "1 <-> 2");
+
+        for x in [8, 0, 5, 3].iter() {
+            list.push_front(*x);
+        }
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(show(&list), "8 <-> 5 <-> 3 <-> 2 <-> 1 <-> 0");
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.pop_back(), Some(1));
+        list.push_back(-1);
+        assert_eq!(list.pop_front(), Some(8));
+
+        // 稳定性：按长度排序后长度相同的字符串保持原有顺序
+        let mut words = List::new();
+        for word in ["ccc", "a", "bb", "dd", "e", "fff"].iter() {
+            words.push_back(*word);
+        }
+        words.sort_by_key(|word| word.len());
+        assert_eq!(words.to_string(), "a <-> e <-> bb <-> dd <-> ccc <-> fff");
+        assert_eq!(words.peek_back(), Some(&"fff"));
+
+        let mut list = List::new();
+        let mut vec = Vec::new();
+        let mut x = 11u32;
+        for _ in 0..257 {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345) % 512;
+            list.push_front(x as i32);
+            vec.insert(0, x as i32);
+        }
+        list.sort();
+        vec.sort();
+        let mut sorted = Vec::new();
+        while let Some(x) = list.pop_front() {
+            sorted.push(x);
+        }
+        assert_eq!(sorted, vec);
+    }
+
+    #[test]
+    fn sort_panic() {
+        // 比较函数中途panic后prev和tail已经重新设置，链表可以继续使用
+        let mut list = List::new();
+        for x in [4, 1, 3, 1, 5, 9, 2, 6].iter() {
+            list.push_back(*x);
+        }
+        let mut calls = 0;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by_key(|x| {
+                calls += 1;
+                assert!(calls < 12, "key function gave up");
+                *x
+            })
+        }));
+        assert!(res.is_err());
+        assert_eq!(list.len(), 8);
+        assert_eq!(list.iter().rev().count(), 8);
+        assert_eq!(list.iter().sum::<i32>(), 31);
+        while list.pop_back().is_some() {}
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut a = List::new();
+        let mut b = List::new();
+        a.merge_sorted(&mut b);
+        assert!(a.is_empty());
+        for x in [2, 4, 4, 9].iter() {
+            a.push_back(*x);
+        }
+        a.merge_sorted(&mut b);
+        assert_eq!(show(&a), "2 <-> 4 <-> 4 <-> 9");
+        for x in [1, 4, 10].iter() {
+            b.push_back(*x);
+        }
+        a.merge_sorted(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(b.peek_back(), None);
+        assert_eq!(show(&a), "1 <-> 2 <-> 4 <-> 4 <-> 4 <-> 9 <-> 10");
+        assert_eq!(a.len(), 7);
+
+        // 只按key比较，相等时self中的元素在前
+        struct Tagged(i32, char);
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Tagged {}
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Tagged {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        let mut left = List::new();
+        let mut right = List::new();
+        left.push_back(Tagged(1, 'l'));
+        right.push_back(Tagged(1, 'r'));
+        right.push_back(Tagged(0, 'r'));
+        right.reverse();
+        right.merge_sorted(&mut left);
+        let tags: Vec<(i32, char)> = right.into_iter().rev().map(|t| (t.0, t.1)).collect();
+        assert_eq!(tags, vec![(1, 'l'), (1, 'r'), (0, 'r')]);
+    }
+
+    #[test]
+    fn dedup_reverse() {
+        let mut list = List::new();
+        list.reverse();
+        list.dedup();
+        assert_eq!(show(&list), "Ø");
+
+        for x in [3, 3, 3].iter() {
+            list.push_back(*x);
+        }
+        list.dedup();
+        assert_eq!(show(&list), "3");
+        assert_eq!(list.len(), 1);
+
+        for x in [1, 1, 3, 2, 2].iter() {
+            list.push_front(*x);
+        }
+        list.dedup();
+        assert_eq!(show(&list), "2 <-> 3 <-> 1 <-> 3");
+        list.reverse();
+        assert_eq!(show(&list), "3 <-> 1 <-> 3 <-> 2");
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+        list.push_back(3);
+        list.dedup();
+        list.reverse();
+        assert_eq!(show(&list), "3 <-> 1");
+    }
+
+    #[test]
+    fn miri_food() {
+        let mut list = List::new();
+        for x in [6, 1, 1, 8, 3].iter() {
+            list.push_front(*x);
+        }
+        list.dedup();
+        list.sort_by(|a, b| b.cmp(a));
+        for x in list.iter_mut().rev() {
+            *x += 1;
+        }
+        assert_eq!(show(&list), "9 <-> 7 <-> 4 <-> 2");
+
+        let mut other = List::new();
+        other.push_back(5);
+        other.push_back(3);
+        other.reverse();
+        list.reverse();
+        list.merge_sorted(&mut other);
+        if let Some(x) = list.peek_front_mut() {
+            *x = 0;
+        }
+        assert_eq!(show(&list), "0 <-> 3 <-> 4 <-> 5 <-> 7 <-> 9");
+
+        // 一边修改一边从两端摘下节点，再接到另一条链表后面
+        let mut iter = list.iter_mut();
+        if let (Some(front), Some(back)) = (iter.next(), iter.next_back()) {
+            std::mem::swap(front, back);
+        }
+        let mut rest = List::new();
+        rest.push_back(10);
+        rest.append(&mut list);
+        rest.sort_by_key(|x| *x % 3);
+        assert_eq!(show(&rest), "9 <-> 3 <-> 0 <-> 10 <-> 4 <-> 7 <-> 5");
+        let mut iter = rest.into_iter();
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next(), Some(9));
+
+        list.push_back(1);
+        list.clear();
+        list.push_back(2);
+
+        // Drop it on the ground and let the dtor exercise itself
+    }
+
+    #[test]
+    fn miri_iter_meet() {
+        let mut list = List::new();
+        list.push_back(1);
+        list.push_back(2);
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // 两端相遇后不能再得到同一元素的第二个&mut
+        let mut iter = list.iter_mut();
+        let a = iter.next().unwrap();
+        let b = iter.next_back().unwrap();
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+        *a += 10;
+        *b += 20;
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![11, 22]);
+
+        list.push_back(3);
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        let mid = iter.nth(1).unwrap();
+        assert_eq!(iter.next_back(), Some(&mut 3));
+        assert_eq!(iter.next_back(), None);
+        *mid = 0;
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![3, 0, 11]);
+    }
+
+    #[test]
+    fn no_default_bound() {
+        struct Handle(i32);
+
+        let mut list = List::default();
+        list.push_back(Handle(1));
+        list.push_back(Handle(2));
+        list.push_front(Handle(0));
+        assert_eq!(list.pop_back().map(|h| h.0), Some(2));
+        assert_eq!(list.pop_back().map(|h| h.0), Some(1));
+        assert_eq!(list.pop_back().map(|h| h.0), Some(0));
+        assert!(list.pop_back().is_none());
+        list.push_back(Handle(3));
+        list.push_back(Handle(4));
+        let elems: Vec<i32> = list.into_iter().rev().map(|h| h.0).collect();
+        assert_eq!(elems, vec![4, 3]);
+    }
+
+    #[test]
+    fn clear_append() {
+        let mut list = List::new();
+        let mut other = List::new();
+        list.append(&mut other);
+        assert!(list.is_empty());
+
+        other.push_back(1);
+        other.push_back(2);
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(show(&list), "1 <-> 2");
+
+        list.append(&mut other);
+        assert_eq!(show(&list), "1 <-> 2");
+
+        other.push_back(3);
+        other.push_back(4);
+        list.append(&mut other);
+        assert_eq!(other.len(), 0);
+        assert_eq!(other.peek_back(), None);
+        assert_eq!(show(&list), "1 <-> 2 <-> 3 <-> 4");
+        assert_eq!(list.pop_back(), Some(4));
+        list.push_back(5);
+        assert_eq!(show(&list), "1 <-> 2 <-> 3 <-> 5");
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.peek_back(), None);
+        list.push_back(6);
+        assert_eq!(show(&list), "6");
+    }
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        list.push_back(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(list.to_string(), "1 <-> 2 <-> 3");
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:#?}", list), "[\n    1,\n    2,\n    3,\n]");
+    }
 }
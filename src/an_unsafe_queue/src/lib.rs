@@ -1,7 +1,9 @@
+use std::fmt::{self, Debug, Display, Formatter};
 
 pub struct List<T> {
     head: Link<T>,
     tail: *mut Node<T>,
+    len: usize,
 }
 
 type Link<T> = *mut Node<T>;
@@ -16,6 +18,7 @@ impl<T> List<T> {
         List {
             head: std::ptr::null_mut(),
             tail: std::ptr::null_mut(),
+            len: 0,
         }
     }
 
@@ -32,6 +35,7 @@ impl<T> List<T> {
                 self.head = new_tail;
             }
             self.tail = new_tail;
+            self.len += 1;
         }
     }
 
@@ -46,6 +50,7 @@ impl<T> List<T> {
                 if self.head.is_null() {
                     self.tail = std::ptr::null_mut();
                 }
+                self.len -= 1;
                 Some(node.elem)
             }
 
@@ -72,8 +77,8 @@ impl<T> List<T> {
         self.head.is_null()
     }
 
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
+    pub fn len(&self) -> usize {
+        self.len
     }
 
     pub fn iter(&self) -> Iter<'_, T> {
@@ -93,14 +98,29 @@ impl<T> List<T> {
     }
 }
 
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        while self.pop().is_some() {}
     }
 }
 
 pub struct IntoIter<T>(List<T>);
 
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
 impl<T> Iterator for IntoIter<T> {
     type Item = T;
 
@@ -143,6 +163,28 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     }
 }
 
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        match iter.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in iter {
+                    write!(f, " -> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::List;
@@ -190,6 +232,24 @@ mod test {
         assert!(list.is_empty());
     }
 
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+        list.pop();
+        assert_eq!(list.len(), 2);
+        list.pop();
+        list.pop();
+        list.pop();
+        assert_eq!(list.len(), 0);
+        list.push(4);
+        assert_eq!(list.len(), 1);
+    }
+
     #[test]
     fn peek() {
         let mut list = List::new();
@@ -212,9 +272,9 @@ mod test {
         assert_eq!(list.peek(), Some(&String::from("jack")));
         assert_eq!(list.peek_mut(), Some(&mut String::from("jack")));
 
-        list.peek_mut().map(|value| {
+        if let Some(value) = list.peek_mut() {
             value.push_str(" hello");
-        });
+        }
 
         assert_eq!(list.peek(), Some(&String::from("jack hello")));
         assert_eq!(list.pop(), Some(String::from("jack hello")));
@@ -224,9 +284,9 @@ mod test {
         list.push(2);
         list.push(3);
         assert_eq!(list.peek_mut(), Some(&mut 1));
-        list.peek_mut().map(|value| {
+        if let Some(value) = list.peek_mut() {
             *value = 11;
-        });
+        }
         assert_eq!(list.peek_mut(), Some(&mut 11));
     }
 
@@ -265,20 +325,20 @@ mod test {
         list.push(2);
         list.push(3);
         let mut iter = list.iter_mut();
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 4
-        });
-        iter.next().map(|elem| {
+        }
+        if let Some(elem) = iter.next() {
             *elem = 5
-        });
+        }
 
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 6
-        });
+        }
 
-        iter.next().map(|elem| {
+        if let Some(elem) = iter.next() {
             *elem = 7
-        });
+        }
 
         let mut iter = list.iter_mut();
         assert_eq!(iter.next(), Some(&mut 4));
@@ -302,7 +362,9 @@ mod test {
 
         assert!(list.peek() == Some(&3));
         list.push(6);
-        list.peek_mut().map(|x| *x *= 10);
+        if let Some(x) = list.peek_mut() {
+            *x *= 10;
+        }
         assert!(list.peek() == Some(&30));
         assert!(list.pop() == Some(30));
 
@@ -318,10 +380,26 @@ mod test {
         assert_eq!(iter.next(), None);
 
         assert!(list.pop() == Some(400));
-        list.peek_mut().map(|x| *x *= 10);
+        if let Some(x) = list.peek_mut() {
+            *x *= 10;
+        }
         assert!(list.peek() == Some(&5000));
         list.push(7);
 
         // Drop it on the ground and let the dtor exercise itself
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø");
+        assert_eq!(format!("{:?}", list), "[]");
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.to_string(), "1 -> 2 -> 3");
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        list.pop();
+        assert_eq!(list.to_string(), "2 -> 3");
+    }
+}
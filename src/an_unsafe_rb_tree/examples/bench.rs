@@ -12,4 +12,13 @@ fn main() {
         "RB Tree insert 10000 times took {} ms.",
         elapsed_time.as_millis()
     );
+
+    let now = Instant::now();
+    let tree = RBTree::from_sorted_iter((0..10000).map(|i| (i, i)));
+    let elapsed_time = now.elapsed();
+    println!(
+        "RB Tree from_sorted_iter with {} keys took {} ms.",
+        tree.len(),
+        elapsed_time.as_millis()
+    );
 }
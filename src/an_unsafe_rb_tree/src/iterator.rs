@@ -1,20 +1,360 @@
-use std::collections::VecDeque;
+use crate::node::NodeQuery;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{Bound, VecDeque};
+use std::marker::PhantomData;
 
-//遍历迭代器，包括前序、中序、后序、层序
+// 中序迭代器，可以从两端迭代。正向沿后继前进，反向沿前驱前进
+pub struct Iter<'a, K, V> {
+    front: NodeQuery<K, V>, // 正向迭代的下一个节点
+    back: NodeQuery<K, V>,  // 反向迭代的下一个节点
+    remaining: usize,       // 剩余未输出的键值对个数，为0时两端相遇
+    marker: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<'a, K: 'a, V: 'a> Iter<'a, K, V> {
+    pub fn new(root: NodeQuery<K, V>, len: usize) -> Self {
+        Iter {
+            front: root.minimum(),
+            back: root.maximum(),
+            remaining: len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.front.clone();
+        self.front = node.successor();
+        self.remaining -= 1;
+        node.get_kv()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back.clone();
+        self.back = node.predecessor();
+        self.remaining -= 1;
+        node.get_kv()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for Iter<'a, K, V> {}
+
+// key是否不小于下边界
+fn above_lower<Q: ?Sized>(from: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match from {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Less,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Greater,
+        Bound::Unbounded => true,
+    }
+}
+
+// key是否不大于上边界
+fn below_upper<Q: ?Sized>(to: Bound<&Q>, key: &Q, cmp: fn(&Q, &Q) -> Ordering) -> bool {
+    match to {
+        Bound::Included(bound) => cmp(key, bound) != Ordering::Greater,
+        Bound::Excluded(bound) => cmp(key, bound) == Ordering::Less,
+        Bound::Unbounded => true,
+    }
+}
+
+// 范围迭代器，可以从两端迭代。创建时只从根查找一次两端的节点，之后沿后继、前驱前进，
+// len为范围内键值对的个数，由调用者根据子树大小求出
+pub struct RangePairIter<'a, K, V> {
+    front: NodeQuery<K, V>, // 正向迭代的下一个节点
+    back: NodeQuery<K, V>,  // 反向迭代的下一个节点
+    remaining: usize,       // 剩余未输出的键值对个数，为0时两端相遇
+    marker: PhantomData<(&'a K, &'a V)>,
+}
+
+// 返回范围内的第一个和最后一个节点。范围为空时返回的节点没有意义，
+// 调用者根据范围内键值对的个数判断是否结束
+fn range_ends<K, V, Q: ?Sized>(
+    root: NodeQuery<K, V>,
+    lower: Bound<&Q>,
+    upper: Bound<&Q>,
+    cmp: fn(&Q, &Q) -> Ordering,
+) -> (NodeQuery<K, V>, NodeQuery<K, V>)
+where
+    K: Borrow<Q>,
+{
+    // 第一个不小于下边界的节点
+    let mut front = root.clone();
+    let mut cur = root.clone();
+    while let Some(key) = cur.get_key() {
+        if above_lower(lower, key.borrow(), cmp) {
+            front = cur.clone();
+            cur = cur.left();
+        } else {
+            cur = cur.right();
+        }
+    }
+    // 最后一个不大于上边界的节点
+    let mut back = root.clone();
+    let mut cur = root;
+    while let Some(key) = cur.get_key() {
+        if below_upper(upper, key.borrow(), cmp) {
+            back = cur.clone();
+            cur = cur.right();
+        } else {
+            cur = cur.left();
+        }
+    }
+    (front, back)
+}
+
+impl<'a, K: 'a, V: 'a> RangePairIter<'a, K, V> {
+    pub fn new<Q: ?Sized>(
+        root: NodeQuery<K, V>,
+        lower: Bound<&Q>,
+        upper: Bound<&Q>,
+        len: usize,
+        cmp: fn(&Q, &Q) -> Ordering,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let (front, back) = range_ends(root, lower, upper, cmp);
+        RangePairIter {
+            front,
+            back,
+            remaining: len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for RangePairIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.front.clone();
+        self.front = node.successor();
+        self.remaining -= 1;
+        node.get_kv()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for RangePairIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back.clone();
+        self.back = node.predecessor();
+        self.remaining -= 1;
+        node.get_kv()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for RangePairIter<'a, K, V> {}
+
+// 遍历的顺序，中序遍历由Iter完成
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Order {
+    Pre,
+    Post,
+    Level,
+}
+
+//遍历迭代器，包括前序、后序、层序。迭代器只保存待访问的节点，按需产生键值对
 pub struct TraverseIter<'a, K, V> {
-    data: VecDeque<(&'a K, &'a V)>,
+    order: Order,
+    // 前序、后序遍历时作为栈使用，层序遍历时作为队列使用
+    // bool表示节点的孩子是否已经展开，只在后序遍历时使用
+    nodes: VecDeque<(NodeQuery<K, V>, bool)>,
+    remaining: usize, // 剩余未输出的键值对个数
+    marker: PhantomData<(&'a K, &'a V)>,
 }
 
-impl<'a, K, V> TraverseIter<'a, K, V> {
-    pub fn new(queue: VecDeque<(&'a K, &'a V)>) -> Self {
-        TraverseIter { data: queue }
+impl<'a, K: 'a, V: 'a> TraverseIter<'a, K, V> {
+    pub fn new(root: NodeQuery<K, V>, order: Order, len: usize) -> Self {
+        let mut iter = TraverseIter {
+            order,
+            nodes: VecDeque::new(),
+            remaining: len,
+            marker: PhantomData,
+        };
+        iter.push(root);
+        iter
+    }
+
+    // 非空节点加入待访问序列
+    fn push(&mut self, node: NodeQuery<K, V>) {
+        if !node.is_nil() {
+            self.nodes.push_back((node, false));
+        }
+    }
+
+    fn next_node(&mut self) -> Option<NodeQuery<K, V>> {
+        match self.order {
+            Order::Pre => {
+                let (node, _) = self.nodes.pop_back()?;
+                self.push(node.right());
+                self.push(node.left());
+                Some(node)
+            }
+            Order::Post => loop {
+                let (node, expanded) = self.nodes.pop_back()?;
+                if expanded || (node.left().is_nil() && node.right().is_nil()) {
+                    return Some(node);
+                }
+                self.nodes.push_back((node.clone(), true));
+                self.push(node.right());
+                self.push(node.left());
+            },
+            Order::Level => {
+                let (node, _) = self.nodes.pop_front()?;
+                self.push(node.left());
+                self.push(node.right());
+                Some(node)
+            }
+        }
     }
 }
 
-impl<'a, K: PartialOrd + Clone, V> Iterator for TraverseIter<'a, K, V> {
+impl<'a, K: 'a, V: 'a> Iterator for TraverseIter<'a, K, V> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.data.pop_front()
+        let node = self.next_node()?;
+        self.remaining -= 1;
+        node.get_kv()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for TraverseIter<'a, K, V> {}
+
+// 中序可变迭代器，可以从两端迭代，只能修改值，不能修改键
+pub struct IterMut<'a, K, V> {
+    front: NodeQuery<K, V>, // 正向迭代的下一个节点
+    back: NodeQuery<K, V>,  // 反向迭代的下一个节点
+    remaining: usize,       // 剩余未输出的键值对个数，为0时两端相遇
+    marker: PhantomData<(&'a K, &'a mut V)>,
+}
+
+impl<'a, K: 'a, V: 'a> IterMut<'a, K, V> {
+    pub fn new(root: NodeQuery<K, V>, len: usize) -> Self {
+        IterMut {
+            front: root.minimum(),
+            back: root.maximum(),
+            remaining: len,
+            marker: PhantomData,
+        }
+    }
+
+    // 只迭代键在范围内的节点，len为范围内键值对的个数，由调用者根据子树大小求出
+    pub fn with_range<Q: ?Sized>(
+        root: NodeQuery<K, V>,
+        lower: Bound<&Q>,
+        upper: Bound<&Q>,
+        len: usize,
+        cmp: fn(&Q, &Q) -> Ordering,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let (front, back) = range_ends(root, lower, upper, cmp);
+        IterMut {
+            front,
+            back,
+            remaining: len,
+            marker: PhantomData,
+        }
+    }
+
+    // 每个节点只会被访问一次，返回的可变借用互不重叠；只借用字段，不借用整个节点
+    fn pair_mut(node: &NodeQuery<K, V>) -> Option<(&'a K, &'a mut V)> {
+        let node = node.inner()?.as_ptr();
+        unsafe { Some((&(*node).key, &mut (*node).value)) }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.front.clone();
+        self.front = node.successor();
+        self.remaining -= 1;
+        Self::pair_mut(&node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back.clone();
+        self.back = node.predecessor();
+        self.remaining -= 1;
+        Self::pair_mut(&node)
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for IterMut<'a, K, V> {}
+
+// 按键的顺序返回值的可变借用
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> ValuesMut<'a, K, V> {
+    pub fn new(inner: IterMut<'a, K, V>) -> Self {
+        ValuesMut { inner }
     }
 }
+
+impl<'a, K: 'a, V: 'a> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for ValuesMut<'a, K, V> {}
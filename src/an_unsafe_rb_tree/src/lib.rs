@@ -1,5 +1,7 @@
+mod entry;
 mod iterator;
 mod node;
 mod rbtree;
 
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
 pub use rbtree::RBTree;
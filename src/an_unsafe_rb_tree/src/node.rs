@@ -1,6 +1,7 @@
+use ordered_map::{dot_escape, draw_tree, Compare, InvariantViolation};
+use std::borrow::Borrow;
 use std::cmp::{max, Ordering};
-use std::collections::VecDeque;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::ptr::NonNull;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -16,82 +17,43 @@ pub struct Node<K, V> {
     pub right: Option<NonNull<Node<K, V>>>,
     pub parent: Option<NonNull<Node<K, V>>>,
     pub color: Color,
+    pub size: usize, // 以该节点为根的子树中的节点个数
 }
 
 impl<K, V> Node<K, V> {
-    fn new_node(key: K, value: V, color: Color, nil: NonNull<Node<K, V>>) -> Self {
+    fn new_node(key: K, value: V, color: Color) -> Self {
         Node {
             key,
             value,
-            left: Some(nil), //使用哑节点Nil代替None
-            right: Some(nil),
-            parent: Some(nil),
+            left: None, //叶节点NIL用None表示
+            right: None,
+            parent: None,
             color,
+            size: 1,
         }
     }
 
-    pub fn new(key: K, value: V, nil: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
-        let box_node = Box::new(Self::new_node(key, value, Color::Red, nil));
+    pub fn new(key: K, value: V) -> NonNull<Node<K, V>> {
+        let box_node = Box::new(Self::new_node(key, value, Color::Red));
         NonNull::from(Box::leak(box_node))
     }
 }
 
-/// 辅助结构体，封装了节点指针和哑节点指针，方便直接对Option<NonNull<Node<K, V>>>操作
+/// 辅助结构体，封装了节点指针，方便直接对Option<NonNull<Node<K, V>>>操作。
+/// None表示叶节点NIL，颜色为黑色，子树大小为0，读写None的字段都被忽略
 pub struct NodeQuery<K, V> {
     node: Option<NonNull<Node<K, V>>>,
-    nil: NonNull<Node<K, V>>,
 }
 
 impl<K, V> Clone for NodeQuery<K, V> {
     fn clone(&self) -> Self {
-        NodeQuery {
-            node: self.node,
-            nil: self.nil,
-        }
-    }
-}
-
-impl<K: PartialOrd + Clone, V> PartialEq for NodeQuery<K, V> {
-    fn eq(&self, other: &Self) -> bool {
-        if self.is_nil() || other.is_nil() {
-            return false;
-        }
-        self.get_key().unwrap() == other.get_key().unwrap()
-    }
-}
-
-impl<K: PartialOrd + Clone, V> PartialOrd for NodeQuery<K, V> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.is_nil() || other.is_nil() {
-            return None;
-        }
-        self.get_key()
-            .unwrap()
-            .partial_cmp(other.get_key().unwrap())
-    }
-}
-
-impl<K: PartialOrd + Clone, V> PartialEq<K> for NodeQuery<K, V> {
-    fn eq(&self, other: &K) -> bool {
-        if self.is_nil() {
-            return false;
-        }
-        self.get_key().unwrap() == other
+        NodeQuery { node: self.node }
     }
 }
 
-impl<K: PartialOrd + Clone, V> PartialOrd<K> for NodeQuery<K, V> {
-    fn partial_cmp(&self, other: &K) -> Option<Ordering> {
-        if self.is_nil() {
-            return None;
-        }
-        self.get_key().unwrap().partial_cmp(other)
-    }
-}
-
-impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
-    pub fn new(node: Option<NonNull<Node<K, V>>>, nil: NonNull<Node<K, V>>) -> Self {
-        NodeQuery { node, nil }
+impl<'a, K: 'a, V: 'a> NodeQuery<K, V> {
+    pub fn new(node: Option<NonNull<Node<K, V>>>) -> Self {
+        NodeQuery { node }
     }
 
     /// 返回节点内部的指针
@@ -101,25 +63,24 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
 
     /// 返回节点的键
     pub fn get_key(&self) -> Option<&'a K> {
-        if self.is_nil() {
-            return None;
-        }
         self.node.map(|node| unsafe { &node.as_ref().key })
     }
 
+    /// 按比较器C比较节点的键与key，叶节点NIL返回None
+    pub fn compare_key<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Option<Ordering>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key().map(|cur_key| C::compare(cur_key.borrow(), key))
+    }
+
     /// 返回节点的值
     pub fn get_value(&self) -> Option<&'a V> {
-        if self.is_nil() {
-            return None;
-        }
         self.node.map(|node| unsafe { &node.as_ref().value })
     }
 
     /// 返回节点的键值对
     pub fn get_kv(&self) -> Option<(&'a K, &'a V)> {
-        if self.is_nil() {
-            return None;
-        }
         self.node
             .map(|node| unsafe { (&node.as_ref().key, &node.as_ref().value) })
     }
@@ -127,13 +88,13 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
     /// 返回节点的左子树
     pub fn left(&self) -> Self {
         let left = self.inner().and_then(|node| unsafe { node.as_ref().left });
-        Self::new(left, self.nil)
+        Self::new(left)
     }
 
     /// 返回节点的右子树
     pub fn right(&self) -> Self {
         let right = self.inner().and_then(|node| unsafe { node.as_ref().right });
-        Self::new(right, self.nil)
+        Self::new(right)
     }
 
     /// 返回节点的双亲
@@ -141,7 +102,7 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
         let parent = self
             .inner()
             .and_then(|node| unsafe { node.as_ref().parent });
-        Self::new(parent, self.nil)
+        Self::new(parent)
     }
 
     /// 返回节点的叔叔
@@ -221,24 +182,103 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
         }
     }
 
-    /// 返回节点的颜色
-    pub fn color(&self) -> Option<Color> {
-        self.inner().map(|node| unsafe { node.as_ref().color })
+    /// 返回以节点为根的子树中的节点个数，叶节点NIL返回0
+    pub fn size(&self) -> usize {
+        self.inner().map_or(0, |node| unsafe { node.as_ref().size })
     }
 
-    /// 判断节点是否为Some
-    pub fn is_some(&self) -> bool {
-        self.inner().is_some()
+    /// 设置以节点为根的子树中的节点个数，叶节点NIL的个数始终为0
+    pub fn set_size(&mut self, size: usize) {
+        if let Some(mut node) = self.inner() {
+            unsafe { node.as_mut().size = size }
+        }
     }
 
-    /// 判断节点是否为None
-    pub fn is_none(&self) -> bool {
-        self.inner().is_none()
+    /// 根据左右子树重新计算节点的子树节点个数
+    pub fn update_size(&mut self) {
+        let size = self.left().size() + self.right().size() + 1;
+        self.set_size(size);
+    }
+
+    /// 返回以节点为根的树中第k小(从0开始)的节点，k超出范围时返回叶节点NIL
+    pub fn select(&self, mut k: usize) -> Self {
+        let mut cur = self.clone();
+        while !cur.is_nil() {
+            let left_size = cur.left().size();
+            if k < left_size {
+                cur = cur.left();
+            } else if k == left_size {
+                break;
+            } else {
+                k -= left_size + 1;
+                cur = cur.right();
+            }
+        }
+        cur
+    }
+
+    /// 返回以节点为根的树中小于key(inclusive为true时是不大于key)的键的个数
+    pub fn count_below<Q: ?Sized, C: Compare<Q>>(&self, key: &Q, inclusive: bool) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        let mut count = 0;
+        let mut cur = self.clone();
+        while let Some(order) = cur.compare_key::<Q, C>(key) {
+            if order == Ordering::Less || (inclusive && order == Ordering::Equal) {
+                count += cur.left().size() + 1;
+                cur = cur.right();
+            } else {
+                cur = cur.left();
+            }
+        }
+        count
+    }
+
+    /// 返回以节点为根的树中第一个大于key的节点，key可以不存在树中，不存在时返回叶节点NIL
+    pub fn successor_of<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut res = Self::new(None);
+        let mut cur = self.clone();
+        while let Some(order) = cur.compare_key::<Q, C>(key) {
+            if order == Ordering::Greater {
+                res = cur.clone();
+                cur = cur.left();
+            } else {
+                cur = cur.right();
+            }
+        }
+        res
+    }
+
+    /// 返回以节点为根的树中第一个小于key的节点，key可以不存在树中，不存在时返回叶节点NIL
+    pub fn predecessor_of<Q: ?Sized, C: Compare<Q>>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut res = Self::new(None);
+        let mut cur = self.clone();
+        while let Some(order) = cur.compare_key::<Q, C>(key) {
+            if order == Ordering::Less {
+                res = cur.clone();
+                cur = cur.right();
+            } else {
+                cur = cur.left();
+            }
+        }
+        res
+    }
+
+    /// 返回节点的颜色，叶节点NIL返回None
+    pub fn color(&self) -> Option<Color> {
+        self.inner().map(|node| unsafe { node.as_ref().color })
     }
 
-    /// 判断节点是否等于哑节点
+    /// 判断节点是否为叶节点NIL
     pub fn is_nil(&self) -> bool {
-        self.is_none() || self.inner() == Some(self.nil)
+        self.inner().is_none()
     }
 
     /// 判断节点是否为红色
@@ -246,89 +286,105 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
         self.color() == Some(Color::Red)
     }
 
-    /// 判断节点是否为黑色
+    /// 判断节点是否为黑色，叶节点NIL是黑色
     pub fn is_black(&self) -> bool {
-        self.color() == Some(Color::Black)
+        !self.is_red()
     }
 
     /// 判断节点是否为父亲的左孩子
     pub fn is_left_child(&self) -> bool {
-        self.is_some() && self.parent().left().inner() == self.inner()
+        !self.is_nil() && self.parent().left().inner() == self.inner()
     }
 
     /// 判断节点是否为父亲的右孩子
     pub fn is_right_child(&self) -> bool {
-        self.is_some() && self.parent().right().inner() == self.inner()
+        !self.is_nil() && self.parent().right().inner() == self.inner()
     }
 
     /// 返回以节点为根的树中的最小节点
     pub fn minimum(&self) -> Self {
-        if self.left().is_nil() {
-            return Self::new(self.inner(), self.nil);
-        }
-        let mut cur = self.left();
+        let mut cur = self.clone();
         while !cur.left().is_nil() {
             cur = cur.left();
         }
-        Self::new(cur.inner(), cur.nil)
+        cur
     }
 
     /// 返回以节点为根的树中的最大节点
     pub fn maximum(&self) -> Self {
-        if self.right().is_nil() {
-            return Self::new(self.inner(), self.nil);
-        }
-        let mut cur = self.right();
+        let mut cur = self.clone();
         while !cur.right().is_nil() {
             cur = cur.right();
         }
-        Self::new(cur.inner(), cur.nil)
+        cur
     }
 
-    /// 前序遍历
-    pub fn pre_order(&self, buf: &mut Vec<K>) {
-        if !self.is_nil() {
-            buf.push(self.get_key().unwrap().clone());
-            self.left().pre_order(buf);
-            self.right().pre_order(buf);
+    /// 返回中序遍历中的下一个节点，不存在时返回叶节点NIL
+    pub fn successor(&self) -> Self {
+        if !self.right().is_nil() {
+            return self.right().minimum();
         }
-    }
-
-    /// 中序遍历
-    pub fn in_order(&self, buf: &mut Vec<K>) {
-        if !self.is_nil() {
-            self.left().in_order(buf);
-            buf.push(self.get_key().unwrap().clone());
-            self.right().in_order(buf);
+        let mut x = self.clone();
+        let mut y = x.parent();
+        while !y.is_nil() && x.is_right_child() {
+            x = y.clone();
+            y = y.parent();
         }
+        y
     }
 
-    /// 后序遍历
-    pub fn post_order(&self, buf: &mut Vec<K>) {
-        if !self.is_nil() {
-            self.left().post_order(buf);
-            self.right().post_order(buf);
-            buf.push(self.get_key().unwrap().clone());
+    /// 返回中序遍历中的上一个节点，不存在时返回叶节点NIL
+    pub fn predecessor(&self) -> Self {
+        if !self.left().is_nil() {
+            return self.left().maximum();
         }
-    }
-
-    /// 层序遍历
-    pub fn level_order(&self, buf: &mut Vec<K>) {
-        let mut queue = VecDeque::new();
-        if !self.is_nil() {
-            queue.push_back(self.clone());
+        let mut x = self.clone();
+        let mut y = x.parent();
+        while !y.is_nil() && x.is_left_child() {
+            x = y.clone();
+            y = y.parent();
         }
-        while !queue.is_empty() {
-            if let Some(node) = queue.pop_front() {
-                buf.push(node.get_key().unwrap().clone());
-                if !node.left().is_nil() {
-                    queue.push_back(node.left());
-                }
-                if !node.right().is_nil() {
-                    queue.push_back(node.right());
-                }
+        y
+    }
+
+    /// 检查以节点为根的子树：键有序、红色节点的孩子都是黑色、每条路径上的黑色节点个数相同，
+    /// 以及双亲指针和子树节点个数正确。满足时返回子树的黑高，否则返回第一个被发现的问题
+    pub fn check_rb<C: Compare<K>>(&self) -> Result<usize, InvariantViolation> {
+        let key = match self.get_key() {
+            None => return Ok(0),
+            Some(key) => key,
+        };
+        let (left, right) = (self.left(), self.right());
+        for child in [&left, &right].iter() {
+            if !child.is_nil() && child.parent().inner() != self.inner() {
+                return Err(InvariantViolation::ParentLink);
             }
         }
+        if !left.is_nil() && left.maximum().compare_key::<K, C>(key) != Some(Ordering::Less) {
+            return Err(InvariantViolation::KeyOrder);
+        }
+        if !right.is_nil() && right.minimum().compare_key::<K, C>(key) != Some(Ordering::Greater) {
+            return Err(InvariantViolation::KeyOrder);
+        }
+        if self.is_red() && (left.is_red() || right.is_red()) {
+            return Err(InvariantViolation::RedRed);
+        }
+        let size = left.size() + right.size() + 1;
+        if self.size() != size {
+            return Err(InvariantViolation::StaleSize {
+                cached: self.size(),
+                actual: size,
+            });
+        }
+        let left_height = left.check_rb::<C>()?;
+        let right_height = right.check_rb::<C>()?;
+        if left_height != right_height {
+            return Err(InvariantViolation::BlackHeight {
+                left: left_height,
+                right: right_height,
+            });
+        }
+        Ok(left_height + self.is_black() as usize)
     }
 
     /// 返回以该节点为根的树高
@@ -342,27 +398,70 @@ impl<'a, K: Clone + PartialOrd + 'a, V: 'a> NodeQuery<K, V> {
     }
 }
 
-impl<K: Clone + PartialOrd + ToString, V: ToString> ToString for NodeQuery<K, V> {
-    fn to_string(&self) -> String {
+impl<K: Display, V: Display> Display for NodeQuery<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if self.is_nil() {
-            "Ø".to_string()
+            write!(f, "Ø")
         } else {
-            format!(
+            write!(
+                f,
                 "[K: {}, V: {}, C: {:?} L: {}, R: {}]",
-                self.get_key().unwrap().to_string(),
-                self.get_value().unwrap().to_string(),
+                self.get_key().unwrap(),
+                self.get_value().unwrap(),
                 self.color().unwrap(),
-                self.left().to_string(),
-                self.right().to_string(),
+                self.left(),
+                self.right(),
             )
         }
     }
 }
 
+impl<K, V> NodeQuery<K, V> {
+    /// 把以该节点为根的树画成多行，label写出每个节点的键值对，节点的颜色写在键值对之后。
+    /// 叶节点NIL画成Ø
+    pub fn draw(&self, f: &mut Formatter<'_>, label: &dyn Fn(&K, &V, &mut Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        if self.is_nil() {
+            return write!(f, "Ø");
+        }
+        let write_node = |node: &Self, f: &mut Formatter<'_>| {
+            label(node.get_key().unwrap(), node.get_value().unwrap(), f)?;
+            write!(f, " ({:?})", node.color().unwrap())
+        };
+        draw_tree(f, self.clone(), &write_node, &|node: &Self| {
+            let (left, right) = (node.left(), node.right());
+            if left.is_nil() && right.is_nil() {
+                return Vec::new();
+            }
+            let child = |child: Self| if child.is_nil() { None } else { Some(child) };
+            vec![child(left), child(right)]
+        })
+    }
+}
+
+impl<K: Display, V: Display> NodeQuery<K, V> {
+    /// 按前序把以该节点为根的子树写成DOT格式，节点按颜色填充，id为下一个可用的节点编号。
+    /// 只有一个孩子时用一个点表示叶节点NIL，以便区分左右
+    pub fn write_dot(&self, out: &mut String, id: &mut usize) {
+        let me = *id;
+        *id += 1;
+        let label = dot_escape(&format!("{}: {}", self.get_key().unwrap(), self.get_value().unwrap()));
+        let color = if self.is_red() { "red" } else { "black" };
+        out.push_str(&format!("    {} [label=\"{}\", fillcolor={}];\n", me, label, color));
+        let (left, right) = (self.left(), self.right());
+        for child in [&left, &right].iter() {
+            if !child.is_nil() {
+                out.push_str(&format!("    {} -> {};\n", me, *id));
+                child.write_dot(out, id);
+            } else if !left.is_nil() || !right.is_nil() {
+                out.push_str(&format!("    nil{0} [shape=point];\n    {0} -> nil{0};\n", me));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::node::{Color, Node, NodeQuery};
-    use std::ptr::NonNull;
 
     #[test]
     fn basic() {
@@ -373,23 +472,14 @@ mod tests {
                        \
                         4(r)
         */
-        let nil_node = Box::new(Node {
-            key: 0,
-            value: 0,
-            left: None,
-            right: None,
-            parent: None,
-            color: Color::Black,
-        });
-        let nil = NonNull::from(Box::leak(nil_node));
-        let root_node = Node::new(2, 2, nil);
-        let left_node = Node::new(1, 1, nil);
-        let mut right_node = Node::new(3, 3, nil);
-        let right_right_node = Node::new(4, 4, nil);
-        let mut root_query = NodeQuery::new(Some(root_node), nil);
-        let mut left_query = NodeQuery::new(Some(left_node), nil);
-        let mut right_query = NodeQuery::new(Some(right_node), nil);
-        let mut rr_query = NodeQuery::new(Some(right_right_node), nil);
+        let root_node = Node::new(2, 2);
+        let left_node = Node::new(1, 1);
+        let mut right_node = Node::new(3, 3);
+        let right_right_node = Node::new(4, 4);
+        let mut root_query = NodeQuery::new(Some(root_node));
+        let mut left_query = NodeQuery::new(Some(left_node));
+        let mut right_query = NodeQuery::new(Some(right_node));
+        let mut rr_query = NodeQuery::new(Some(right_right_node));
         root_query.set_left(Some(left_node));
         root_query.set_right(Some(right_node));
         rr_query.set_parent(Some(right_node));
@@ -401,7 +491,7 @@ mod tests {
         right_query.set_color(Color::Black);
         // 设置完成
         assert!(!root_query.is_red());
-        assert!(root_query.is_some());
+        assert!(!root_query.is_nil());
         assert!(left_query.is_left_child());
         assert!(right_query.is_right_child());
         assert!(!root_query.is_left_child());
@@ -410,12 +500,13 @@ mod tests {
 
         assert_eq!(root_query.left().node, Some(left_node));
         assert_eq!(root_query.right().node, Some(right_node));
-        assert_eq!(left_query.left().node, Some(nil));
-        assert_eq!(left_query.right().node, Some(nil));
+        assert_eq!(left_query.left().node, None);
+        assert_eq!(left_query.right().node, None);
 
         assert_eq!(root_query.left().color(), Some(Color::Black));
         assert_eq!(root_query.right().color(), Some(Color::Black));
-        assert_eq!(left_query.left().color(), Some(Color::Black));
+        assert_eq!(left_query.left().color(), None);
+        assert!(left_query.left().is_black());
 
         assert_eq!(root_query.get_key(), Some(&2));
         assert_eq!(root_query.left().get_key(), Some(&1));
@@ -423,7 +514,7 @@ mod tests {
         assert_eq!(left_query.left().get_key(), None);
 
         assert_eq!(rr_query.grandparent().node, Some(root_node));
-        assert_eq!(left_query.grandparent().node, Some(nil));
+        assert_eq!(left_query.grandparent().node, None);
         assert_eq!(root_query.grandparent().node, None);
 
         assert_eq!(rr_query.uncle().node, Some(left_node));
@@ -434,11 +525,11 @@ mod tests {
         assert_eq!(root_query.brother().node, None);
         assert_eq!(left_query.brother().node, right_query.node);
         assert_eq!(right_query.brother().node, left_query.node);
-        assert_eq!(rr_query.brother().node, Some(nil));
+        assert_eq!(rr_query.brother().node, None);
 
         assert_eq!(left_query.parent().node, Some(root_node));
         assert_eq!(right_query.parent().node, Some(root_node));
-        assert_eq!(root_query.parent().node, Some(nil));
+        assert_eq!(root_query.parent().node, None);
 
         rr_query.set_entry(5, 5);
         assert_eq!(right_query.right().get_key(), Some(&5));
@@ -456,41 +547,10 @@ mod tests {
         assert_eq!(left_query.height(), 1);
         assert_eq!(right_query.height(), 2);
         assert_eq!(rr_query.height(), 1);
-    }
 
-    #[test]
-    fn partion_cmp() {
-        let nil_node = Box::new(Node {
-            key: 0,
-            value: 0,
-            left: None,
-            right: None,
-            parent: None,
-            color: Color::Black,
-        });
-        let nil = NonNull::from(Box::leak(nil_node));
-        let big_node = Node::new(20, 2, nil);
-        let middle_node = Node::new(10, 2, nil);
-        let small_node = Node::new(1, 1, nil);
-        let eq_small_node = Node::new(1, 1, nil);
-        let big_query = NodeQuery::new(Some(big_node), nil);
-        let middle_query = NodeQuery::new(Some(middle_node), nil);
-        let eq_small_query = NodeQuery::new(Some(eq_small_node), nil);
-        let small_query = NodeQuery::new(Some(small_node), nil);
-        let none_query = NodeQuery::new(None, nil);
-        assert!(big_query > middle_query);
-        assert!(middle_query > small_query);
-        assert!(small_query < middle_query);
-        assert!(small_query == eq_small_query);
-        assert_eq!(none_query == small_query, false);
-        assert_eq!(none_query > small_query, false);
-        assert_eq!(none_query < small_query, false);
-
-        assert!(big_query > 10);
-        assert!(middle_query == 10);
-        assert!(small_query < 10);
-        assert_eq!(none_query == 10, false);
-        assert_eq!(none_query > 10, false);
-        assert_eq!(none_query < 10, false);
+        // 手动构建的节点不属于任何树，需要逐个释放
+        for node in [root_node, left_node, right_node, right_right_node].iter() {
+            unsafe { drop(Box::from_raw(node.as_ptr())) };
+        }
     }
 }
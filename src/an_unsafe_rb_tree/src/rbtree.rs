@@ -1,9 +1,17 @@
-use crate::iterator::TraverseIter;
+use crate::entry::{Entry, OccupiedEntry, VacantEntry};
+use crate::iterator::{Iter, IterMut, Order, RangePairIter, TraverseIter, ValuesMut};
 use crate::node::{Color, Node, NodeQuery};
-use std::collections::VecDeque;
+use ordered_map::{sort_unique_pairs, Compare, Difference, Intersection, InvariantViolation, Natural, OrderedMap, SymmetricDifference, Union};
+use std::borrow::Borrow;
+use std::cmp::{max, Ordering};
+use std::fmt::{self, Debug, Display, Formatter};
 use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds, Sub};
 use std::ptr::NonNull;
 
+// 一棵独立红黑树的根和黑高，用于分割与连接
+type Subtree<K, V> = (Option<NonNull<Node<K, V>>>, usize);
+
 /// 红黑树是满足以下性质的自平衡二叉搜索树:
 ///
 /// 1.每个节点要么是红色，要么是黑色
@@ -16,36 +24,174 @@ use std::ptr::NonNull;
 ///
 /// 5.对每个节点，从该节点到其所有后代叶节点的简单路径上，均包含相同数目的黑色节点
 ///
-pub struct RBTree<K, V> {
+/// 键的顺序由比较器C决定，默认按`Ord`比较
+pub struct RBTree<K, V, C = Natural> {
     root: Option<NonNull<Node<K, V>>>,
-    nil: NonNull<Node<K, V>>,
-    marker: PhantomData<Box<Node<K, V>>>,
+    len: usize,
+    marker: PhantomData<(Box<Node<K, V>>, C)>,
 }
 
-impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
-    /// 构建一棵空的红黑树
+impl<K: Ord, V> RBTree<K, V> {
+    /// 构建一棵空的红黑树，按`Ord`比较键
     /// # Examples
     /// ```
     /// use an_unsafe_rb_tree::RBTree;
     /// let mut tree: RBTree<i32, i32> = RBTree::new();
     /// ```
     pub fn new() -> Self {
-        // 构建哨兵节点nil，为了方便处理红黑树中的边界条件。color属性为Black，其他属性可以任意
-        let nil_node = Box::new(Node {
-            key: K::default(),
-            value: V::default(),
-            left: None,
-            right: None,
-            parent: None,
-            color: Color::Black,
-        });
+        Self::with_comparator()
+    }
+
+    /// 由按键有序的键值对在O(n)时间内构建红黑树，避免逐个插入时反复修复。
+    /// 树的形状完全平衡，只有最后一层不满时该层的节点是红色，其余节点都是黑色。
+    /// 输入无序时先按键排序，时间复杂度变为O(n log n)，重复的键保留最后一个值
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let tree = RBTree::from_sorted_iter((0..100).map(|key| (key, key * 10)));
+    /// assert_eq!(tree.len(), 100);
+    /// assert_eq!(tree.get(&42), Some(&420));
+    /// assert!(tree.is_rb_tree());
+    /// let tree = RBTree::from_sorted_iter(vec![(3, 'c'), (1, 'a'), (3, 'd')]);
+    /// let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
+    /// assert_eq!(res, vec![(&1, &'a'), (&3, &'d')]);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        Self::from_sorted_iter_with_comparator(pairs)
+    }
+}
+
+impl<K, V, C: Compare<K>> RBTree<K, V, C> {
+    /// 构建一棵空的红黑树，按比较器C比较键
+    /// # Examples
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// use ordered_map::Reverse;
+    /// let mut tree: RBTree<i32, char, Reverse> = RBTree::with_comparator();
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// assert_eq!(tree.min_pair(), Some((&2, &'b')));
+    /// ```
+    pub fn with_comparator() -> Self {
         RBTree {
             root: None,
-            nil: NonNull::from(Box::leak(nil_node)),
+            len: 0,
             marker: Default::default(),
         }
     }
 
+    /// 与`from_sorted_iter`相同，按比较器C的顺序构建
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// use ordered_map::Reverse;
+    /// let tree: RBTree<i32, char, Reverse> = RBTree::from_sorted_iter_with_comparator(vec![(1, 'a'), (3, 'c')]);
+    /// assert_eq!(tree.min_pair(), Some((&3, &'c')));
+    /// assert!(tree.is_rb_tree());
+    /// ```
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        let pairs = sort_unique_pairs(pairs, C::compare);
+        let len = pairs.len();
+        let mut tree = Self::with_comparator();
+        if len > 0 {
+            // 前red_depth层都是满的，深度为red_depth的节点位于不满的最后一层
+            let red_depth = (usize::BITS - 1 - (len + 1).leading_zeros()) as usize;
+            tree.root = tree.build_balanced(&mut pairs.into_iter(), len, 0, red_depth);
+            tree.len = len;
+        }
+        tree.check();
+        tree
+    }
+
+    // 由按键严格递增的n个键值对构建平衡的子树，左右子树的节点个数最多相差1，
+    // 深度为red_depth的节点染成红色，其余染成黑色，返回子树的根，n为0时返回None
+    fn build_balanced<I: Iterator<Item = (K, V)>>(
+        &self,
+        pairs: &mut I,
+        n: usize,
+        depth: usize,
+        red_depth: usize,
+    ) -> Option<NonNull<Node<K, V>>> {
+        if n == 0 {
+            return None;
+        }
+        let left = self.build_balanced(pairs, (n - 1) / 2, depth + 1, red_depth);
+        let (key, value) = pairs.next().expect("RBTree broken");
+        let node = Node::new(key, value);
+        let right = self.build_balanced(pairs, n - 1 - (n - 1) / 2, depth + 1, red_depth);
+        let mut z = NodeQuery::new(Some(node));
+        z.set_left(left);
+        z.set_right(right);
+        z.set_color(if depth == red_depth { Color::Red } else { Color::Black });
+        z.set_size(n);
+        Some(node)
+    }
+
+    /// 检查红黑树的所有性质：根节点是黑色、键按比较器C严格递增、红色节点的孩子都是黑色、
+    /// 每条路径上的黑色节点个数相同，以及双亲指针、子树节点个数和键值对个数正确。
+    /// 不满足时返回第一个被发现的问题
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// for key in 0..100 {
+    ///     tree.insert(key, key);
+    /// }
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let root = NodeQuery::new(self.root);
+        if root.is_red() {
+            return Err(InvariantViolation::RedRoot);
+        }
+        if !root.parent().is_nil() {
+            return Err(InvariantViolation::ParentLink);
+        }
+        root.check_rb::<C>()?;
+        if root.size() != self.len {
+            return Err(InvariantViolation::LenMismatch {
+                cached: self.len,
+                actual: root.size(),
+            });
+        }
+        Ok(())
+    }
+
+    // 开启checked特性时，每次修改之后检查红黑树的性质，不满足时panic
+    fn check(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("RBTree broken: {}", violation);
+        }
+    }
+}
+
+impl<K: Display, V: Display, C> RBTree<K, V, C> {
+    /// 生成DOT格式的图，节点按颜色填充为红色或黑色，可以保存为文件后用Graphviz绘制：`dot -Tpng tree.dot -o tree.png`
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for key in 1..=3 {
+    ///     tree.insert(key, key);
+    /// }
+    /// let dot = tree.to_dot();
+    /// assert!(dot.starts_with("digraph RBTree {"));
+    /// assert!(dot.contains("0 [label=\"2: 2\", fillcolor=black];\n    0 -> 1;\n    1 [label=\"1: 1\", fillcolor=red];\n"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph RBTree {\n    ordering=out;\n    node [shape=circle, style=filled, fontcolor=white];\n");
+        let root = NodeQuery::new(self.root);
+        if !root.is_nil() {
+            root.write_dot(&mut out, &mut 0);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> RBTree<K, V, C> {
     /// 向红黑树中插入键值对，如果键已经存在，则替换旧值为新值
     /// # Example
     /// ```
@@ -57,32 +203,50 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert_eq!(tree.get(&2), Some(&'b'));
     /// ```
     pub fn insert(&mut self, key: K, value: V) {
-        let mut y_node = Some(self.nil);
-        let mut x = NodeQuery::new(self.root, self.nil);
+        let mut y_node = None;
+        let mut x = NodeQuery::new(self.root);
 
-        while !x.is_nil() {
+        while let Some(order) = x.compare_key::<K, C>(&key) {
             y_node = x.inner();
-            if x > key {
-                x = x.left();
-            } else if x < key {
-                x = x.right();
-            } else {
-                x.set_entry(key, value);
-                return;
+            match order {
+                Ordering::Greater => x = x.left(),
+                Ordering::Less => x = x.right(),
+                Ordering::Equal => {
+                    x.set_entry(key, value);
+                    return;
+                }
             }
         }
-        let new_node = Node::new(key, value, self.nil);
-        let mut z = NodeQuery::new(Some(new_node), self.nil);
-        let mut y = NodeQuery::new(y_node, self.nil);
-        z.set_parent(y.inner());
-        if y.is_nil() {
-            self.root = z.inner();
-        } else if z.get_key() < y.get_key() {
-            y.set_left(z.inner());
-        } else {
-            y.set_right(z.inner());
+        self.insert_node(y_node, key, value);
+    }
+
+    /// 获取键对应的入口，只需查找一次即可完成“不存在则插入，存在则修改”
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for c in "abcab".chars() {
+    ///     *tree.entry(c).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(tree.get(&'a'), Some(&2));
+    /// assert_eq!(tree.get(&'c'), Some(&1));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, C> {
+        let mut y_node = None;
+        let mut x = NodeQuery::new(self.root);
+
+        while let Some(order) = x.compare_key::<K, C>(&key) {
+            y_node = x.inner();
+            match order {
+                Ordering::Greater => x = x.left(),
+                Ordering::Less => x = x.right(),
+                Ordering::Equal => {
+                    let node = x.inner().expect("RBTree broken");
+                    return Entry::Occupied(OccupiedEntry::new(self, node));
+                }
+            }
         }
-        self.insert_fixup(z.inner());
+        Entry::Vacant(VacantEntry::new(self, key, y_node))
     }
 
     /// 从红黑树中删除键值对，如果找不到键值对，则忽略
@@ -91,48 +255,18 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// use an_unsafe_rb_tree::RBTree;
     /// let mut tree = RBTree::new();
     /// tree.insert(1, 'a');
-    /// tree.delete(1);
+    /// tree.delete(&1);
     /// assert!(tree.is_empty());
-    /// tree.delete(2);
+    /// tree.delete(&2);
     /// assert!(tree.is_empty());
     /// ```
-    pub fn delete(&mut self, key: K) {
-        let delete_node = self.search(&key);
-        if delete_node.is_none() {
-            return;
-        }
-        let z = NodeQuery::new(delete_node, self.nil); //待删除节点
-        let mut y = z.clone(); // 用于替换待删除节点
-        let mut x; // 待删除节点的右子节点
-        let mut y_original_color = y.color().unwrap();
-        if z.left().is_nil() {
-            x = z.right();
-            self.transplant(z.inner(), z.right().inner());
-        } else if z.right().is_nil() {
-            x = z.left();
-            self.transplant(z.inner(), z.left().inner());
-        } else {
-            y = z.right().minimum();
-            y_original_color = y.color().unwrap();
-            x = y.right();
-            if y.parent().inner() == z.inner() {
-                x.set_parent(y.inner());
-            } else {
-                self.transplant(y.inner(), y.right().inner());
-                y.set_right(z.right().inner());
-                y.right().set_parent(y.inner());
-            }
-            self.transplant(z.inner(), y.inner());
-            y.set_left(z.left().inner());
-            y.left().set_parent(y.inner());
-            y.set_color(z.color().unwrap());
-        }
-        if y_original_color == Color::Black {
-            self.delete_fixup(x.inner());
-        }
-        // 释放删除节点的内存
-        if let Some(delete_ptr) = delete_node {
-            let _ = unsafe { Box::from_raw(delete_ptr.as_ptr()) };
+    pub fn delete<Q: ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        if let Some(node) = self.search(key) {
+            self.remove_node(node);
         }
     }
 
@@ -144,7 +278,25 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        NodeQuery::new(self.root, self.nil).is_nil()
+        NodeQuery::new(self.root).is_nil()
+    }
+
+    /// 返回红黑树中键值对的个数，时间复杂度O(1)
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1, 'a');
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'c');
+    /// assert_eq!(tree.len(), 2);
+    /// tree.delete(&3);
+    /// assert_eq!(tree.len(), 2);
+    /// tree.delete(&1);
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
     }
 
     /// 根据键查找对应的值，找不到返回None，返回值的不可变借用
@@ -155,9 +307,33 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.get(&1), Some(&'a'));
     /// ```
-    pub fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         let p = self.search(key);
-        NodeQuery::new(p, self.nil).get_value()
+        NodeQuery::new(p).get_value()
+    }
+
+    /// 根据键查找对应的值，找不到返回None，返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1, 'a');
+    /// if let Some(value) = tree.get_mut(&1) {
+    ///     *value = 'b';
+    /// }
+    /// assert_eq!(tree.get(&1), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        self.search(key)
+            .map(|mut node| unsafe { &mut node.as_mut().value })
     }
 
     /// 根据键获取相应键值对
@@ -168,9 +344,13 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.get_pair(&1), Some((&1, &'a')));
     /// ```
-    pub fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
+    pub fn get_pair<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         let p = self.search(key);
-        NodeQuery::new(p, self.nil).get_kv()
+        NodeQuery::new(p).get_kv()
     }
 
     /// 查找是否存在键值对
@@ -182,7 +362,11 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert_eq!(tree.contains(&1), true);
     /// assert_eq!(tree.contains(&2), false);
     /// ```
-    pub fn contains(&self, key: &K) -> bool {
+    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
         self.search(key).is_some()
     }
 
@@ -197,7 +381,7 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert_eq!(tree.min_pair(), Some((&1, &'a')));
     /// ```
     pub fn min_pair(&self) -> Option<(&K, &V)> {
-        NodeQuery::new(self.root, self.nil).minimum().get_kv()
+        NodeQuery::new(self.root).minimum().get_kv()
     }
 
     /// 返回红黑树中的最大键值对
@@ -211,10 +395,10 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert_eq!(tree.max_pair(), Some((&3, &'c')));
     /// ```
     pub fn max_pair(&self) -> Option<(&K, &V)> {
-        NodeQuery::new(self.root, self.nil).maximum().get_kv()
+        NodeQuery::new(self.root).maximum().get_kv()
     }
 
-    ///返回红黑树中给定节点的后继，如果给定节点不存在或后继不存在则返回None
+    ///返回第一个大于key的键值对，key可以不存在树中
     /// # Example
     ///```
     /// use an_unsafe_rb_tree::RBTree;
@@ -223,27 +407,18 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// tree.insert(2, 'b');
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.successor(&1), Some((&2, &'b')));
-    /// assert_eq!(tree.successor(&0), None);
+    /// assert_eq!(tree.successor(&0), Some((&1, &'a')));
     /// assert_eq!(tree.successor(&3), None);
     /// ```
-    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
-        let cur = self.search(key)?;
-        let mut x = NodeQuery::new(Some(cur), self.nil);
-        if !x.right().is_nil() {
-            return x.right().minimum().get_kv();
-        }
-        let mut y = x.parent();
-        while !y.is_nil() && x.is_right_child() {
-            x = y.clone();
-            y = y.parent();
-        }
-        if y.is_nil() {
-            return None;
-        }
-        y.get_kv()
+    pub fn successor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        NodeQuery::new(self.root).successor_of::<Q, C>(key).get_kv()
     }
 
-    ///返回红黑树中给定节点的前驱，如果给定节点不存在或前驱不存在则返回None
+    ///返回第一个小于key的键值对，key可以不存在树中
     /// # Example
     ///```
     /// use an_unsafe_rb_tree::RBTree;
@@ -253,23 +428,212 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// tree.insert(1, 'a');
     /// assert_eq!(tree.predecessor(&3), Some((&2, &'b')));
     /// assert_eq!(tree.predecessor(&1), None);
-    /// assert_eq!(tree.predecessor(&6), None);
+    /// assert_eq!(tree.predecessor(&6), Some((&3, &'c')));
     /// ```
-    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
-        let cur = self.search(key)?;
-        let mut x = NodeQuery::new(Some(cur), self.nil);
-        if !x.left().is_nil() {
-            return x.left().maximum().get_kv();
-        }
-        let mut y = x.parent();
-        while !y.is_nil() && x.is_left_child() {
-            x = y.clone();
-            y = y.parent();
+    pub fn predecessor<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        NodeQuery::new(self.root).predecessor_of::<Q, C>(key).get_kv()
+    }
+
+    /// 把树按key分成两棵，键不小于key的键值对移到返回的新树中，两棵树仍是红黑树，时间复杂度O(log n)
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// let other = tree.split_off(&4);
+    /// assert_eq!(tree.len(), 3);
+    /// assert_eq!(other.len(), 7);
+    /// assert_eq!(other.min_pair(), Some((&4, &40)));
+    /// assert!(tree.is_rb_tree() && other.is_rb_tree());
+    /// ```
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let tree = (self.root, self.black_height());
+        self.root = None;
+        let ((less, _), (rest, _)) = self.split(tree, key);
+        self.root = less;
+        let rest_len = NodeQuery::new(rest).size();
+        self.len -= rest_len;
+        self.check();
+        let rest = RBTree {
+            root: rest,
+            len: rest_len,
+            marker: Default::default(),
+        };
+        rest.check();
+        rest
+    }
+
+    /// 把other中的键值对全部移到当前树中，other变为空树，时间复杂度O(log n)
+    /// # Panics
+    /// other中存在不大于当前树最大键的键时panic
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// let mut other = RBTree::new();
+    /// for key in 1..=3 {
+    ///     tree.insert(key, key * 10);
+    ///     other.insert(key + 3, key * 10 + 30);
+    /// }
+    /// tree.append(&mut other);
+    /// assert_eq!(tree.len(), 6);
+    /// assert!(other.is_empty());
+    /// assert!(tree.is_rb_tree());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        if let (Some((max, _)), Some((min, _))) = (self.max_pair(), other.min_pair()) {
+            assert!(C::compare(max, min) == Ordering::Less, "keys of other must be greater than all keys of self");
         }
-        if y.is_nil() {
-            return None;
+        if other.is_empty() {
+            return;
         }
-        y.get_kv()
+        // other中的最小节点作为连接两棵树的中间节点
+        let min = NodeQuery::new(other.root).minimum().inner().expect("RBTree broken");
+        let (key, value) = other.remove_node(min);
+        let mid = Node::new(key, value);
+        let left = (self.root, self.black_height());
+        let right = (other.root, other.black_height());
+        self.root = None;
+        other.root = None;
+        let (root, _) = self.join(left, mid, right);
+        self.root = root;
+        self.len += other.len + 1;
+        other.len = 0;
+        self.check();
+    }
+
+    /// 判断是否满足红黑树的性质，同时检查双亲指针和子树节点个数，空树也是红黑树
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// assert!(tree.is_rb_tree());
+    /// for key in 0..100 {
+    ///     tree.insert(key, key);
+    /// }
+    /// assert!(tree.is_rb_tree());
+    /// ```
+    pub fn is_rb_tree(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// 返回第k小的键值对，k从0开始计数，k超出范围返回None
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(30, 'c');
+    /// tree.insert(10, 'a');
+    /// tree.insert(20, 'b');
+    /// assert_eq!(tree.select(0), Some((&10, &'a')));
+    /// assert_eq!(tree.select(2), Some((&30, &'c')));
+    /// assert_eq!(tree.select(3), None);
+    /// ```
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        NodeQuery::new(self.root).select(k).get_kv()
+    }
+
+    /// 返回树中小于key的键的个数，key可以不存在树中。key存在时就是它在中序序列中的下标
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(30, 'c');
+    /// tree.insert(10, 'a');
+    /// tree.insert(20, 'b');
+    /// assert_eq!(tree.rank(&10), 0);
+    /// assert_eq!(tree.rank(&25), 2);
+    /// assert_eq!(tree.rank(&99), 3);
+    /// ```
+    pub fn rank<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        NodeQuery::new(self.root).count_below::<Q, C>(key, false)
+    }
+
+    /// 返回键在范围内的键值对个数，不需要遍历范围内的节点
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// assert_eq!(tree.count_range(3..=5), 3);
+    /// assert_eq!(tree.count_range(3..5), 2);
+    /// assert_eq!(tree.count_range(..), 10);
+    /// assert_eq!(tree.count_range(8..3), 0);
+    /// ```
+    pub fn count_range<Q: ?Sized, R: RangeBounds<Q>>(&self, range: R) -> usize
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let root = NodeQuery::new(self.root);
+        let lower = match range.start_bound() {
+            Bound::Included(key) => root.count_below::<Q, C>(key, false),
+            Bound::Excluded(key) => root.count_below::<Q, C>(key, true),
+            Bound::Unbounded => 0,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(key) => root.count_below::<Q, C>(key, true),
+            Bound::Excluded(key) => root.count_below::<Q, C>(key, false),
+            Bound::Unbounded => self.len,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    /// 中序可变迭代器，按键的顺序返回键值对，可以从两端迭代，只能修改值
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(2, 'b');
+    /// tree.insert(1, 'a');
+    /// for (key, value) in tree.iter_mut() {
+    ///     if *key == 2 {
+    ///         *value = 'z';
+    ///     }
+    /// }
+    /// assert_eq!(tree.get(&2), Some(&'z'));
+    /// ```
+    /// 键是只读的，修改键无法通过编译
+    /// ```compile_fail
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1, 'a');
+    /// for (key, _) in tree.iter_mut() {
+    ///     *key = 2;
+    /// }
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut::new(NodeQuery::new(self.root), self.len)
+    }
+
+    /// 按键的顺序返回值的可变借用
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1, 10);
+    /// tree.insert(2, 20);
+    /// tree.values_mut().for_each(|value| *value *= 2);
+    /// assert_eq!(tree.get(&2), Some(&40));
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut::new(self.iter_mut())
     }
 
     /// 前序遍历迭代器
@@ -283,18 +647,11 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.preorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn preorder_iter(&self) -> TraverseIter<K, V> {
-        let pre_order = self.prev_order();
-        let mut queue = VecDeque::new();
-        for key in pre_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(NodeQuery::new(self.root), Order::Pre, self.len)
     }
 
-    /// 中序遍历迭代器
+    /// 中序遍历迭代器，可以从两端迭代
     /// # Example
     /// ```
     /// use an_unsafe_rb_tree::RBTree;
@@ -304,16 +661,141 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// tree.insert(1, 'a');
     /// let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    /// let res: Vec<(&i32, &char)> = tree.inorder_iter().rev().collect();
+    /// assert_eq!(res, vec![(&3, &'c'), (&2, &'b'), (&1, &'a')]);
+    /// assert_eq!(tree.inorder_iter().len(), 3);
     /// ```
-    pub fn inorder_iter(&self) -> TraverseIter<K, V> {
-        let in_order = self.in_order();
-        let mut queue = VecDeque::new();
-        for key in in_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn inorder_iter(&self) -> Iter<'_, K, V> {
+        Iter::new(NodeQuery::new(self.root), self.len)
+    }
+
+    /// 范围迭代器，按键的顺序返回范围内的键值对，可以从两端迭代，`len()`的时间复杂度为O(1)
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// let res: Vec<i32> = tree.range(3..=5).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![3, 4, 5]);
+    /// let res: Vec<i32> = tree.range(..3).rev().map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![2, 1]);
+    /// assert_eq!(tree.range(11..).next(), None);
+    /// assert_eq!(tree.range(3..8).len(), 5);
+    /// ```
+    pub fn range<Q: ?Sized, R: RangeBounds<Q>>(&self, range: R) -> RangePairIter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let bounds = (range.start_bound(), range.end_bound());
+        RangePairIter::new(
+            NodeQuery::new(self.root),
+            bounds.0,
+            bounds.1,
+            self.count_range::<Q, _>(bounds),
+            C::compare,
+        )
+    }
+
+    /// 范围可变迭代器，按键的顺序返回范围内的键值对，可以从两端迭代，只能修改值，`len()`的时间复杂度为O(1)
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// for key in 1..=10 {
+    ///     tree.insert(key, key * 10);
+    /// }
+    /// for (_, value) in tree.range_mut(3..=5) {
+    ///     *value += 1;
+    /// }
+    /// let res: Vec<i32> = tree.range(2..=6).map(|(_, v)| *v).collect();
+    /// assert_eq!(res, vec![20, 31, 41, 51, 60]);
+    /// assert_eq!(tree.range_mut(..3).rev().next(), Some((&2, &mut 20)));
+    /// assert_eq!(tree.range_mut(3..8).len(), 5);
+    /// ```
+    pub fn range_mut<Q: ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let bounds = (range.start_bound(), range.end_bound());
+        IterMut::with_range(
+            NodeQuery::new(self.root),
+            bounds.0,
+            bounds.1,
+            self.count_range::<Q, _>(bounds),
+            C::compare,
+        )
+    }
+
+    /// 并集迭代器，按键的顺序返回两棵树中的键值对，两棵树都有的键只返回当前树的键值对
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut a = RBTree::new();
+    /// let mut b = RBTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.union(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, K, V>> {
+        Union::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 交集迭代器，按键的顺序返回两棵树都有的键对应的当前树的键值对
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut a = RBTree::new();
+    /// let mut b = RBTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.intersection(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![3, 4]);
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, K, V>> {
+        Intersection::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 差集迭代器，按键的顺序返回只在当前树中的键值对
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut a = RBTree::new();
+    /// let mut b = RBTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.difference(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2]);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, K, V>> {
+        Difference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
+    }
+
+    /// 对称差迭代器，按键的顺序返回只在其中一棵树中的键值对
+    /// # Example
+    /// ```
+    /// use an_unsafe_rb_tree::RBTree;
+    /// let mut a = RBTree::new();
+    /// let mut b = RBTree::new();
+    /// for key in 1..=4 {
+    ///     a.insert(key, ());
+    ///     b.insert(key + 2, ());
+    /// }
+    /// let res: Vec<i32> = a.symmetric_difference(&b).map(|(k, _)| *k).collect();
+    /// assert_eq!(res, vec![1, 2, 5, 6]);
+    /// ```
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, K, V>> {
+        SymmetricDifference::new(self.inorder_iter(), other.inorder_iter(), |a, b| C::compare(a.0, b.0))
     }
 
     /// 后序遍历迭代器
@@ -327,15 +809,8 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.postorder_iter().collect();
     /// assert_eq!(res, vec![(&1, &'a'), (&3, &'c'), (&2, &'b')]);
     /// ```
-    pub fn postorder_iter(&self) -> TraverseIter<K, V> {
-        let post_order = self.post_order();
-        let mut queue = VecDeque::new();
-        for key in post_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(NodeQuery::new(self.root), Order::Post, self.len)
     }
 
     /// 层序遍历迭代器
@@ -349,15 +824,8 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// let res: Vec<(&i32, &char)> = tree.levelorder_iter().collect();
     /// assert_eq!(res, vec![(&2, &'b'), (&1, &'a'), (&3, &'c')]);
     /// ```
-    pub fn levelorder_iter(&self) -> TraverseIter<K, V> {
-        let level_order = self.level_order();
-        let mut queue = VecDeque::new();
-        for key in level_order {
-            if let Some(p) = self.get_pair(&key) {
-                queue.push_back(p);
-            }
-        }
-        TraverseIter::new(queue)
+    pub fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        TraverseIter::new(NodeQuery::new(self.root), Order::Level, self.len)
     }
 
     /// 测量树高
@@ -371,14 +839,205 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
     /// assert_eq!(tree.tree_height(), 2);
     /// ```
     pub fn tree_height(&self) -> usize {
-        NodeQuery::new(self.root, self.nil).height()
+        NodeQuery::new(self.root).height()
     }
 }
 
-impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
-    // 插入后修复红黑树，使之继续保持红黑树性质
-    fn insert_fixup(&mut self, node: Option<NonNull<Node<K, V>>>) {
-        let mut z = NodeQuery::new(node, self.nil);
+impl<K: Clone, V, C: Compare<K>> RBTree<K, V, C> {
+    // 将新节点挂到parent下并修复红黑树，返回新节点
+    pub(crate) fn insert_node(&mut self, parent: Option<NonNull<Node<K, V>>>, key: K, value: V) -> NonNull<Node<K, V>> {
+        let new_node = Node::new(key, value);
+        let mut z = NodeQuery::new(Some(new_node));
+        let mut y = NodeQuery::new(parent);
+        z.set_parent(y.inner());
+        // 新节点的所有祖先的子树节点个数加1
+        let mut ancestor = y.clone();
+        while !ancestor.is_nil() {
+            ancestor.set_size(ancestor.size() + 1);
+            ancestor = ancestor.parent();
+        }
+        if y.is_nil() {
+            self.root = z.inner();
+        } else if C::compare(z.get_key().expect("RBTree broken"), y.get_key().expect("RBTree broken")) == Ordering::Less {
+            y.set_left(z.inner());
+        } else {
+            y.set_right(z.inner());
+        }
+        self.insert_fixup(z.inner());
+        self.len += 1;
+        self.check();
+        new_node
+    }
+
+    // 从红黑树中摘除节点并修复红黑树，返回被删除的键值对
+    pub(crate) fn remove_node(&mut self, node: NonNull<Node<K, V>>) -> (K, V) {
+        self.len -= 1;
+        let delete_node = Some(node);
+        let z = NodeQuery::new(delete_node); //待删除节点
+        let mut y = z.clone(); // 用于替换待删除节点
+        let x; // 移到被摘除位置上的节点，可能是叶节点NIL
+        let x_parent; // x的双亲，x为NIL时无法从x得到
+        let mut y_original_color = y.color().unwrap();
+        // 被摘除的位置：z最多只有一个孩子时是z自己，否则是z的后继。该位置的所有祖先的子树节点个数减1
+        let mut ancestor = if z.left().is_nil() || z.right().is_nil() {
+            z.parent()
+        } else {
+            z.right().minimum().parent()
+        };
+        while !ancestor.is_nil() {
+            ancestor.set_size(ancestor.size() - 1);
+            ancestor = ancestor.parent();
+        }
+        if z.left().is_nil() {
+            x = z.right();
+            x_parent = z.parent();
+            self.transplant(z.inner(), z.right().inner());
+        } else if z.right().is_nil() {
+            x = z.left();
+            x_parent = z.parent();
+            self.transplant(z.inner(), z.left().inner());
+        } else {
+            y = z.right().minimum();
+            y_original_color = y.color().unwrap();
+            x = y.right();
+            if y.parent().inner() == z.inner() {
+                x_parent = y.clone();
+            } else {
+                x_parent = y.parent();
+                self.transplant(y.inner(), y.right().inner());
+                y.set_right(z.right().inner());
+            }
+            self.transplant(z.inner(), y.inner());
+            y.set_left(z.left().inner());
+            y.set_color(z.color().unwrap());
+            y.set_size(z.size());
+        }
+        if y_original_color == Color::Black {
+            self.delete_fixup(x, x_parent);
+        }
+        // 释放删除节点的内存
+        let delete_node = unsafe { Box::from_raw(node.as_ptr()) };
+        self.check();
+        (delete_node.key, delete_node.value)
+    }
+
+    // 由按键排序的键值对构建新树
+    fn from_sorted_pairs<'a, I>(pairs: I) -> Self
+    where
+        I: Iterator<Item = (&'a K, &'a V)>,
+        K: 'a,
+        V: Clone + 'a,
+    {
+        Self::from_sorted_iter_with_comparator(pairs.map(|(key, value)| (key.clone(), value.clone())))
+    }
+
+    // 根节点到叶子的路径上黑色节点的个数，不含叶节点NIL
+    fn black_height(&self) -> usize {
+        let mut height = 0;
+        let mut cur = NodeQuery::new(self.root);
+        while !cur.is_nil() {
+            if cur.is_black() {
+                height += 1;
+            }
+            cur = cur.left();
+        }
+        height
+    }
+
+    // 把子树从双亲上摘下作为一棵独立的红黑树，红色的根改为黑色，返回根和黑高
+    fn detach(&self, mut node: NodeQuery<K, V>, height: usize) -> Subtree<K, V> {
+        if node.is_nil() {
+            return (None, 0);
+        }
+        node.set_parent(None);
+        if node.is_red() {
+            node.set_color(Color::Black);
+            return (node.inner(), height + 1);
+        }
+        (node.inner(), height)
+    }
+
+    // 以mid为中间节点连接两棵根为黑色(或为空)的独立红黑树，left中的键都小于mid的键，right中的键都大于mid的键，
+    // 返回新树的根和黑高。沿黑高较大的树向下找到黑高相同的黑色节点再连接，时间与两棵树的黑高之差成正比
+    fn join(&mut self, left: Subtree<K, V>, mid: NonNull<Node<K, V>>, right: Subtree<K, V>) -> Subtree<K, V> {
+        let ((left, left_height), (right, right_height)) = (left, right);
+        let left = NodeQuery::new(left);
+        let right = NodeQuery::new(right);
+        let mut x = NodeQuery::new(Some(mid));
+        x.set_parent(None);
+        if left_height == right_height {
+            x.set_left(left.inner());
+            x.set_right(right.inner());
+            x.set_color(Color::Black);
+            x.set_size(left.size() + right.size() + 1);
+            return (x.inner(), left_height + 1);
+        }
+        // 在较高的树中找到黑高与较矮的树相同的黑色节点y，红色的x代替y的位置，y和较矮的树作为x的孩子
+        let left_taller = left_height > right_height;
+        let (tall, short, short_height) = if left_taller {
+            (left, right, right_height)
+        } else {
+            (right, left, left_height)
+        };
+        let mut height = max(left_height, right_height);
+        let mut parent = tall.clone();
+        let mut y = tall.clone();
+        while !(y.is_black() && height == short_height) {
+            if y.is_black() {
+                height -= 1;
+            }
+            parent = y.clone();
+            y = if left_taller { y.right() } else { y.left() };
+        }
+        if left_taller {
+            x.set_left(y.inner());
+            x.set_right(short.inner());
+            parent.set_right(x.inner());
+        } else {
+            x.set_left(short.inner());
+            x.set_right(y.inner());
+            parent.set_left(x.inner());
+        }
+        x.set_color(Color::Red);
+        x.set_size(y.size() + short.size() + 1);
+        let mut ancestor = parent;
+        while !ancestor.is_nil() {
+            ancestor.set_size(ancestor.size() + short.size() + 1);
+            ancestor = ancestor.parent();
+        }
+        self.root = tall.inner();
+        let grown = self.insert_fixup(x.inner());
+        (self.root, max(left_height, right_height) + grown as usize)
+    }
+
+    // 按key把一棵独立红黑树分成两棵，第一棵中的键都小于key，第二棵中的键都不小于key，
+    // 返回两棵树的根和黑高。沿查找路径自底向上依次连接，总时间O(log n)
+    fn split<Q: ?Sized>(&mut self, tree: Subtree<K, V>, key: &Q) -> (Subtree<K, V>, Subtree<K, V>)
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let (node, height) = tree;
+        let node = NodeQuery::new(node);
+        let mid = match node.inner() {
+            Some(mid) => mid,
+            None => return ((None, 0), (None, 0)),
+        };
+        let child_height = if node.is_black() { height - 1 } else { height };
+        let left = self.detach(node.left(), child_height);
+        let right = self.detach(node.right(), child_height);
+        if node.compare_key::<Q, C>(key) == Some(Ordering::Less) {
+            let (less, rest) = self.split(right, key);
+            (self.join(left, mid, less), rest)
+        } else {
+            let (less, rest) = self.split(left, key);
+            (less, self.join(rest, mid, right))
+        }
+    }
+
+    // 插入后修复红黑树，使之继续保持红黑树性质。返回根节点是否由红色改为黑色，此时树的黑高加1
+    fn insert_fixup(&mut self, node: Option<NonNull<Node<K, V>>>) -> bool {
+        let mut z = NodeQuery::new(node);
         while z.parent().is_red() {
             let mut y = z.uncle();
             if z.parent().is_left_child() {
@@ -414,61 +1073,67 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
                 }
             }
         }
-        let mut t = NodeQuery::new(self.root, self.nil);
+        let mut t = NodeQuery::new(self.root);
+        let recolored = t.is_red();
         t.set_color(Color::Black);
+        recolored
     }
 
-    // 删除后修复红黑树，使之继续保持红黑树性质
-    fn delete_fixup(&mut self, node: Option<NonNull<Node<K, V>>>) {
-        let mut x = NodeQuery::new(node, self.nil);
+    // 删除后修复红黑树，使之继续保持红黑树性质。x可能是叶节点NIL，因此单独传入它的双亲
+    fn delete_fixup(&mut self, mut x: NodeQuery<K, V>, mut parent: NodeQuery<K, V>) {
         while x.inner() != self.root && x.is_black() {
-            let mut w = x.brother();
-            if x.is_left_child() {
+            if parent.left().inner() == x.inner() {
+                let mut w = parent.right();
                 if w.is_red() {
                     w.set_color(Color::Black); //case1
-                    x.parent().set_color(Color::Red); //case1
-                    self.left_rotate(x.parent().inner()); //case1
-                    w = x.brother(); //case1
+                    parent.set_color(Color::Red); //case1
+                    self.left_rotate(parent.inner()); //case1
+                    w = parent.right(); //case1
                 }
                 if w.left().is_black() && w.right().is_black() {
                     w.set_color(Color::Red); //case2
-                    x = x.parent(); //case2
+                    x = parent; //case2
+                    parent = x.parent(); //case2
                 } else {
                     if w.right().is_black() {
                         //case3
                         w.left().set_color(Color::Black); //case3
                         w.set_color(Color::Red); //case3
                         self.right_rotate(w.inner()); //case3
-                        w = x.brother(); //case3
+                        w = parent.right(); //case3
                     }
-                    w.set_color(x.parent().color().unwrap()); //case4
-                    x.parent().set_color(Color::Black); //case4
+                    w.set_color(parent.color().unwrap()); //case4
+                    parent.set_color(Color::Black); //case4
                     w.right().set_color(Color::Black); //case4
-                    self.left_rotate(x.parent().inner()); //case4
-                    x = NodeQuery::new(self.root, self.nil); //case4
+                    self.left_rotate(parent.inner()); //case4
+                    x = NodeQuery::new(self.root); //case4
+                    parent = x.parent(); //case4
                 }
             } else {
+                let mut w = parent.left();
                 if w.is_red() {
                     w.set_color(Color::Black);
-                    x.parent().set_color(Color::Red);
-                    self.right_rotate(x.parent().inner());
-                    w = x.brother();
+                    parent.set_color(Color::Red);
+                    self.right_rotate(parent.inner());
+                    w = parent.left();
                 }
                 if w.right().is_black() && w.left().is_black() {
                     w.set_color(Color::Red);
-                    x = x.parent();
+                    x = parent;
+                    parent = x.parent();
                 } else {
                     if w.left().is_black() {
                         w.right().set_color(Color::Black);
                         w.set_color(Color::Red);
                         self.left_rotate(w.inner());
-                        w = x.brother();
+                        w = parent.left();
                     }
-                    w.set_color(x.parent().color().unwrap());
-                    x.parent().set_color(Color::Black);
+                    w.set_color(parent.color().unwrap());
+                    parent.set_color(Color::Black);
                     w.left().set_color(Color::Black);
-                    self.right_rotate(x.parent().inner());
-                    x = NodeQuery::new(self.root, self.nil);
+                    self.right_rotate(parent.inner());
+                    x = NodeQuery::new(self.root);
+                    parent = x.parent();
                 }
             }
         }
@@ -477,7 +1142,7 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
 
     // 左旋转
     fn left_rotate(&mut self, node: Option<NonNull<Node<K, V>>>) {
-        let mut x = NodeQuery::new(node, self.nil);
+        let mut x = NodeQuery::new(node);
         let mut y = x.right();
         x.set_right(y.left().inner());
         if !y.left().is_nil() {
@@ -493,11 +1158,14 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
         }
         y.set_left(x.inner());
         x.set_parent(y.inner());
+        // y取代x成为子树的根，子树的节点个数不变
+        y.set_size(x.size());
+        x.update_size();
     }
 
     // 右旋转
     fn right_rotate(&mut self, node: Option<NonNull<Node<K, V>>>) {
-        let mut x = NodeQuery::new(node, self.nil);
+        let mut x = NodeQuery::new(node);
         let mut y = x.left();
         x.set_left(y.right().inner());
         if !y.right().is_nil() {
@@ -513,18 +1181,22 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
         }
         y.set_right(x.inner());
         x.set_parent(y.inner());
+        y.set_size(x.size());
+        x.update_size();
     }
 
     // 根据键查找节点
-    fn search(&self, key: &K) -> Option<NonNull<Node<K, V>>> {
-        let mut x = NodeQuery::new(self.root, self.nil);
-        while !x.is_nil() {
-            if x > *key {
-                x = x.left();
-            } else if x < *key {
-                x = x.right();
-            } else {
-                break;
+    fn search<Q: ?Sized>(&self, key: &Q) -> Option<NonNull<Node<K, V>>>
+    where
+        K: Borrow<Q>,
+        C: Compare<Q>,
+    {
+        let mut x = NodeQuery::new(self.root);
+        while let Some(order) = x.compare_key::<Q, C>(key) {
+            match order {
+                Ordering::Greater => x = x.left(),
+                Ordering::Less => x = x.right(),
+                Ordering::Equal => break,
             }
         }
         if x.is_nil() {
@@ -535,8 +1207,8 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
 
     //delete 调用的子过程,用src替换dest的位置
     fn transplant(&mut self, dest: Option<NonNull<Node<K, V>>>, src: Option<NonNull<Node<K, V>>>) {
-        let u = NodeQuery::new(dest, self.nil);
-        let mut v = NodeQuery::new(src, self.nil);
+        let u = NodeQuery::new(dest);
+        let mut v = NodeQuery::new(src);
         if u.parent().is_nil() {
             self.root = src;
         } else if u.is_left_child() {
@@ -546,74 +1218,220 @@ impl<K: Default + PartialOrd + Clone, V: Default> RBTree<K, V> {
         }
         v.set_parent(u.parent().inner())
     }
+}
+
+/// 返回两棵树的并集构成的新树
+/// # Example
+/// ```
+/// use an_unsafe_rb_tree::RBTree;
+/// let mut a = RBTree::new();
+/// let mut b = RBTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a | &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2, 3, 4, 5, 6]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitOr<&RBTree<K, V, C>> for &RBTree<K, V, C> {
+    type Output = RBTree<K, V, C>;
 
-    // 前序遍历
-    fn prev_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        let q = NodeQuery::new(self.root, self.nil);
-        q.pre_order(&mut buf);
-        buf
+    fn bitor(self, rhs: &RBTree<K, V, C>) -> RBTree<K, V, C> {
+        RBTree::from_sorted_pairs(self.union(rhs))
     }
+}
+
+/// 返回两棵树的交集构成的新树
+/// # Example
+/// ```
+/// use an_unsafe_rb_tree::RBTree;
+/// let mut a = RBTree::new();
+/// let mut b = RBTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a & &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![3, 4]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitAnd<&RBTree<K, V, C>> for &RBTree<K, V, C> {
+    type Output = RBTree<K, V, C>;
 
-    // 中序遍历
-    fn in_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        let q = NodeQuery::new(self.root, self.nil);
-        q.in_order(&mut buf);
-        buf
+    fn bitand(self, rhs: &RBTree<K, V, C>) -> RBTree<K, V, C> {
+        RBTree::from_sorted_pairs(self.intersection(rhs))
     }
+}
 
-    // 后序遍历
-    fn post_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        let q = NodeQuery::new(self.root, self.nil);
-        q.post_order(&mut buf);
-        buf
+/// 返回两棵树的差集构成的新树
+/// # Example
+/// ```
+/// use an_unsafe_rb_tree::RBTree;
+/// let mut a = RBTree::new();
+/// let mut b = RBTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a - &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> Sub<&RBTree<K, V, C>> for &RBTree<K, V, C> {
+    type Output = RBTree<K, V, C>;
+
+    fn sub(self, rhs: &RBTree<K, V, C>) -> RBTree<K, V, C> {
+        RBTree::from_sorted_pairs(self.difference(rhs))
     }
+}
 
-    // 层序遍历
-    fn level_order(&self) -> Vec<K> {
-        let mut buf = Vec::new();
-        let q = NodeQuery::new(self.root, self.nil);
-        q.level_order(&mut buf);
-        buf
+/// 返回两棵树的对称差构成的新树
+/// # Example
+/// ```
+/// use an_unsafe_rb_tree::RBTree;
+/// let mut a = RBTree::new();
+/// let mut b = RBTree::new();
+/// for key in 1..=4 {
+///     a.insert(key, ());
+///     b.insert(key + 2, ());
+/// }
+/// let res: Vec<i32> = (&a ^ &b).inorder_iter().map(|(k, _)| *k).collect();
+/// assert_eq!(res, vec![1, 2, 5, 6]);
+/// ```
+impl<K: Clone, V: Clone, C: Compare<K>> BitXor<&RBTree<K, V, C>> for &RBTree<K, V, C> {
+    type Output = RBTree<K, V, C>;
+
+    fn bitxor(self, rhs: &RBTree<K, V, C>) -> RBTree<K, V, C> {
+        RBTree::from_sorted_pairs(self.symmetric_difference(rhs))
     }
 }
 
-/// 将红黑树打印成字符串
+/// 将红黑树打印成字符串，`{}`打印成一行嵌套的形式，`{:#}`把树画成多行并注明颜色，空的孩子画成Ø
 /// # Example
 /// ```
 /// use an_unsafe_rb_tree::RBTree;
 /// let mut tree = RBTree::new();
 /// tree.insert(1, 'a');
 /// assert_eq!(tree.to_string(), "[K: 1, V: a, C: Black L: Ø, R: Ø]".to_string());
+/// tree.insert(2, 'b');
+/// assert_eq!(format!("{:#}", tree), "1: a (Black)\n├── Ø\n└── 2: b (Red)");
+/// ```
+impl<K: Display, V: Display, C> Display for RBTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let root = NodeQuery::new(self.root);
+        if f.alternate() {
+            root.draw(f, &|key, value, f| write!(f, "{}: {}", key, value))
+        } else {
+            write!(f, "{}", root)
+        }
+    }
+}
+
+/// `{:?}`按键的顺序打印所有键值对，`{:#?}`把树画成多行并注明颜色
+/// # Example
+/// ```
+/// use an_unsafe_rb_tree::RBTree;
+/// let mut tree = RBTree::new();
+/// for key in 1..=3 {
+///     tree.insert(key, 'a');
+/// }
+/// assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'a', 3: 'a'}");
+/// assert_eq!(format!("{:#?}", tree), "2: 'a' (Black)\n├── 1: 'a' (Red)\n└── 3: 'a' (Red)");
 /// ```
-impl<K: Clone + PartialOrd + ToString, V: ToString> ToString for RBTree<K, V> {
-    fn to_string(&self) -> String {
-        NodeQuery::new(self.root, self.nil).to_string()
+impl<K: Debug + Clone, V: Debug, C: Compare<K>> Debug for RBTree<K, V, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let root = NodeQuery::new(self.root);
+            root.draw(f, &|key, value, f| write!(f, "{:?}: {:?}", key, value))
+        } else {
+            f.debug_map().entries(self.inorder_iter()).finish()
+        }
     }
 }
 
-impl<K: Default + PartialOrd + Clone, V: Default> Default for RBTree<K, V> {
+impl<K, V, C: Compare<K>> Default for RBTree<K, V, C> {
     fn default() -> Self {
-        Self::new()
+        Self::with_comparator()
+    }
+}
+
+impl<K: Clone, V, C: Compare<K>> OrderedMap<K, V> for RBTree<K, V, C> {
+    type TraverseIter<'a> = TraverseIter<'a, K, V> where Self: 'a;
+    type Iter<'a> = Iter<'a, K, V> where Self: 'a;
+
+    fn new() -> Self {
+        RBTree::with_comparator()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        RBTree::insert(self, key, value)
+    }
+
+    fn delete(&mut self, key: K) {
+        RBTree::delete(self, &key)
+    }
+
+    fn len(&self) -> usize {
+        RBTree::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        RBTree::is_empty(self)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        RBTree::get(self, key)
+    }
+
+    fn get_pair(&self, key: &K) -> Option<(&K, &V)> {
+        RBTree::get_pair(self, key)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        RBTree::contains(self, key)
+    }
+
+    fn min_pair(&self) -> Option<(&K, &V)> {
+        RBTree::min_pair(self)
+    }
+
+    fn max_pair(&self) -> Option<(&K, &V)> {
+        RBTree::max_pair(self)
+    }
+
+    fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        RBTree::successor(self, key)
+    }
+
+    fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        RBTree::predecessor(self, key)
+    }
+
+    fn preorder_iter(&self) -> TraverseIter<'_, K, V> {
+        RBTree::preorder_iter(self)
+    }
+
+    fn inorder_iter(&self) -> Iter<'_, K, V> {
+        RBTree::inorder_iter(self)
+    }
+
+    fn postorder_iter(&self) -> TraverseIter<'_, K, V> {
+        RBTree::postorder_iter(self)
+    }
+
+    fn levelorder_iter(&self) -> TraverseIter<'_, K, V> {
+        RBTree::levelorder_iter(self)
     }
 }
 
-impl<K, V> Drop for RBTree<K, V> {
+impl<K, V, C> Drop for RBTree<K, V, C> {
     fn drop(&mut self) {
-        fn visitor<K, V>(node_ptr: Option<NonNull<Node<K, V>>>, nil: NonNull<Node<K, V>>) {
+        fn visitor<K, V>(node_ptr: Option<NonNull<Node<K, V>>>) {
             if let Some(p) = node_ptr {
-                if p == nil {
-                    return;
-                }
                 let node = unsafe { Box::from_raw(p.as_ptr()) };
-                visitor(node.left, nil);
-                visitor(node.right, nil);
+                visitor(node.left);
+                visitor(node.right);
             }
         }
-        visitor(self.root, self.nil);
-        let _nil_node = unsafe { Box::from_raw(self.nil.as_ptr()) };
+        visitor(self.root);
     }
 }
 
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use an_unsafe_rb_tree::RBTree;
+    use an_unsafe_rb_tree::{Entry, RBTree};
+    use ordered_map::{Compare, InvariantViolation, Reverse};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, Bound};
     #[test]
     fn insert_delete() {
         let mut tree = RBTree::new();
@@ -15,8 +18,8 @@ mod tests {
         tree.insert(10, 'j');
         tree.insert(9, 'i');
         tree.insert(8, 'h');
-        tree.delete(6);
-        tree.delete(4);
+        tree.delete(&6);
+        tree.delete(&4);
         assert_eq!(tree.to_string(), "[K: 5, V: e, C: Black L: [K: 2, V: b, C: Black L: [K: 1, V: a, C: Black L: Ø, R: Ø]\
         , R: [K: 3, V: c, C: Black L: Ø, R: Ø]], R: [K: 9, V: i, C: Black L: [K: 7, V: g, C: Black L: Ø, R: [K: 8, V: h, C: Red L: Ø, R: Ø]]\
         , R: [K: 10, V: j, C: Black L: Ø, R: Ø]]]".to_string());
@@ -32,7 +35,7 @@ mod tests {
         assert_eq!(tree.tree_height(), 4);
         assert!(!tree.contains(&4));
         assert!(!tree.contains(&6));
-        tree.delete(8);
+        tree.delete(&8);
         assert_eq!(tree.to_string(), "[K: 5, V: e, C: Black L: [K: 2, V: b, C: Black L: [K: 1, V: a, C: Black L: Ø, R: Ø]\
         , R: [K: 3, V: c, C: Black L: Ø, R: Ø]], R: [K: 9, V: i, C: Black L: [K: 7, V: g, C: Black L: Ø, R: Ø]\
         , R: [K: 10, V: j, C: Black L: Ø, R: Ø]]]".to_string());
@@ -44,7 +47,7 @@ mod tests {
                    1   3   7     10
         */
         assert_eq!(tree.tree_height(), 3);
-        tree.delete(9);
+        tree.delete(&9);
         assert_eq!(tree.to_string(), "[K: 5, V: e, C: Black L: [K: 2, V: b, C: Red L: [K: 1, V: a, C: Black L: Ø, R: Ø]\
         , R: [K: 3, V: c, C: Black L: Ø, R: Ø]], R: [K: 10, V: j, C: Black L: [K: 7, V: g, C: Red L: Ø, R: Ø], R: Ø]]".to_string());
         /*
@@ -55,7 +58,7 @@ mod tests {
                    1   3  7(r)
         */
         assert_eq!(tree.tree_height(), 3);
-        tree.delete(10);
+        tree.delete(&10);
         assert_eq!(
             tree.to_string(),
             "[K: 5, V: e, C: Black L: [K: 2, V: b, C: Red L: [K: 1, V: a, C: Black L: Ø, R: Ø]\
@@ -70,7 +73,7 @@ mod tests {
                1   3
         */
         assert_eq!(tree.tree_height(), 3);
-        tree.delete(7);
+        tree.delete(&7);
         assert_eq!(
             tree.to_string(),
             "[K: 2, V: b, C: Black L: [K: 1, V: a, C: Black L: Ø, R: Ø]\
@@ -85,7 +88,7 @@ mod tests {
                       3(r)
         */
         assert_eq!(tree.tree_height(), 3);
-        tree.delete(2);
+        tree.delete(&2);
         assert_eq!(tree.to_string(), "[K: 3, V: c, C: Black L: [K: 1, V: a, C: Black L: Ø, R: Ø], R: [K: 5, V: e, C: Black L: Ø, R: Ø]]".to_string());
         /*
                  3
@@ -93,7 +96,7 @@ mod tests {
              1      5
         */
         assert_eq!(tree.tree_height(), 2);
-        tree.delete(3);
+        tree.delete(&3);
         assert_eq!(
             tree.to_string(),
             "[K: 5, V: e, C: Black L: [K: 1, V: a, C: Red L: Ø, R: Ø], R: Ø]".to_string()
@@ -104,7 +107,7 @@ mod tests {
              1(r)
         */
         assert_eq!(tree.tree_height(), 2);
-        tree.delete(5);
+        tree.delete(&5);
         assert_eq!(
             tree.to_string(),
             "[K: 1, V: a, C: Black L: Ø, R: Ø]".to_string()
@@ -113,7 +116,7 @@ mod tests {
                  1
         */
         assert_eq!(tree.tree_height(), 1);
-        tree.delete(1);
+        tree.delete(&1);
         assert_eq!(tree.to_string(), "Ø".to_string());
         assert!(tree.is_empty());
     }
@@ -190,8 +193,8 @@ mod tests {
         assert_eq!(tree.predecessor(&5), Some((&4, &"4")));
         assert_eq!(tree.successor(&10), None);
         assert_eq!(tree.predecessor(&1), None);
-        assert_eq!(tree.successor(&0), None);
-        assert_eq!(tree.predecessor(&100), None);
+        assert_eq!(tree.successor(&0), Some((&1, &"1")));
+        assert_eq!(tree.predecessor(&100), Some((&10, &"10")));
     }
 
     #[test]
@@ -273,6 +276,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn entry() {
+        // 通过入口插入和删除，必须与insert、delete得到形状和颜色完全相同的树
+        let mut expected = RBTree::new();
+        let mut tree = RBTree::new();
+        for key in 1..=64 {
+            expected.insert(key, key * 10);
+            let value = tree.entry(key).or_insert(0);
+            assert_eq!(*value, 0);
+            *value = key * 10;
+        }
+        assert_eq!(tree.to_string(), expected.to_string());
+        assert!(tree.tree_height() <= 12);
+
+        for key in 60..=70 {
+            if key > 64 {
+                expected.insert(key, key);
+            }
+            tree.entry(key).and_modify(|v| *v += 1).or_insert_with(|| key);
+        }
+        assert_eq!(tree.get(&60), Some(&601));
+        assert_eq!(tree.get(&70), Some(&70));
+        assert_eq!(*tree.entry(100).key(), 100);
+
+        for key in 65..=70 {
+            expected.delete(&key);
+            tree.delete(&key);
+        }
+        for key in 60..=64 {
+            if let Entry::Occupied(mut entry) = tree.entry(key) {
+                entry.insert(key * 10);
+            }
+        }
+        for key in [8, 32, 1, 17, 64] {
+            expected.delete(&key);
+            match tree.entry(key) {
+                Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), (key, key * 10)),
+                Entry::Vacant(_) => unreachable!(),
+            }
+        }
+        assert_eq!(tree.to_string(), expected.to_string());
+        match tree.entry(8) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), 8),
+            Entry::Occupied(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn mutable_access() {
+        let mut tree = RBTree::new();
+        for key in 1..=20 {
+            tree.insert(key, key);
+        }
+        assert_eq!(tree.get_mut(&21), None);
+        if let Some(value) = tree.get_mut(&7) {
+            *value = 700;
+        }
+        assert_eq!(tree.get(&7), Some(&700));
+
+        let keys: Vec<i32> = tree
+            .iter_mut()
+            .map(|(key, value)| {
+                *value += 1;
+                *key
+            })
+            .collect();
+        assert_eq!(keys, (1..=20).collect::<Vec<i32>>());
+        for value in tree.values_mut() {
+            *value *= 2;
+        }
+        assert_eq!(tree.get(&7), Some(&1402));
+        assert_eq!(tree.get(&20), Some(&42));
+        assert_eq!(tree.successor(&7), Some((&8, &18)));
+        assert_eq!(tree.predecessor(&7), Some((&6, &14)));
+
+        let mut empty: RBTree<i32, i32> = RBTree::new();
+        assert_eq!(empty.iter_mut().next(), None);
+        assert_eq!(empty.values_mut().next(), None);
+    }
+
+    #[test]
+    fn lazy_traverse() {
+        let mut tree = RBTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        let res: Vec<(&i32, &i32)> = tree.inorder_iter().take(3).collect();
+        assert_eq!(res, vec![(&0, &0), (&1, &2), (&2, &4)]);
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+
+        // 前序遍历的第一个、后序遍历的最后一个以及层序遍历的第一个都是根节点
+        let root = tree.preorder_iter().next();
+        assert_eq!(root, tree.levelorder_iter().next());
+        assert_eq!(root, tree.postorder_iter().last());
+        for iter in [tree.preorder_iter(), tree.postorder_iter(), tree.levelorder_iter()] {
+            let mut keys: Vec<i32> = iter.map(|(k, _)| *k).collect();
+            keys.sort();
+            assert_eq!(keys, (0..1000).collect::<Vec<i32>>());
+        }
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut tree = RBTree::new();
+        // 37与1000互质，键以打乱的顺序插入
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key * 2);
+        }
+        tree.insert(1, 1);
+        assert_eq!(tree.len(), 1000);
+        for key in (0..1000).step_by(3) {
+            tree.delete(&key);
+        }
+        tree.delete(&1000);
+        let expected: Vec<i32> = (0..1000).filter(|key| key % 3 != 0).collect();
+        assert_eq!(tree.len(), expected.len());
+        let keys: Vec<i32> = tree.inorder_iter().rev().map(|(k, _)| *k).collect();
+        assert_eq!(keys, expected.iter().rev().cloned().collect::<Vec<i32>>());
+
+        // 两端交替迭代，在中间相遇后两端都结束
+        let mut iter = tree.inorder_iter();
+        let n = expected.len();
+        for i in 0..n / 2 {
+            assert_eq!(iter.len(), n - 2 * i);
+            assert_eq!(iter.next().map(|(k, _)| *k), Some(expected[i]));
+            assert_eq!(iter.next_back().map(|(k, _)| *k), Some(expected[n - 1 - i]));
+        }
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(tree.preorder_iter().len(), n);
+        assert_eq!(tree.postorder_iter().len(), n);
+        assert_eq!(tree.levelorder_iter().len(), n);
+
+        // 删除修复时x为叶节点NIL，旋转之后仍要沿着正确的双亲继续修复，否则会丢失节点
+        let mut tree = RBTree::new();
+        for key in [8, 5, 3, 2, 4, 6, 7, 15, 12, 17, 10, 14] {
+            tree.insert(key, key);
+        }
+        for key in [12, 6, 8, 2] {
+            tree.delete(&key);
+        }
+        let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![3, 4, 5, 7, 10, 14, 15, 17]);
+        assert_eq!(tree.len(), 8);
+    }
+
     #[test]
     fn to_string() {
         let mut tree = RBTree::new();
@@ -282,4 +436,452 @@ mod tests {
         tree.insert(4, 'd');
         assert_eq!(tree.to_string(), String::from("[K: 2, V: b, C: Black L: [K: 1, V: a, C: Black L: Ø, R: Ø], R: [K: 3, V: c, C: Black L: Ø, R: [K: 4, V: d, C: Red L: Ø, R: Ø]]]"))
     }
+
+    #[test]
+    fn to_dot() {
+        let mut tree = RBTree::new();
+        assert_eq!(
+            tree.to_dot(),
+            "digraph RBTree {\n    ordering=out;\n    node [shape=circle, style=filled, fontcolor=white];\n}\n"
+        );
+        tree.insert(3, 'c');
+        tree.insert(2, 'b');
+        tree.insert(1, 'a');
+        tree.insert(4, 'd');
+        let expected = [
+            "digraph RBTree {",
+            "    ordering=out;",
+            "    node [shape=circle, style=filled, fontcolor=white];",
+            r#"    0 [label="2: b", fillcolor=black];"#,
+            "    0 -> 1;",
+            r#"    1 [label="1: a", fillcolor=black];"#,
+            "    0 -> 2;",
+            r#"    2 [label="3: c", fillcolor=black];"#,
+            "    nil2 [shape=point];",
+            "    2 -> nil2;",
+            "    2 -> 3;",
+            r#"    3 [label="4: d", fillcolor=red];"#,
+            "}",
+        ];
+        assert_eq!(tree.to_dot(), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn pretty_print() {
+        let mut tree = RBTree::new();
+        assert_eq!(format!("{:#}", tree), "Ø");
+        assert_eq!(format!("{:?}", tree), "{}");
+        tree.insert(3, 'c');
+        tree.insert(2, 'b');
+        tree.insert(1, 'a');
+        tree.insert(4, 'd');
+        let expected = ["2: b (Black)", "├── 1: a (Black)", "└── 3: c (Black)", "    ├── Ø", "    └── 4: d (Red)"];
+        assert_eq!(format!("{:#}", tree), expected.join("\n"));
+        assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'b', 3: 'c', 4: 'd'}");
+        let expected = ["2: 'b' (Black)", "├── 1: 'a' (Black)", "└── 3: 'c' (Black)", "    ├── Ø", "    └── 4: 'd' (Red)"];
+        assert_eq!(format!("{:#?}", tree), expected.join("\n"));
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = RBTree::new();
+        let mut expected = BTreeSet::new();
+        // 37与1000互质，键以打乱的顺序插入，只保留偶数键
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            if key % 2 == 0 {
+                tree.insert(key, key);
+                expected.insert(key);
+            }
+        }
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Included(101), Bound::Excluded(199)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Excluded(500), Bound::Excluded(502)),
+            (Bound::Included(-5), Bound::Excluded(0)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            let want: Vec<i32> = expected.range((lower, upper)).cloned().collect();
+            let res: Vec<i32> = tree.range((lower, upper)).map(|(k, _)| *k).collect();
+            assert_eq!(res, want);
+            let res: Vec<i32> = tree.range((lower, upper)).rev().map(|(k, _)| *k).collect();
+            assert_eq!(res, want.iter().rev().cloned().collect::<Vec<i32>>());
+            // 两端交替迭代，相遇后两端都结束
+            let mut iter = tree.range((lower, upper));
+            let (mut front, mut back) = (0, want.len());
+            while front < back {
+                assert_eq!(iter.len(), back - front);
+                assert_eq!(iter.next().map(|(k, _)| *k), Some(want[front]));
+                front += 1;
+                if front < back {
+                    back -= 1;
+                    assert_eq!(iter.next_back().map(|(k, _)| *k), Some(want[back]));
+                }
+            }
+            assert_eq!(iter.next().map(|(k, _)| *k), None);
+            assert_eq!(iter.next_back().map(|(k, _)| *k), None);
+            assert_eq!(iter.len(), 0);
+            // 可变范围迭代器与不可变范围迭代器覆盖相同的节点
+            let res: Vec<i32> = tree.range_mut((lower, upper)).rev().map(|(k, _)| *k).collect();
+            assert_eq!(res, want.iter().rev().cloned().collect::<Vec<i32>>());
+            assert_eq!(tree.range_mut((lower, upper)).len(), want.len());
+        }
+        let res: Vec<i32> = tree.range(3..=9).map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![4, 6, 8]);
+        for (key, value) in tree.range_mut(3..=9) {
+            *value = -*key;
+        }
+        let res: Vec<i32> = tree.range(..=10).map(|(_, v)| *v).collect();
+        assert_eq!(res, vec![0, 2, -4, -6, -8, 10]);
+        assert_eq!(tree.range_mut((Bound::Included(9), Bound::Excluded(3))).next(), None);
+    }
+
+    #[test]
+    fn order_statistics() {
+        let mut tree = RBTree::new();
+        let mut expected = BTreeSet::new();
+        // 插入、删除以及通过入口增删穿插进行，覆盖各种旋转
+        for i in 0..2000 {
+            let key = i * 37 % 1000;
+            match i % 5 {
+                0 | 1 => {
+                    tree.insert(key, key);
+                    expected.insert(key);
+                }
+                2 => {
+                    tree.entry(key).or_insert(key);
+                    expected.insert(key);
+                }
+                3 => {
+                    tree.delete(&(key / 2));
+                    expected.remove(&(key / 2));
+                }
+                _ => {
+                    if let Entry::Occupied(entry) = tree.entry(key / 3) {
+                        entry.remove();
+                    }
+                    expected.remove(&(key / 3));
+                }
+            }
+        }
+        assert_eq!(tree.len(), expected.len());
+        for (i, key) in expected.iter().enumerate() {
+            assert_eq!(tree.select(i), Some((key, key)));
+            assert_eq!(tree.rank(key), i);
+            assert_eq!(tree.rank(&(key + 1)), expected.range(..key + 1).count());
+        }
+        assert_eq!(tree.select(expected.len()), None);
+        assert_eq!(tree.rank(&-1), 0);
+        assert_eq!(tree.rank(&1000), expected.len());
+
+        let bounds = [
+            (Bound::Included(100), Bound::Included(200)),
+            (Bound::Excluded(100), Bound::Excluded(200)),
+            (Bound::Unbounded, Bound::Included(7)),
+            (Bound::Excluded(990), Bound::Unbounded),
+            (Bound::Included(500), Bound::Included(500)),
+            (Bound::Unbounded, Bound::Unbounded),
+        ];
+        for (lower, upper) in bounds.iter().cloned() {
+            assert_eq!(tree.count_range((lower, upper)), expected.range((lower, upper)).count());
+        }
+        assert_eq!(tree.count_range((Bound::Included(600), Bound::Excluded(400))), 0);
+    }
+
+    #[test]
+    fn split_off_append() {
+        let build = || {
+            let mut tree = RBTree::new();
+            for i in 0..1000 {
+                let key = i * 37 % 1000;
+                tree.insert(key, key);
+            }
+            tree
+        };
+        for &at in [-1, 0, 1, 137, 500, 512, 999, 1000, 2000].iter() {
+            let mut tree = build();
+            let mut other = tree.split_off(&at);
+            let split = at.clamp(0, 1000) as usize;
+            assert_eq!(tree.len(), split);
+            assert_eq!(other.len(), 1000 - split);
+            assert!(tree.is_rb_tree());
+            assert!(other.is_rb_tree());
+            let keys: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).collect();
+            assert_eq!(keys, (0..split as i32).collect::<Vec<i32>>());
+            let keys: Vec<i32> = other.inorder_iter().map(|(k, _)| *k).collect();
+            assert_eq!(keys, (split as i32..1000).collect::<Vec<i32>>());
+
+            tree.append(&mut other);
+            assert!(other.is_empty());
+            assert_eq!(tree.len(), 1000);
+            assert!(tree.is_rb_tree());
+            // 连接后的树可以继续增删
+            for key in (0..1000).step_by(7) {
+                tree.delete(&key);
+            }
+            tree.insert(2000, 2000);
+            assert!(tree.is_rb_tree());
+            assert_eq!(tree.select(0), Some((&1, &1)));
+            assert_eq!(tree.rank(&2000), tree.len() - 1);
+        }
+
+        // 两棵无关的树也可以直接连接，黑高相差较多时沿较高的树向下找到连接点
+        for &(small, large) in [(10, 500), (500, 10)].iter() {
+            let mut tree = RBTree::new();
+            let mut other = RBTree::new();
+            for key in 0..small {
+                tree.insert(key, key);
+            }
+            for key in 0..large {
+                other.insert(key + small, key + small);
+            }
+            tree.append(&mut other);
+            drop(other);
+            assert_eq!(tree.len(), (small + large) as usize);
+            assert!(tree.is_rb_tree());
+            tree.delete(&0);
+            tree.insert(-1, -1);
+            assert!(tree.is_rb_tree());
+        }
+
+        // 分割出的树不依赖原树，原树先释放后仍然可用
+        let mut tree = build();
+        let mut other = tree.split_off(&300);
+        drop(tree);
+        other.delete(&300);
+        other.insert(-1, -1);
+        assert!(other.is_rb_tree());
+        assert_eq!(other.len(), 700);
+
+        // 来自不同树的分割结果可以互相连接，删空后再插入
+        let mut low = build().split_off(&900);
+        let mut high = RBTree::new();
+        for key in 2000..2100 {
+            high.insert(key, key);
+        }
+        let mut high = high.split_off(&2050);
+        low.append(&mut high);
+        assert_eq!(low.len(), 150);
+        assert!(low.is_rb_tree());
+        for key in (900..1000).chain(2050..2100) {
+            low.delete(&key);
+        }
+        assert!(low.is_empty());
+        low.insert(1, 1);
+        assert!(low.is_rb_tree());
+    }
+
+    #[test]
+    fn set_operations() {
+        let sets: Vec<BTreeSet<i32>> = vec![
+            BTreeSet::new(),
+            (0..100).collect(),
+            (0..100).filter(|key| key % 3 == 0).collect(),
+            (50..150).filter(|key| key % 2 == 0).collect(),
+            (200..210).collect(),
+        ];
+        let build = |set: &BTreeSet<i32>| {
+            let mut tree = RBTree::new();
+            for key in set.iter() {
+                tree.insert(*key, *key * 10);
+            }
+            tree
+        };
+        // 检查值与键对应，返回键
+        let key_of = |(key, value): (&i32, &i32)| {
+            assert_eq!(*value, *key * 10);
+            *key
+        };
+        let tree_keys = |tree: RBTree<i32, i32>| -> Vec<i32> { tree.inorder_iter().map(|(k, _)| *k).collect() };
+        for x in sets.iter() {
+            for y in sets.iter() {
+                let (a, b) = (build(x), build(y));
+                let union: Vec<i32> = x.union(y).cloned().collect();
+                let intersection: Vec<i32> = x.intersection(y).cloned().collect();
+                let difference: Vec<i32> = x.difference(y).cloned().collect();
+                let symmetric_difference: Vec<i32> = x.symmetric_difference(y).cloned().collect();
+                assert_eq!(a.union(&b).map(key_of).collect::<Vec<i32>>(), union);
+                assert_eq!(a.intersection(&b).map(key_of).collect::<Vec<i32>>(), intersection);
+                assert_eq!(a.difference(&b).map(key_of).collect::<Vec<i32>>(), difference);
+                assert_eq!(a.symmetric_difference(&b).map(key_of).collect::<Vec<i32>>(), symmetric_difference);
+                assert_eq!(tree_keys(&a | &b), union);
+                assert_eq!(tree_keys(&a & &b), intersection);
+                assert_eq!(tree_keys(&a - &b), difference);
+                assert_eq!(tree_keys(&a ^ &b), symmetric_difference);
+            }
+        }
+
+        // 两棵树都有的键取左边树的值
+        let (mut a, mut b) = (RBTree::new(), RBTree::new());
+        a.insert(1, 'a');
+        b.insert(1, 'b');
+        b.insert(2, 'b');
+        assert_eq!(a.union(&b).collect::<Vec<_>>(), vec![(&1, &'a'), (&2, &'b')]);
+        assert_eq!((&a & &b).get(&1), Some(&'a'));
+    }
+
+    #[test]
+    fn no_default_bound() {
+        use std::num::NonZeroU64;
+        use std::rc::Rc;
+
+        // 键和值都没有实现Default，值在析构时计数
+        struct Handle(Rc<()>);
+
+        let counter = Rc::new(());
+        let key = |k: u64| NonZeroU64::new(k).unwrap();
+        let mut tree = RBTree::new();
+        for k in 1..=100 {
+            tree.insert(key(k), Handle(Rc::clone(&counter)));
+        }
+        assert_eq!(Rc::strong_count(&counter), 101);
+        assert!(tree.is_rb_tree());
+        for k in (1..=100).step_by(2) {
+            tree.delete(&key(k));
+        }
+        assert!(tree.is_rb_tree());
+        assert_eq!(Rc::strong_count(&counter), 51);
+        assert_eq!(tree.min_pair().map(|(k, _)| k.get()), Some(2));
+        tree.insert(key(2), Handle(Rc::clone(&counter)));
+        assert_eq!(Rc::strong_count(&counter), 51);
+        assert!(tree.get(&key(2)).is_some_and(|h| Rc::ptr_eq(&h.0, &counter)));
+
+        let rest = tree.split_off(&key(51));
+        assert_eq!(tree.len() + rest.len(), 50);
+        drop(rest);
+        drop(tree);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        for n in 0..70 {
+            let mut tree = RBTree::from_sorted_iter((0..n).map(|key| (key, key * 2)));
+            assert_eq!(tree.len(), n as usize);
+            assert!(tree.is_rb_tree());
+            assert!(tree.inorder_iter().map(|(k, v)| (*k, *v)).eq((0..n).map(|key| (key, key * 2))));
+            for key in 0..n {
+                assert_eq!(tree.rank(&key), key as usize);
+            }
+            // 构建出的树可以继续插入删除
+            tree.insert(n, 0);
+            tree.delete(&0);
+            assert!(tree.is_rb_tree());
+        }
+        let pairs = vec![(5, 'e'), (1, 'a'), (3, 'c'), (1, 'z'), (4, 'd'), (2, 'b')];
+        let tree = RBTree::from_sorted_iter(pairs);
+        assert!(tree.is_rb_tree());
+        let res: Vec<(&i32, &char)> = tree.inorder_iter().collect();
+        assert_eq!(res, vec![(&1, &'z'), (&2, &'b'), (&3, &'c'), (&4, &'d'), (&5, &'e')]);
+    }
+    // 忽略大小写比较字符串，同时支持按String和&str比较
+    struct CaseInsensitive;
+
+    impl Compare<str> for CaseInsensitive {
+        fn compare(a: &str, b: &str) -> Ordering {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        }
+    }
+
+    impl Compare<String> for CaseInsensitive {
+        fn compare(a: &String, b: &String) -> Ordering {
+            <Self as Compare<str>>::compare(a, b)
+        }
+    }
+
+    #[test]
+    fn comparator() {
+        let mut tree: RBTree<i32, i32, Reverse> = RBTree::with_comparator();
+        for key in 0..50 {
+            tree.insert(key, key * 10);
+        }
+        assert!(tree.is_rb_tree());
+        let res: Vec<i32> = tree.inorder_iter().map(|(k, _)| *k).take(3).collect();
+        assert_eq!(res, vec![49, 48, 47]);
+        assert_eq!(tree.max_pair(), Some((&0, &0)));
+        assert_eq!(tree.successor(&10), Some((&9, &90)));
+        assert_eq!(tree.rank(&45), 4);
+        assert_eq!(tree.count_range((Bound::Included(20), Bound::Excluded(10))), 10);
+        let res: Vec<i32> = tree.range((Bound::Included(12), Bound::Included(9))).rev().map(|(k, _)| *k).collect();
+        assert_eq!(res, vec![9, 10, 11, 12]);
+        for key in (0..50).step_by(2) {
+            tree.delete(&key);
+        }
+        assert!(tree.is_rb_tree());
+        let mut low = tree.split_off(&20);
+        assert_eq!(low.min_pair(), Some((&19, &190)));
+        low.append(&mut RBTree::from_sorted_iter_with_comparator(vec![(-1, -10), (-3, -30)]));
+        assert!(low.is_rb_tree());
+        assert_eq!(low.max_pair(), Some((&-3, &-30)));
+        let res: Vec<i32> = (&tree ^ &low).inorder_iter().map(|(k, _)| *k).take(2).collect();
+        assert_eq!(res, vec![49, 47]);
+
+        let mut tree: RBTree<String, i32, CaseInsensitive> = RBTree::with_comparator();
+        for word in ["Apple", "banana", "APPLE", "Cherry"].iter() {
+            *tree.entry(word.to_string()).or_insert(0) += 1;
+        }
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.get("apple"), Some(&2));
+        assert_eq!(tree.get_pair("CHERRY"), Some((&String::from("Cherry"), &1)));
+        assert!(tree.contains("BANANA"));
+        assert!(tree.is_rb_tree());
+    }
+
+    #[test]
+    fn borrowed_lookup() {
+        let mut tree = RBTree::new();
+        for word in ["b", "a", "c"].iter() {
+            tree.insert(word.to_string(), word.len());
+        }
+        assert_eq!(tree.get("a"), Some(&1));
+        assert!(tree.contains("c"));
+        *tree.get_mut("b").unwrap() += 1;
+        assert_eq!(tree.get_pair("b"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.successor("a"), Some((&String::from("b"), &2)));
+        assert_eq!(tree.predecessor("b"), Some((&String::from("a"), &1)));
+        assert_eq!(tree.rank("c"), 2);
+        let bounds: (Bound<&str>, Bound<&str>) = (Bound::Excluded("a"), Bound::Unbounded);
+        assert_eq!(tree.count_range::<str, _>(bounds), 2);
+        let res: Vec<&String> = tree.range::<str, _>(bounds).map(|(k, _)| k).collect();
+        assert_eq!(res, vec!["b", "c"]);
+        let other = tree.split_off("c");
+        assert_eq!(other.len(), 1);
+        tree.delete("a");
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn validate() {
+        let mut tree = RBTree::new();
+        assert_eq!(tree.validate(), Ok(()));
+        for i in 0..1000 {
+            let key = i * 37 % 1000;
+            tree.insert(key, key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in (0..1000).step_by(3) {
+            tree.delete(&key);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        for key in 0..100 {
+            *tree.entry(key).or_insert(0) += 1;
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        let mut other = tree.split_off(&500);
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(other.validate(), Ok(()));
+        tree.append(&mut other);
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(other.validate(), Ok(()));
+        let tree = RBTree::from_sorted_iter((0..100).map(|key| (key, key)));
+        assert_eq!(tree.validate(), Ok(()));
+
+        let violation = InvariantViolation::LenMismatch { cached: 3, actual: 2 };
+        assert_eq!(violation.to_string(), "键值对个数为3，实际有2个");
+    }
 }
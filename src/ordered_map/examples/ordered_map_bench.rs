@@ -0,0 +1,25 @@
+use an_ok_avl_tree::AVLTree;
+use an_ok_bstree::BSTree;
+use an_unsafe_rb_tree::RBTree;
+use ordered_map::OrderedMap;
+use std::time::Instant;
+
+fn bench<M: OrderedMap<i32, i32>>(name: &str) {
+    let now = Instant::now();
+    let mut tree = M::new();
+    for i in 0..10000 {
+        tree.insert(i, i);
+    }
+    let elapsed_time = now.elapsed();
+    println!(
+        "{} insert 10000 times took {} ms.",
+        name,
+        elapsed_time.as_millis()
+    );
+}
+
+fn main() {
+    bench::<BSTree<i32, i32>>("BSTree");
+    bench::<AVLTree<i32, i32>>("AVL Tree");
+    bench::<RBTree<i32, i32>>("RB Tree");
+}
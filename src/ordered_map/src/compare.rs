@@ -0,0 +1,49 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+/// 键的比较器，作为树的类型参数决定键的顺序。比较器不保存状态，只通过类型选择比较规则，
+/// 因此同一种树的两个实例顺序一定相同，集合运算可以直接归并
+/// # Example
+/// ```
+/// use ordered_map::Compare;
+/// use std::cmp::Ordering;
+///
+/// struct CaseInsensitive;
+///
+/// impl Compare<str> for CaseInsensitive {
+///     fn compare(a: &str, b: &str) -> Ordering {
+///         a.to_lowercase().cmp(&b.to_lowercase())
+///     }
+/// }
+///
+/// assert_eq!(CaseInsensitive::compare("abc", "ABC"), Ordering::Equal);
+/// ```
+pub trait Compare<T: ?Sized> {
+    /// 比较两个键的大小
+    fn compare(a: &T, b: &T) -> Ordering;
+}
+
+/// 按`Ord`比较键，是所有树的默认比较器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Natural;
+
+impl<T: Ord + ?Sized> Compare<T> for Natural {
+    fn compare(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// 将比较器C的顺序反转
+/// # Example
+/// ```
+/// use ordered_map::{Compare, Natural, Reverse};
+/// use std::cmp::Ordering;
+/// assert_eq!(<Reverse<Natural>>::compare(&1, &2), Ordering::Greater);
+/// ```
+pub struct Reverse<C = Natural>(PhantomData<C>);
+
+impl<T: ?Sized, C: Compare<T>> Compare<T> for Reverse<C> {
+    fn compare(a: &T, b: &T) -> Ordering {
+        C::compare(b, a)
+    }
+}
@@ -0,0 +1,33 @@
+/// 转义DOT标签中的反斜杠和双引号，使任意文本都能放进`label="..."`中
+/// # Example
+/// ```
+/// use ordered_map::dot_escape;
+/// assert_eq!(dot_escape(r#"say "hi""#), r#"say \"hi\""#);
+/// ```
+pub fn dot_escape(label: &str) -> String {
+    let mut res = String::with_capacity(label.len());
+    for c in label.chars() {
+        if matches!(c, '\\' | '"') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// 转义record形状节点的标签，除反斜杠和双引号外，`{ } | < >`在record中也有特殊含义
+/// # Example
+/// ```
+/// use ordered_map::dot_escape_record;
+/// assert_eq!(dot_escape_record("{1|2}"), r"\{1\|2\}");
+/// ```
+pub fn dot_escape_record(label: &str) -> String {
+    let mut res = String::with_capacity(label.len());
+    for c in label.chars() {
+        if matches!(c, '\\' | '"' | '{' | '}' | '|' | '<' | '>') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
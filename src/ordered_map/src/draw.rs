@@ -0,0 +1,58 @@
+use std::fmt::{self, Formatter};
+
+/// 把树画成多行文本，每个节点占一行，孩子按顺序画在节点下方并用制表符连接，空的孩子画成Ø。
+/// label写出节点本身，children返回节点的孩子，叶子节点返回空的Vec
+/// # Example
+/// ```
+/// use ordered_map::draw_tree;
+/// use std::fmt::{self, Display, Formatter};
+///
+/// // 用数组表示的完全二叉树，下标i的孩子是2i + 1和2i + 2
+/// struct Heap(Vec<i32>);
+///
+/// impl Display for Heap {
+///     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+///         let len = self.0.len();
+///         draw_tree(f, 0, &|i: &usize, f: &mut Formatter<'_>| write!(f, "{}", self.0[*i]), &|i: &usize| {
+///             if 2 * i + 1 >= len {
+///                 return Vec::new();
+///             }
+///             vec![Some(2 * i + 1), Some(2 * i + 2).filter(|&right| right < len)]
+///         })
+///     }
+/// }
+///
+/// let heap = Heap(vec![1, 2, 3, 4]);
+/// assert_eq!(heap.to_string(), "1\n├── 2\n│   ├── 4\n│   └── Ø\n└── 3");
+/// ```
+pub fn draw_tree<N, L, C>(f: &mut Formatter<'_>, root: N, label: &L, children: &C) -> fmt::Result
+where
+    L: Fn(&N, &mut Formatter<'_>) -> fmt::Result,
+    C: Fn(&N) -> Vec<Option<N>>,
+{
+    label(&root, f)?;
+    draw_children(f, &root, "", label, children)
+}
+
+// 画出node的所有孩子，prefix是node所在行左侧的连接线
+fn draw_children<N, L, C>(f: &mut Formatter<'_>, node: &N, prefix: &str, label: &L, children: &C) -> fmt::Result
+where
+    L: Fn(&N, &mut Formatter<'_>) -> fmt::Result,
+    C: Fn(&N) -> Vec<Option<N>>,
+{
+    let kids = children(node);
+    let count = kids.len();
+    for (i, kid) in kids.into_iter().enumerate() {
+        let last = i + 1 == count;
+        write!(f, "\n{}{}", prefix, if last { "└── " } else { "├── " })?;
+        match kid {
+            None => write!(f, "Ø")?,
+            Some(kid) => {
+                label(&kid, f)?;
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                draw_children(f, &kid, &prefix, label, children)?;
+            }
+        }
+    }
+    Ok(())
+}
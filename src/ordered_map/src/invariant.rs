@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// 树的性质被破坏时`validate`返回的错误，说明第一个被发现的问题
+/// # Example
+/// ```
+/// use ordered_map::InvariantViolation;
+/// let violation = InvariantViolation::BlackHeight { left: 2, right: 3 };
+/// assert_eq!(violation.to_string(), "左右子树的黑高不相等：左边2，右边3");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// 中序遍历得到的键不是递增的
+    KeyOrder,
+    /// 树中记录的键值对个数与实际个数不一致
+    LenMismatch { cached: usize, actual: usize },
+    /// 节点记录的子树节点个数与实际个数不一致
+    StaleSize { cached: usize, actual: usize },
+    /// AVL树节点记录的树高与实际树高不一致
+    StaleHeight { cached: usize, actual: usize },
+    /// AVL树节点左右子树的高度差超过1
+    Unbalanced { left: usize, right: usize },
+    /// 红黑树的根节点是红色
+    RedRoot,
+    /// 红色节点有红色的孩子
+    RedRed,
+    /// 红黑树节点左右子树的黑高不相等
+    BlackHeight { left: usize, right: usize },
+    /// 孩子节点的双亲指针没有指向该节点
+    ParentLink,
+    /// B树非根节点的关键字个数少于t - 1
+    Underfull { keys: usize, min: usize },
+    /// B树节点的关键字个数多于2t - 1
+    Overfull { keys: usize, max: usize },
+    /// B树内部节点的孩子个数不等于关键字个数加1
+    ChildCount { keys: usize, children: usize },
+    /// B树的叶子节点不在同一层
+    LeafDepth { expected: usize, actual: usize },
+    /// B+树的叶子链表与树中叶子的顺序不一致
+    LeafLink,
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            InvariantViolation::KeyOrder => write!(f, "键没有按顺序排列"),
+            InvariantViolation::LenMismatch { cached, actual } => {
+                write!(f, "键值对个数为{}，实际有{}个", cached, actual)
+            }
+            InvariantViolation::StaleSize { cached, actual } => {
+                write!(f, "节点记录的子树大小为{}，实际为{}", cached, actual)
+            }
+            InvariantViolation::StaleHeight { cached, actual } => {
+                write!(f, "节点记录的树高为{}，实际为{}", cached, actual)
+            }
+            InvariantViolation::Unbalanced { left, right } => {
+                write!(f, "左右子树的高度差超过1：左边{}，右边{}", left, right)
+            }
+            InvariantViolation::RedRoot => write!(f, "根节点是红色"),
+            InvariantViolation::RedRed => write!(f, "红色节点有红色的孩子"),
+            InvariantViolation::BlackHeight { left, right } => {
+                write!(f, "左右子树的黑高不相等：左边{}，右边{}", left, right)
+            }
+            InvariantViolation::ParentLink => write!(f, "孩子节点的双亲指针错误"),
+            InvariantViolation::Underfull { keys, min } => {
+                write!(f, "节点只有{}个关键字，至少需要{}个", keys, min)
+            }
+            InvariantViolation::Overfull { keys, max } => {
+                write!(f, "节点有{}个关键字，最多只能有{}个", keys, max)
+            }
+            InvariantViolation::ChildCount { keys, children } => {
+                write!(f, "节点有{}个关键字，却有{}个孩子", keys, children)
+            }
+            InvariantViolation::LeafDepth { expected, actual } => {
+                write!(f, "叶子节点的深度为{}，其他叶子的深度为{}", actual, expected)
+            }
+            InvariantViolation::LeafLink => write!(f, "叶子链表与树中叶子的顺序不一致"),
+        }
+    }
+}
+
+impl Error for InvariantViolation {}
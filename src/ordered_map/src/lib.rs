@@ -0,0 +1,102 @@
+mod compare;
+mod dot;
+mod draw;
+mod invariant;
+mod merge;
+
+pub use compare::{Compare, Natural, Reverse};
+pub use dot::{dot_escape, dot_escape_record};
+pub use draw::draw_tree;
+pub use invariant::InvariantViolation;
+pub use merge::{sort_unique_pairs, Difference, Intersection, SymmetricDifference, Union};
+
+/// 有序映射的公共接口，`BSTree`、`AVLTree`和`RBTree`都实现了该trait，
+/// 泛型代码和性能测试只需针对`OrderedMap`编写一次，即可替换底层的树结构
+/// # Example
+/// ```
+/// use ordered_map::OrderedMap;
+///
+/// fn fill<M: OrderedMap<i32, char>>(map: &mut M) {
+///     map.insert(2, 'b');
+///     map.insert(1, 'a');
+///     map.insert(3, 'c');
+/// }
+///
+/// fn keys<M: OrderedMap<i32, char>>(map: &M) -> Vec<i32> {
+///     map.inorder_iter().map(|(k, _)| *k).collect()
+/// }
+/// ```
+pub trait OrderedMap<K, V> {
+    /// 遍历迭代器，包括前序、后序、层序
+    type TraverseIter<'a>: ExactSizeIterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// 中序迭代器，按键的顺序双向迭代
+    type Iter<'a>: DoubleEndedIterator<Item = (&'a K, &'a V)> + ExactSizeIterator
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// 构建一个空的有序映射
+    fn new() -> Self
+    where
+        Self: Sized;
+
+    /// 插入键值对，如果键已经存在，则替换旧值为新值
+    fn insert(&mut self, key: K, value: V);
+
+    /// 删除键值对，如果找不到键值对，则忽略
+    fn delete(&mut self, key: K);
+
+    /// 返回键值对的个数
+    fn len(&self) -> usize;
+
+    /// 判断是否为空
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 根据键查找对应的值，找不到返回None
+    fn get(&self, key: &K) -> Option<&V>;
+
+    /// 根据键获取相应键值对
+    fn get_pair(&self, key: &K) -> Option<(&K, &V)>;
+
+    /// 据键查找对应的值，找不到返回默认值
+    fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// 查找是否存在键值对
+    fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// 返回最小键值对
+    fn min_pair(&self) -> Option<(&K, &V)>;
+
+    /// 返回最大键值对
+    fn max_pair(&self) -> Option<(&K, &V)>;
+
+    /// 返回第一个大于key的键值对，key可以不存在树中
+    fn successor(&self, key: &K) -> Option<(&K, &V)>;
+
+    /// 返回第一个小于key的键值对，key可以不存在树中
+    fn predecessor(&self, key: &K) -> Option<(&K, &V)>;
+
+    /// 前序遍历迭代器
+    fn preorder_iter(&self) -> Self::TraverseIter<'_>;
+
+    /// 中序遍历迭代器
+    fn inorder_iter(&self) -> Self::Iter<'_>;
+
+    /// 后序遍历迭代器
+    fn postorder_iter(&self) -> Self::TraverseIter<'_>;
+
+    /// 层序遍历迭代器
+    fn levelorder_iter(&self) -> Self::TraverseIter<'_>;
+}
@@ -0,0 +1,208 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+
+/// 收集键值对用于批量构建树：输入已经按cmp严格递增时直接返回，时间复杂度O(n)；
+/// 否则按cmp稳定排序，相等的键只保留最后一个，与依次insert的结果一致
+/// # Example
+/// ```
+/// use ordered_map::{sort_unique_pairs, Compare, Natural};
+/// let pairs = sort_unique_pairs(vec![(3, 'c'), (1, 'a'), (3, 'd'), (2, 'b')], Natural::compare);
+/// assert_eq!(pairs, vec![(1, 'a'), (2, 'b'), (3, 'd')]);
+/// ```
+pub fn sort_unique_pairs<K, V, I: IntoIterator<Item = (K, V)>>(pairs: I, cmp: fn(&K, &K) -> Ordering) -> Vec<(K, V)> {
+    let mut pairs: Vec<(K, V)> = pairs.into_iter().collect();
+    if pairs.windows(2).all(|w| cmp(&w[0].0, &w[1].0) == Ordering::Less) {
+        return pairs;
+    }
+    pairs.sort_by(|a, b| cmp(&a.0, &b.0));
+    let mut unique: Vec<(K, V)> = Vec::with_capacity(pairs.len());
+    for pair in pairs {
+        match unique.last_mut() {
+            Some(last) if cmp(&last.0, &pair.0) == Ordering::Equal => *last = pair,
+            _ => unique.push(pair),
+        }
+    }
+    unique
+}
+
+// 比较两个迭代器的下一个元素，任意一个迭代器结束时返回None
+fn peek_cmp<I: Iterator>(
+    a: &mut Peekable<I>,
+    b: &mut Peekable<I>,
+    cmp: fn(&I::Item, &I::Item) -> Ordering,
+) -> Option<Ordering> {
+    match (a.peek(), b.peek()) {
+        (Some(x), Some(y)) => Some(cmp(x, y)),
+        _ => None,
+    }
+}
+
+/// 两个有序迭代器的并集，两边都有的键只输出第一个迭代器的元素，时间复杂度O(m + n)
+pub struct Union<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: fn(&I::Item, &I::Item) -> Ordering,
+}
+
+impl<I: Iterator> Union<I> {
+    pub fn new(a: I, b: I, cmp: fn(&I::Item, &I::Item) -> Ordering) -> Self {
+        Union {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Union<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match peek_cmp(&mut self.a, &mut self.b, self.cmp) {
+            Some(Ordering::Less) => self.a.next(),
+            Some(Ordering::Greater) => self.b.next(),
+            Some(Ordering::Equal) => {
+                self.b.next();
+                self.a.next()
+            }
+            None => self.a.next().or_else(|| self.b.next()),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_min, a_max) = self.a.size_hint();
+        let (b_min, b_max) = self.b.size_hint();
+        let max = a_max.and_then(|a| b_max.and_then(|b| a.checked_add(b)));
+        (a_min.max(b_min), max)
+    }
+}
+
+/// 两个有序迭代器的交集，输出第一个迭代器的元素，时间复杂度O(m + n)
+pub struct Intersection<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: fn(&I::Item, &I::Item) -> Ordering,
+}
+
+impl<I: Iterator> Intersection<I> {
+    pub fn new(a: I, b: I, cmp: fn(&I::Item, &I::Item) -> Ordering) -> Self {
+        Intersection {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Intersection<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match peek_cmp(&mut self.a, &mut self.b, self.cmp)? {
+                Ordering::Less => {
+                    self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, a_max) = self.a.size_hint();
+        let (_, b_max) = self.b.size_hint();
+        let max = match (a_max, b_max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (0, max)
+    }
+}
+
+/// 两个有序迭代器的差集，输出只在第一个迭代器中出现的元素，时间复杂度O(m + n)
+pub struct Difference<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: fn(&I::Item, &I::Item) -> Ordering,
+}
+
+impl<I: Iterator> Difference<I> {
+    pub fn new(a: I, b: I, cmp: fn(&I::Item, &I::Item) -> Ordering) -> Self {
+        Difference {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Difference<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match peek_cmp(&mut self.a, &mut self.b, self.cmp) {
+                Some(Ordering::Less) | None => return self.a.next(),
+                Some(Ordering::Greater) => {
+                    self.b.next();
+                }
+                Some(Ordering::Equal) => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.a.size_hint().1)
+    }
+}
+
+/// 两个有序迭代器的对称差，输出只在其中一个迭代器中出现的元素，时间复杂度O(m + n)
+pub struct SymmetricDifference<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: fn(&I::Item, &I::Item) -> Ordering,
+}
+
+impl<I: Iterator> SymmetricDifference<I> {
+    pub fn new(a: I, b: I, cmp: fn(&I::Item, &I::Item) -> Ordering) -> Self {
+        SymmetricDifference {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for SymmetricDifference<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match peek_cmp(&mut self.a, &mut self.b, self.cmp) {
+                Some(Ordering::Less) => return self.a.next(),
+                Some(Ordering::Greater) => return self.b.next(),
+                Some(Ordering::Equal) => {
+                    self.a.next();
+                    self.b.next();
+                }
+                None => return self.a.next().or_else(|| self.b.next()),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, a_max) = self.a.size_hint();
+        let (_, b_max) = self.b.size_hint();
+        let max = a_max.and_then(|a| b_max.and_then(|b| a.checked_add(b)));
+        (0, max)
+    }
+}
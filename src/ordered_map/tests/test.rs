@@ -0,0 +1,199 @@
+// 一致性测试：同一组用例对每一种OrderedMap实现都运行一遍
+#[cfg(test)]
+mod tests {
+    use ordered_map::OrderedMap;
+
+    fn build<M: OrderedMap<i32, char>>() -> M {
+        let mut map = M::new();
+        map.insert(8, 'h');
+        map.insert(5, 'e');
+        map.insert(3, 'c');
+        map.insert(2, 'b');
+        map.insert(4, 'd');
+        map.insert(6, 'f');
+        map.insert(7, 'g');
+        map.insert(15, 'o');
+        map.insert(12, 'l');
+        map.insert(17, 'q');
+        map.insert(10, 'j');
+        map.insert(14, 'n');
+        map
+    }
+
+    fn in_order<M: OrderedMap<i32, char>>(map: &M) -> Vec<(i32, char)> {
+        map.inorder_iter().map(|(k, v)| (*k, *v)).collect()
+    }
+
+    fn empty<M: OrderedMap<i32, char>>() {
+        let mut map = M::new();
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.min_pair(), None);
+        assert_eq!(map.max_pair(), None);
+        assert_eq!(map.inorder_iter().next(), None);
+        map.delete(1);
+        assert!(map.is_empty());
+        map.insert(1, 'a');
+        assert!(!map.is_empty());
+        map.delete(1);
+        assert!(map.is_empty());
+    }
+
+    fn insert_replace<M: OrderedMap<i32, char>>() {
+        let mut map = build::<M>();
+        assert_eq!(map.get(&4), Some(&'d'));
+        map.insert(4, 'y');
+        assert_eq!(map.get(&4), Some(&'y'));
+        assert_eq!(map.get_pair(&4), Some((&4, &'y')));
+        assert_eq!(map.inorder_iter().count(), 12);
+        assert_eq!(map.len(), 12);
+    }
+
+    fn delete<M: OrderedMap<i32, char>>() {
+        let mut map = build::<M>();
+        map.delete(12);
+        map.delete(6);
+        map.delete(8);
+        map.delete(100);
+        assert_eq!(map.len(), 9);
+        assert!(!map.contains(&12));
+        assert!(!map.contains(&6));
+        assert!(!map.contains(&8));
+        assert_eq!(
+            in_order(&map),
+            vec![
+                (2, 'b'),
+                (3, 'c'),
+                (4, 'd'),
+                (5, 'e'),
+                (7, 'g'),
+                (10, 'j'),
+                (14, 'n'),
+                (15, 'o'),
+                (17, 'q')
+            ]
+        );
+        for key in [2, 3, 4, 5, 7, 10, 14, 15, 17] {
+            map.delete(key);
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+    }
+
+    fn lookup<M: OrderedMap<i32, char>>() {
+        let map = build::<M>();
+        assert_eq!(map.get(&12), Some(&'l'));
+        assert_eq!(map.get(&11), None);
+        assert_eq!(map.get_pair(&15), Some((&15, &'o')));
+        assert_eq!(map.get_pair(&16), None);
+        assert_eq!(map.get_or(&5, &'z'), &'e');
+        assert_eq!(map.get_or(&11, &'z'), &'z');
+        assert!(map.contains(&10));
+        assert!(!map.contains(&22));
+        assert_eq!(map.min_pair(), Some((&2, &'b')));
+        assert_eq!(map.max_pair(), Some((&17, &'q')));
+    }
+
+    fn successor_predecessor<M: OrderedMap<i32, char>>() {
+        let map = build::<M>();
+        assert_eq!(map.successor(&2), Some((&3, &'c')));
+        assert_eq!(map.successor(&8), Some((&10, &'j')));
+        assert_eq!(map.successor(&14), Some((&15, &'o')));
+        assert_eq!(map.successor(&17), None);
+        assert_eq!(map.predecessor(&2), None);
+        assert_eq!(map.predecessor(&10), Some((&8, &'h')));
+        assert_eq!(map.predecessor(&5), Some((&4, &'d')));
+        assert_eq!(map.predecessor(&17), Some((&15, &'o')));
+        // key不在树中时返回相邻的键值对
+        assert_eq!(map.successor(&9), Some((&10, &'j')));
+        assert_eq!(map.successor(&1), Some((&2, &'b')));
+        assert_eq!(map.successor(&20), None);
+        assert_eq!(map.predecessor(&11), Some((&10, &'j')));
+        assert_eq!(map.predecessor(&20), Some((&17, &'q')));
+        assert_eq!(map.predecessor(&1), None);
+    }
+
+    fn traverse<M: OrderedMap<i32, char>>() {
+        let map = build::<M>();
+        let keys: Vec<i32> = map.inorder_iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![2, 3, 4, 5, 6, 7, 8, 10, 12, 14, 15, 17]);
+        // 不同的树形状不同，但前序、后序、层序遍历都必须恰好访问每个节点一次
+        for iter in [map.preorder_iter(), map.postorder_iter(), map.levelorder_iter()] {
+            let mut keys: Vec<i32> = iter.map(|(k, _)| *k).collect();
+            keys.sort();
+            assert_eq!(keys, vec![2, 3, 4, 5, 6, 7, 8, 10, 12, 14, 15, 17]);
+        }
+        let root = map.preorder_iter().next();
+        assert_eq!(root, map.levelorder_iter().next());
+        assert_eq!(root, map.postorder_iter().last());
+    }
+
+    fn double_ended<M: OrderedMap<i32, char>>() {
+        let map = build::<M>();
+        let keys: Vec<i32> = map.inorder_iter().rev().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![17, 15, 14, 12, 10, 8, 7, 6, 5, 4, 3, 2]);
+        // 两端交替迭代，相遇后停止，每个键恰好输出一次
+        let mut iter = map.inorder_iter();
+        let mut keys = Vec::new();
+        assert_eq!(iter.len(), 12);
+        while let Some((front, _)) = iter.next() {
+            keys.push(*front);
+            if let Some((back, _)) = iter.next_back() {
+                keys.push(*back);
+            }
+        }
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next_back(), None);
+        keys.sort();
+        assert_eq!(keys, vec![2, 3, 4, 5, 6, 7, 8, 10, 12, 14, 15, 17]);
+        assert_eq!(map.preorder_iter().len(), map.len());
+        assert_eq!(map.postorder_iter().skip(5).len(), 7);
+        assert_eq!(map.levelorder_iter().len(), map.len());
+    }
+
+    macro_rules! conformance {
+        ($name:ident, $map:ty) => {
+            mod $name {
+                #[test]
+                fn empty() {
+                    super::empty::<$map>();
+                }
+
+                #[test]
+                fn insert_replace() {
+                    super::insert_replace::<$map>();
+                }
+
+                #[test]
+                fn delete() {
+                    super::delete::<$map>();
+                }
+
+                #[test]
+                fn lookup() {
+                    super::lookup::<$map>();
+                }
+
+                #[test]
+                fn successor_predecessor() {
+                    super::successor_predecessor::<$map>();
+                }
+
+                #[test]
+                fn traverse() {
+                    super::traverse::<$map>();
+                }
+
+                #[test]
+                fn double_ended() {
+                    super::double_ended::<$map>();
+                }
+            }
+        };
+    }
+
+    conformance!(bstree, an_ok_bstree::BSTree<i32, char>);
+    conformance!(avl_tree, an_ok_avl_tree::AVLTree<i32, char>);
+    conformance!(rb_tree, an_unsafe_rb_tree::RBTree<i32, char>);
+}
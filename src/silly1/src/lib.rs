@@ -1,3 +1,6 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter;
+
 
 pub struct Stack<T> {
     head: Link<T>,
@@ -56,6 +59,12 @@ impl<T> Stack<T> {
     }
 }
 
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for Stack<T> {
     fn drop(&mut self) {
         let mut cur_link = self.head.take();
@@ -122,6 +131,73 @@ impl<T> List<T> {
     }
 }
 
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stack<T> {
+    // 从栈顶开始依次返回每个元素，用于打印
+    fn elems(&self) -> impl Iterator<Item = &T> {
+        let mut cur = self.head.as_deref();
+        iter::from_fn(move || {
+            cur.map(|node| {
+                cur = node.next.as_deref();
+                &node.elem
+            })
+        })
+    }
+}
+
+impl<T: Display> Display for Stack<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut elems = self.elems();
+        match elems.next() {
+            None => write!(f, "Ø"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                for elem in elems {
+                    write!(f, " -> {}", elem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Debug> Debug for Stack<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.elems()).finish()
+    }
+}
+
+// 左边的栈顶和右边的栈顶分别位于|的两侧，箭头指向远离|的方向
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let left: Vec<&T> = self.left.elems().collect();
+        match left.split_last() {
+            None => write!(f, "Ø")?,
+            Some((last, rest)) => {
+                write!(f, "{}", last)?;
+                for elem in rest.iter().rev() {
+                    write!(f, " <- {}", elem)?;
+                }
+            }
+        }
+        write!(f, " | {}", self.right)
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("List")
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{Stack, List};
@@ -177,9 +253,9 @@ mod test {
         assert_eq!(stack.peek(), Some(&String::from("hello")));
         assert_eq!(stack.peek_mut(), Some(&mut String::from("hello")));
 
-        stack.peek_mut().map(|value| {
+        if let Some(value) = stack.peek_mut() {
             value.push_str(" world");
-        });
+        }
 
         assert_eq!(stack.peek(), Some(&String::from("hello world")));
         assert_eq!(stack.pop(), Some(String::from("hello world")));
@@ -214,4 +290,25 @@ mod test {
         assert_eq!(list.pop_left(), None);
 
     }
+
+    #[test]
+    fn display() {
+        let mut list = List::new();
+        assert_eq!(list.to_string(), "Ø | Ø");
+        list.push_left(1);
+        list.push_left(2);
+        list.push_right(4);
+        list.push_right(3);
+        assert_eq!(list.to_string(), "1 <- 2 | 3 -> 4");
+        assert_eq!(format!("{:?}", list), "List { left: [2, 1], right: [3, 4] }");
+        list.go_left();
+        assert_eq!(list.to_string(), "1 | 2 -> 3 -> 4");
+
+        let mut stack = Stack::new();
+        assert_eq!(stack.to_string(), "Ø");
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(stack.to_string(), "2 -> 1");
+        assert_eq!(format!("{:?}", stack), "[2, 1]");
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
 pub struct List<'a, T> {
     pub data: T,
     pub prev: Option<&'a List<'a, T>>,
@@ -25,12 +27,37 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.prev.as_deref();
+            self.next = node.prev;
             &node.data
         })
     }
 }
 
+impl<'a, T: Display> Display for List<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.data)?;
+        let mut cur = self.prev;
+        while let Some(node) = cur {
+            write!(f, " -> {}", node.data)?;
+            cur = node.prev;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Debug> Debug for List<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entry(&self.data);
+        let mut cur = self.prev;
+        while let Some(node) = cur {
+            list.entry(&node.data);
+            cur = node.prev;
+        }
+        list.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::List;
@@ -80,4 +107,17 @@ mod tests {
             })
         })
     }
+
+    #[test]
+    fn display() {
+        List::push(None, 1, |list| {
+            assert_eq!(list.to_string(), "1");
+            List::push(Some(list), 2, |list| {
+                List::push(Some(list), 3, |list| {
+                    assert_eq!(list.to_string(), "3 -> 2 -> 1");
+                    assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+                })
+            })
+        })
+    }
 }